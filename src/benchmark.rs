@@ -0,0 +1,280 @@
+use std::{
+    collections::HashSet,
+    fmt::Debug,
+    fs::File,
+    hash::BuildHasher,
+    io::{self, BufReader, Write},
+    path::Path,
+    time::Instant,
+};
+
+use csv::WriterBuilder;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    compute_treewidth_upper_bound, graph_readers::read_dimacs, CliqueSource,
+    SpanningTreeConstructionMethod,
+};
+
+/// The result of running a heuristic on a graph in a benchmark.
+///
+/// graph is the name of the graph (e.g. the file it was read from)
+///
+/// heuristic is the name of the heuristic, for example the spanning tree construction method and
+/// edge weight function used
+///
+/// width is the width of the computed tree decomposition
+///
+/// millis is the running time of the heuristic in milliseconds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchmarkResult {
+    pub graph: String,
+    pub heuristic: String,
+    pub width: usize,
+    pub millis: u128,
+}
+
+/// Writes the benchmark results as CSV with the header graph,heuristic,width,millis and one row per
+/// result to the writer.
+pub fn write_benchmark_csv<W: Write>(
+    results: &[BenchmarkResult],
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut csv_writer = WriterBuilder::new().flexible(false).from_writer(writer);
+
+    csv_writer.write_record(["graph", "heuristic", "width", "millis"])?;
+    for result in results {
+        csv_writer.write_record([
+            result.graph.as_str(),
+            result.heuristic.as_str(),
+            &result.width.to_string(),
+            &result.millis.to_string(),
+        ])?;
+    }
+
+    csv_writer.flush()
+}
+
+/// Computes an upper bound for the treewidth of each of the given graphs with
+/// [compute_treewidth_upper_bound] using the maximal cliques and returns the widths in the order of
+/// the given graphs.
+///
+/// The graphs are handled one after another, see [run_benchmarks] for distributing them among
+/// several threads with the `rayon` feature.
+pub fn treewidth_batch<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graphs: &[Graph<N, E, Undirected>],
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> Vec<usize> {
+    graphs
+        .iter()
+        .map(|graph| {
+            compute_treewidth_upper_bound(
+                graph,
+                edge_weight_function,
+                treewidth_computation_method,
+                false,
+                CliqueSource::Maximal,
+                None,
+            )
+        })
+        .collect()
+}
+
+/// A heuristic to run in a benchmark, that is, a spanning tree construction method together with
+/// an edge weight function for the clique graph (see [compute_treewidth_upper_bound]). The name is
+/// used as the heuristic of the resulting [BenchmarkResult]s.
+#[derive(Clone, Debug)]
+pub struct BenchmarkHeuristic<O, S> {
+    pub name: String,
+    pub treewidth_computation_method: SpanningTreeConstructionMethod,
+    pub edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+}
+
+/// Runs each of the heuristics on each of the given named graphs and returns the results per graph
+/// in the order of the given graphs.
+///
+/// With the `rayon` feature, the graphs are independent of each other and are thus distributed
+/// among a thread pool of jobs threads (at least one). Without it, the graphs are handled one after
+/// another and jobs is ignored. Heuristics that log to files (like
+/// [FilWhILogBagSize][SpanningTreeConstructionMethod::FilWhILogBagSize]) serialize their writes, so
+/// they can be used in either case.
+pub fn run_benchmarks<
+    N: Clone + Sync,
+    E: Clone + Sync,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graphs: &[(String, Graph<N, E, Undirected>)],
+    heuristics: &[BenchmarkHeuristic<O, S>],
+    jobs: usize,
+) -> Vec<(String, Vec<BenchmarkResult>)> {
+    let benchmark_graph = |(name, graph): &(String, Graph<N, E, Undirected>)| {
+        let graph_results: Vec<BenchmarkResult> = heuristics
+            .iter()
+            .map(|heuristic| {
+                let start = Instant::now();
+                let width = compute_treewidth_upper_bound::<_, _, _, S>(
+                    graph,
+                    heuristic.edge_weight_function,
+                    heuristic.treewidth_computation_method,
+                    false,
+                    CliqueSource::Maximal,
+                    None,
+                );
+                BenchmarkResult {
+                    graph: name.clone(),
+                    heuristic: heuristic.name.clone(),
+                    width,
+                    millis: start.elapsed().as_millis(),
+                }
+            })
+            .collect();
+        (name.clone(), graph_results)
+    };
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.max(1))
+            .build()
+            .expect("Thread pool should be buildable")
+            .install(|| graphs.par_iter().map(benchmark_graph).collect())
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        let _ = jobs;
+        graphs.iter().map(benchmark_graph).collect()
+    }
+}
+
+/// Reads all graphs in the DIMACS format (files ending in .col, see
+/// [read_dimacs][crate::read_dimacs]) from the given directory and runs the heuristics on them
+/// using [run_benchmarks]. The graphs are named by their file names and sorted by them.
+pub fn run_benchmarks_over_dir<
+    P: AsRef<Path>,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    dir: P,
+    heuristics: &[BenchmarkHeuristic<O, S>],
+    jobs: usize,
+) -> io::Result<Vec<(String, Vec<BenchmarkResult>)>> {
+    let mut graphs = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "col") {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let graph = read_dimacs(BufReader::new(File::open(&path)?))?;
+            graphs.push((name, graph));
+        }
+    }
+    graphs.sort_by(|(first_name, _), (second_name, _)| first_name.cmp(second_name));
+
+    Ok(run_benchmarks(&graphs, heuristics, jobs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_benchmark_csv() {
+        let results = [
+            BenchmarkResult {
+                graph: "test graph 0".to_string(),
+                heuristic: "FilWh, negative_intersection".to_string(),
+                width: 3,
+                millis: 12,
+            },
+            BenchmarkResult {
+                graph: "test graph 1".to_string(),
+                heuristic: "MSTre".to_string(),
+                width: 4,
+                millis: 7,
+            },
+        ];
+
+        let mut output = Vec::new();
+        write_benchmark_csv(&results, &mut output).expect("Writing to a Vec should be possible");
+        let output = String::from_utf8(output).expect("CSV should be valid UTF-8");
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "graph,heuristic,width,millis");
+        // Fields containing the delimiter are quoted
+        assert_eq!(
+            lines[1],
+            "test graph 0,\"FilWh, negative_intersection\",3,12"
+        );
+    }
+
+    #[test]
+    fn test_run_benchmarks_in_parallel() {
+        let graphs: Vec<(String, Graph<i32, i32, Undirected>)> = (0..2)
+            .map(|i| {
+                (
+                    format!("test graph {}", i),
+                    crate::tests::setup_test_graph(i + 1).graph,
+                )
+            })
+            .collect();
+        let heuristics = [
+            BenchmarkHeuristic::<_, std::hash::RandomState> {
+                name: "FilWh".to_string(),
+                treewidth_computation_method: SpanningTreeConstructionMethod::FilWh,
+                edge_weight_function: crate::negative_intersection,
+            },
+            BenchmarkHeuristic {
+                name: "MSTre".to_string(),
+                treewidth_computation_method: SpanningTreeConstructionMethod::MSTre,
+                edge_weight_function: crate::negative_intersection,
+            },
+        ];
+
+        let results = run_benchmarks(&graphs, &heuristics, 2);
+
+        assert_eq!(results.len(), 2);
+        for ((name, graph_results), (graph_name, _)) in results.iter().zip(&graphs) {
+            assert_eq!(name, graph_name);
+            assert_eq!(graph_results.len(), heuristics.len());
+            assert!(graph_results.iter().all(|result| result.width >= 3));
+        }
+    }
+
+    #[test]
+    fn test_treewidth_batch_on_test_graphs() {
+        let graphs: Vec<_> = (0..3)
+            .map(|i| crate::tests::setup_test_graph(i).graph)
+            .collect();
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let widths = treewidth_batch::<_, _, _, std::hash::RandomState>(
+                &graphs,
+                computation_method,
+                crate::negative_intersection,
+            );
+
+            assert_eq!(widths.len(), 3);
+            for (i, width) in widths.into_iter().enumerate() {
+                let test_graph = crate::tests::setup_test_graph(i);
+                assert!(width >= test_graph.treewidth, "Test graph: {}", i);
+                // Test graph 1 is the only one that isn't chordal
+                if i != 1 {
+                    assert_eq!(width, test_graph.treewidth, "Test graph: {}", i);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use petgraph::graph::NodeIndex;
+
+/// A bag of vertices with an index less than 128 represented as a bitset in a u128, where bit i
+/// is set iff the vertex with NodeIndex i is contained in the bag.
+///
+/// For graphs with at most [BitsetBag::CAPACITY] vertices this turns intersection, union and
+/// difference of bags into single instructions. [construct_clique_graph][crate::construct_clique_graph::construct_clique_graph]
+/// uses it to check which cliques intersect and the edge weight functions (e.g.
+/// [negative_intersection][crate::negative_intersection]) compute on it if the graph is small enough.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitsetBag(u128);
+
+impl BitsetBag {
+    /// The maximum number of vertices a graph can have for its bags to be represented as bitsets.
+    pub const CAPACITY: usize = 128;
+
+    /// Returns the bitset representation of the given vertices or None if one of them has an index
+    /// of at least [BitsetBag::CAPACITY].
+    pub fn try_from_vertices<'a>(
+        vertices: impl IntoIterator<Item = &'a NodeIndex>,
+    ) -> Option<Self> {
+        let mut bag = BitsetBag::default();
+        for vertex in vertices {
+            if vertex.index() >= Self::CAPACITY {
+                return None;
+            }
+            bag.insert(*vertex);
+        }
+        Some(bag)
+    }
+
+    /// Inserts the vertex into the bag.
+    ///
+    /// **Panics**
+    /// Panics if the index of the vertex is at least [BitsetBag::CAPACITY].
+    pub fn insert(&mut self, vertex: NodeIndex) {
+        assert!(
+            vertex.index() < Self::CAPACITY,
+            "Vertex {:?} doesn't fit into a bitset bag",
+            vertex
+        );
+        self.0 |= 1 << vertex.index();
+    }
+
+    /// Returns true if the bag contains the vertex.
+    pub fn contains(&self, vertex: NodeIndex) -> bool {
+        vertex.index() < Self::CAPACITY && self.0 & (1 << vertex.index()) != 0
+    }
+
+    /// Returns the number of vertices in the bag.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns true if the bag contains no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the bag containing the vertices contained in both bags.
+    pub fn intersection(&self, other: &BitsetBag) -> BitsetBag {
+        BitsetBag(self.0 & other.0)
+    }
+
+    /// Returns the bag containing the vertices contained in one of the bags.
+    pub fn union(&self, other: &BitsetBag) -> BitsetBag {
+        BitsetBag(self.0 | other.0)
+    }
+
+    /// Returns the bag containing the vertices contained in this bag but not in the other.
+    pub fn difference(&self, other: &BitsetBag) -> BitsetBag {
+        BitsetBag(self.0 & !other.0)
+    }
+
+    /// Returns the bag containing the vertices contained in exactly one of the bags.
+    pub fn symmetric_difference(&self, other: &BitsetBag) -> BitsetBag {
+        BitsetBag(self.0 ^ other.0)
+    }
+
+    /// Returns true if the bags have at least one vertex in common.
+    pub fn intersects(&self, other: &BitsetBag) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns an iterator over the vertices in the bag in ascending order of their indices.
+    pub fn iter(&self) -> impl Iterator<Item = NodeIndex> {
+        let mut remaining = self.0;
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                None
+            } else {
+                let index = remaining.trailing_zeros() as usize;
+                // Clear lowest set bit
+                remaining &= remaining - 1;
+                Some(NodeIndex::new(index))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset_bag_operations() {
+        let first_bag = BitsetBag::try_from_vertices(&[0, 1, 2, 127].map(NodeIndex::new))
+            .expect("Indices should be less than 128");
+        let second_bag = BitsetBag::try_from_vertices(&[2, 3, 127].map(NodeIndex::new))
+            .expect("Indices should be less than 128");
+
+        assert_eq!(first_bag.len(), 4);
+        assert!(first_bag.contains(NodeIndex::new(127)));
+        assert!(!first_bag.contains(NodeIndex::new(200)));
+        assert!(first_bag.intersects(&second_bag));
+        assert_eq!(
+            first_bag
+                .intersection(&second_bag)
+                .iter()
+                .collect::<Vec<_>>(),
+            [2, 127].map(NodeIndex::new)
+        );
+        assert_eq!(first_bag.union(&second_bag).len(), 5);
+        assert_eq!(
+            first_bag.difference(&second_bag).iter().collect::<Vec<_>>(),
+            [0, 1].map(NodeIndex::new)
+        );
+        assert_eq!(first_bag.symmetric_difference(&second_bag).len(), 3);
+        assert!(BitsetBag::try_from_vertices(&[NodeIndex::new(128)]).is_none());
+    }
+}
@@ -35,23 +35,9 @@ pub fn check_tree_decomposition<N, E, O, S: BuildHasher + Default>(
     }
     // Check if (2) from tree decomposition is satisfied (for all edges in starting graph there is bag containing
     // both its vertices)
-    for edge_reference in starting_graph.edge_references() {
-        let (vertex_one, vertex_two) = (edge_reference.source(), edge_reference.target());
-        let mut edge_as_set: HashSet<_, S> = Default::default();
-        edge_as_set.insert(vertex_one);
-        edge_as_set.insert(vertex_two);
-        let mut edge_is_contained = false;
-
-        for vertex_weight in tree_decomposition_graph.node_weights() {
-            if vertex_weight.is_superset(&edge_as_set) {
-                edge_is_contained = true;
-            }
-        }
-
-        if !edge_is_contained {
-            println!("Tree decomposition doesn't contain edge: {:?}", edge_as_set);
-            return false;
-        }
+    if let Some(edge) = find_uncovered_edge(starting_graph, tree_decomposition_graph) {
+        println!("Tree decomposition doesn't contain edge: {:?}", edge);
+        return false;
     }
     // check if (3) from tree decomposition definition is satisfied (for one vertex in starting graph, all bags
     // contain this vertex induce a subtree)
@@ -144,3 +130,370 @@ pub fn check_tree_decomposition<N, E, O, S: BuildHasher + Default>(
     }
     true
 }
+
+/// Returns true if for each edge of the original graph there is a bag of the decomposition
+/// containing both its endpoints, which is property (2) of a tree decomposition.
+///
+/// Instead of checking each bag for each edge, this first builds the sets of bags containing each
+/// vertex and then checks whether the sets of the endpoints of each edge intersect. Thus it takes
+/// O(E·b) time where b is the average number of bags containing a vertex.
+pub fn edges_covered<N, E, O, S: BuildHasher>(
+    original: &Graph<N, E, Undirected>,
+    decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> bool {
+    find_uncovered_edge(original, decomposition).is_none()
+}
+
+/// Returns the endpoints of an edge of the original graph that no bag of the decomposition
+/// contains, see [edges_covered].
+fn find_uncovered_edge<N, E, O, S: BuildHasher>(
+    original: &Graph<N, E, Undirected>,
+    decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Option<(NodeIndex, NodeIndex)> {
+    let bags_containing_vertex = bags_containing_vertex(decomposition);
+
+    original
+        .edge_references()
+        .map(|edge_reference| (edge_reference.source(), edge_reference.target()))
+        .find(|(vertex_one, vertex_two)| {
+            match (
+                bags_containing_vertex.get(vertex_one),
+                bags_containing_vertex.get(vertex_two),
+            ) {
+                (Some(first_bags), Some(second_bags)) => first_bags.is_disjoint(second_bags),
+                _ => true,
+            }
+        })
+}
+
+/// Assigns each edge of the original graph to a bag of the decomposition containing both its
+/// endpoints, preferring the smallest such bag (and among those the one with the smallest
+/// NodeIndex). This is the usual assignment of factors over two vertices to bags for dynamic
+/// programming over tree decompositions.
+///
+/// The keys are the endpoints of the edges with the smaller NodeIndex first. Edges that no bag
+/// covers (see [edges_covered]) get no entry.
+pub fn assign_edges_to_bags<N, E, O, S: BuildHasher>(
+    original: &Graph<N, E, Undirected>,
+    decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> HashMap<(NodeIndex, NodeIndex), NodeIndex> {
+    let bags_containing_vertex = bags_containing_vertex(decomposition);
+    let mut assignment: HashMap<(NodeIndex, NodeIndex), NodeIndex> = HashMap::new();
+
+    for edge_reference in original.edge_references() {
+        let (vertex_one, vertex_two) = (edge_reference.source(), edge_reference.target());
+        if let (Some(first_bags), Some(second_bags)) = (
+            bags_containing_vertex.get(&vertex_one),
+            bags_containing_vertex.get(&vertex_two),
+        ) {
+            if let Some(smallest_bag) = first_bags
+                .intersection(second_bags)
+                .min_by_key(|bag| (decomposition[**bag].len(), **bag))
+            {
+                assignment.insert(
+                    (vertex_one.min(vertex_two), vertex_one.max(vertex_two)),
+                    *smallest_bag,
+                );
+            }
+        }
+    }
+
+    assignment
+}
+
+/// Returns the map from the vertices of the original graph to the bags of the decomposition
+/// containing them.
+fn bags_containing_vertex<O, S: BuildHasher>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> HashMap<NodeIndex, HashSet<NodeIndex>> {
+    let mut bags_containing_vertex: HashMap<NodeIndex, HashSet<NodeIndex>> = HashMap::new();
+    for bag in decomposition.node_indices() {
+        for vertex in decomposition[bag].iter() {
+            bags_containing_vertex
+                .entry(*vertex)
+                .or_default()
+                .insert(bag);
+        }
+    }
+
+    bags_containing_vertex
+}
+
+/// The reasons for a tree decomposition to be invalid, see [validate_tree_decomposition].
+///
+/// MissingVertex A vertex of the graph is not contained in any bag
+///
+/// MissingEdge No bag contains both endpoints of an edge of the graph
+///
+/// NotATree The decomposition graph is not a tree
+///
+/// DisconnectedBags The bags containing the vertex don't induce a subtree. The first and second bag
+/// both contain the vertex but the path between them leaves the bags containing the vertex
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TreeDecompositionFault {
+    MissingVertex {
+        vertex: NodeIndex,
+    },
+    MissingEdge {
+        vertex_one: NodeIndex,
+        vertex_two: NodeIndex,
+    },
+    NotATree,
+    DisconnectedBags {
+        vertex: NodeIndex,
+        first_bag: NodeIndex,
+        second_bag: NodeIndex,
+    },
+}
+
+impl std::fmt::Display for TreeDecompositionFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeDecompositionFault::MissingVertex { vertex } => {
+                write!(f, "Tree decomposition doesn't contain vertex: {:?}", vertex)
+            }
+            TreeDecompositionFault::MissingEdge {
+                vertex_one,
+                vertex_two,
+            } => write!(
+                f,
+                "Tree decomposition doesn't contain edge: ({:?}, {:?})",
+                vertex_one, vertex_two
+            ),
+            TreeDecompositionFault::NotATree => write!(f, "Tree decomposition is not a tree"),
+            TreeDecompositionFault::DisconnectedBags {
+                vertex,
+                first_bag,
+                second_bag,
+            } => write!(
+                f,
+                "The bags {:?} and {:?} contain vertex {:?} but the bags along their path don't",
+                first_bag, second_bag, vertex
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TreeDecompositionFault {}
+
+/// Checks if the given tree decomposition is a valid tree decomposition of the starting graph.
+/// Returns the first fault found otherwise, see [TreeDecompositionFault].
+///
+/// In contrast to [check_tree_decomposition] this doesn't print or panic and checks the subtree
+/// property once per vertex instead of once per pair of bags.
+pub fn validate_tree_decomposition<N, E, O, S: BuildHasher>(
+    starting_graph: &Graph<N, E, Undirected>,
+    tree_decomposition_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Result<(), TreeDecompositionFault> {
+    // Map from the vertices of the starting graph to the bags containing them
+    let mut bags_containing_vertex: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+    for bag in tree_decomposition_graph.node_indices() {
+        for vertex in tree_decomposition_graph[bag].iter() {
+            bags_containing_vertex.entry(*vertex).or_default().push(bag);
+        }
+    }
+
+    // (1) All vertices are contained in some bag
+    if let Some(vertex) = starting_graph
+        .node_indices()
+        .find(|v| !bags_containing_vertex.contains_key(v))
+    {
+        return Err(TreeDecompositionFault::MissingVertex { vertex });
+    }
+
+    // (2) Both endpoints of each edge are contained in some bag
+    for edge_reference in starting_graph.edge_references() {
+        let (vertex_one, vertex_two) = (edge_reference.source(), edge_reference.target());
+        if !bags_containing_vertex[&vertex_one]
+            .iter()
+            .any(|bag| tree_decomposition_graph[*bag].contains(&vertex_two))
+        {
+            return Err(TreeDecompositionFault::MissingEdge {
+                vertex_one,
+                vertex_two,
+            });
+        }
+    }
+
+    // The decomposition is a tree
+    let bag_count = tree_decomposition_graph.node_count();
+    if bag_count > 0
+        && (tree_decomposition_graph.edge_count() != bag_count - 1
+            || petgraph::algo::connected_components(tree_decomposition_graph) != 1)
+    {
+        return Err(TreeDecompositionFault::NotATree);
+    }
+
+    // (3) The bags containing a vertex induce a subtree
+    let mut vertices: Vec<_> = bags_containing_vertex.keys().copied().collect();
+    vertices.sort();
+    for vertex in vertices {
+        let bags = &bags_containing_vertex[&vertex];
+        let first_bag = bags[0];
+        let mut seen: HashSet<NodeIndex> = HashSet::new();
+        seen.insert(first_bag);
+        let mut stack = vec![first_bag];
+        while let Some(current_bag) = stack.pop() {
+            for next_bag in tree_decomposition_graph.neighbors(current_bag) {
+                if tree_decomposition_graph[next_bag].contains(&vertex) && seen.insert(next_bag) {
+                    stack.push(next_bag);
+                }
+            }
+        }
+
+        if let Some(second_bag) = bags.iter().find(|bag| !seen.contains(bag)) {
+            return Err(TreeDecompositionFault::DisconnectedBags {
+                vertex,
+                first_bag,
+                second_bag: *second_bag,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that every vertex in every bag of the given decomposition is a vertex of the original
+/// graph. Returns the first vertex (the smallest one of the first bag containing such a vertex)
+/// that is not a vertex of the original graph otherwise.
+///
+/// This is a precondition for checking the properties of a tree decomposition of an externally
+/// supplied decomposition.
+pub fn bags_reference_valid_vertices<N, E, O, S: BuildHasher>(
+    original: &Graph<N, E, Undirected>,
+    decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Result<(), NodeIndex> {
+    for bag in decomposition.node_weights() {
+        if let Some(invalid_vertex) = bag
+            .iter()
+            .filter(|vertex| vertex.index() >= original.node_count())
+            .min()
+        {
+            return Err(*invalid_vertex);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    #[test]
+    fn test_edges_covered_agrees_with_naive_check() {
+        let test_graph = crate::tests::setup_test_graph(0);
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        for clique in test_graph.expected_max_cliques.iter() {
+            decomposition.add_node(clique.iter().copied().collect());
+        }
+        let naive_edges_covered =
+            |decomposition: &Graph<HashSet<NodeIndex, RandomState>, i32, Undirected>| {
+                test_graph.graph.edge_references().all(|edge_reference| {
+                    decomposition.node_weights().any(|bag| {
+                        bag.contains(&edge_reference.source())
+                            && bag.contains(&edge_reference.target())
+                    })
+                })
+            };
+
+        assert!(edges_covered(&test_graph.graph, &decomposition));
+        // Removing a vertex from a bag may leave some edges uncovered
+        for bag in decomposition.node_indices() {
+            for vertex in decomposition[bag].clone() {
+                let mut broken_decomposition = decomposition.clone();
+                broken_decomposition[bag].remove(&vertex);
+
+                assert_eq!(
+                    edges_covered(&test_graph.graph, &broken_decomposition),
+                    naive_edges_covered(&broken_decomposition),
+                    "Bag: {:?}, vertex: {:?}",
+                    bag,
+                    vertex
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_assign_edges_to_bags() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let decomposition = crate::full_treewidth_result::<_, _, _, RandomState>(
+                &test_graph.graph,
+                crate::SpanningTreeConstructionMethod::FilWh,
+                crate::negative_intersection,
+            )
+            .decomposition;
+
+            let assignment = assign_edges_to_bags(&test_graph.graph, &decomposition);
+
+            assert_eq!(assignment.len(), test_graph.graph.edge_count());
+            for edge_reference in test_graph.graph.edge_references() {
+                let (vertex_one, vertex_two) = (edge_reference.source(), edge_reference.target());
+                let bag = assignment[&(vertex_one.min(vertex_two), vertex_one.max(vertex_two))];
+
+                assert!(decomposition[bag].contains(&vertex_one));
+                assert!(decomposition[bag].contains(&vertex_two));
+                assert!(decomposition.node_weights().all(|other_bag| {
+                    !(other_bag.contains(&vertex_one) && other_bag.contains(&vertex_two))
+                        || other_bag.len() >= decomposition[bag].len()
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_tree_decomposition() {
+        let graph = petgraph::graph::UnGraph::<i32, ()>::from_edges([(0, 1), (1, 2)]);
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let first_bag = decomposition.add_node([0, 1].into_iter().map(NodeIndex::new).collect());
+        let second_bag = decomposition.add_node([2].into_iter().map(NodeIndex::new).collect());
+        let third_bag = decomposition.add_node([1, 2].into_iter().map(NodeIndex::new).collect());
+        decomposition.add_edge(first_bag, second_bag, 0);
+        decomposition.add_edge(second_bag, third_bag, 0);
+
+        assert_eq!(
+            validate_tree_decomposition(&graph, &decomposition),
+            Err(TreeDecompositionFault::DisconnectedBags {
+                vertex: NodeIndex::new(1),
+                first_bag,
+                second_bag: third_bag,
+            })
+        );
+
+        decomposition[second_bag].insert(NodeIndex::new(1));
+        assert_eq!(validate_tree_decomposition(&graph, &decomposition), Ok(()));
+
+        decomposition.add_edge(first_bag, third_bag, 0);
+        assert_eq!(
+            validate_tree_decomposition(&graph, &decomposition),
+            Err(TreeDecompositionFault::NotATree)
+        );
+    }
+
+    #[test]
+    fn test_bags_reference_valid_vertices() {
+        let test_graph = crate::tests::setup_test_graph(2);
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let first_bag = decomposition.add_node([0, 1, 2, 3].map(NodeIndex::new).into());
+        let second_bag = decomposition.add_node([1, 2, 3, 4].map(NodeIndex::new).into());
+        decomposition.add_edge(first_bag, second_bag, 0);
+
+        assert_eq!(
+            bags_reference_valid_vertices(&test_graph.graph, &decomposition),
+            Ok(())
+        );
+
+        decomposition[second_bag].insert(NodeIndex::new(17));
+        assert_eq!(
+            bags_reference_valid_vertices(&test_graph.graph, &decomposition),
+            Err(NodeIndex::new(17))
+        );
+    }
+}
@@ -2,6 +2,21 @@ use petgraph::graph::NodeIndex;
 use rand::Rng;
 use std::{collections::HashSet, hash::BuildHasher};
 
+use crate::bitset_bag::BitsetBag;
+
+/// Returns the bags as [BitsetBag]s if all of their vertices fit into one, which is always the case
+/// for graphs with at most [BitsetBag::CAPACITY] vertices. The edge weight functions use this to
+/// compute on bitsets instead of HashSets for small graphs.
+fn as_bitset_bags<S>(
+    first_vertex: &HashSet<NodeIndex, S>,
+    second_vertex: &HashSet<NodeIndex, S>,
+) -> Option<(BitsetBag, BitsetBag)> {
+    Some((
+        BitsetBag::try_from_vertices(first_vertex)?,
+        BitsetBag::try_from_vertices(second_vertex)?,
+    ))
+}
+
 /// Returns 0.
 pub fn constant<S>(_: &HashSet<NodeIndex, S>, _: &HashSet<NodeIndex, S>) -> i32 {
     0
@@ -14,25 +29,47 @@ pub fn random<S>(_: &HashSet<NodeIndex, S>, _: &HashSet<NodeIndex, S>) -> i32 {
 }
 
 /// Returns the negative of the cardinality of the intersection.
+///
+/// Uses [negative_intersection_bitset] if the bags fit into [BitsetBag]s.
 pub fn negative_intersection<S: BuildHasher + Default>(
     first_vertex: &HashSet<NodeIndex, S>,
     second_vertex: &HashSet<NodeIndex, S>,
 ) -> i32 {
-    -(first_vertex
-        .intersection(second_vertex)
-        .collect::<HashSet<_, S>>()
-        .len() as i32)
+    match as_bitset_bags(first_vertex, second_vertex) {
+        Some((first_bag, second_bag)) => negative_intersection_bitset(&first_bag, &second_bag),
+        None => {
+            -(first_vertex
+                .intersection(second_vertex)
+                .collect::<HashSet<_, S>>()
+                .len() as i32)
+        }
+    }
+}
+
+/// Same as [negative_intersection] for bags represented as [BitsetBag]s.
+pub fn negative_intersection_bitset(first_vertex: &BitsetBag, second_vertex: &BitsetBag) -> i32 {
+    -(first_vertex.intersection(second_vertex).len() as i32)
 }
 
 /// Returns the cardinality of the intersection.
+///
+/// Uses [positive_intersection_bitset] if the bags fit into [BitsetBag]s.
 pub fn positive_intersection<S: BuildHasher + Default>(
     first_vertex: &HashSet<NodeIndex, S>,
     second_vertex: &HashSet<NodeIndex, S>,
 ) -> i32 {
-    first_vertex
-        .intersection(second_vertex)
-        .collect::<HashSet<_, S>>()
-        .len() as i32
+    match as_bitset_bags(first_vertex, second_vertex) {
+        Some((first_bag, second_bag)) => positive_intersection_bitset(&first_bag, &second_bag),
+        None => first_vertex
+            .intersection(second_vertex)
+            .collect::<HashSet<_, S>>()
+            .len() as i32,
+    }
+}
+
+/// Same as [positive_intersection] for bags represented as [BitsetBag]s.
+pub fn positive_intersection_bitset(first_vertex: &BitsetBag, second_vertex: &BitsetBag) -> i32 {
+    first_vertex.intersection(second_vertex).len() as i32
 }
 
 /// Returns the sum of the cardinalities (the sum of the disjoint union).
@@ -44,25 +81,45 @@ pub fn disjoint_union<S: BuildHasher>(
 }
 
 /// Returns the cardinality of the union (sum of the cardinalities - cardinality of intersection).
+///
+/// Uses [union_bitset] if the bags fit into [BitsetBag]s.
 pub fn union<S: BuildHasher + Default>(
     first_vertex: &HashSet<NodeIndex, S>,
     second_vertex: &HashSet<NodeIndex, S>,
 ) -> i32 {
-    first_vertex
-        .union(second_vertex)
-        .collect::<HashSet<_, S>>()
-        .len() as i32
+    match as_bitset_bags(first_vertex, second_vertex) {
+        Some((first_bag, second_bag)) => union_bitset(&first_bag, &second_bag),
+        None => first_vertex
+            .union(second_vertex)
+            .collect::<HashSet<_, S>>()
+            .len() as i32,
+    }
+}
+
+/// Same as [union] for bags represented as [BitsetBag]s.
+pub fn union_bitset(first_vertex: &BitsetBag, second_vertex: &BitsetBag) -> i32 {
+    first_vertex.union(second_vertex).len() as i32
 }
 
 /// Returns the cardinality of the symmetric difference.
+///
+/// Uses [least_difference_bitset] if the bags fit into [BitsetBag]s.
 pub fn least_difference<S: BuildHasher + Default>(
     first_vertex: &HashSet<NodeIndex, S>,
     second_vertex: &HashSet<NodeIndex, S>,
 ) -> i32 {
-    first_vertex
-        .symmetric_difference(second_vertex)
-        .collect::<HashSet<_, S>>()
-        .len() as i32
+    match as_bitset_bags(first_vertex, second_vertex) {
+        Some((first_bag, second_bag)) => least_difference_bitset(&first_bag, &second_bag),
+        None => first_vertex
+            .symmetric_difference(second_vertex)
+            .collect::<HashSet<_, S>>()
+            .len() as i32,
+    }
+}
+
+/// Same as [least_difference] for bags represented as [BitsetBag]s.
+pub fn least_difference_bitset(first_vertex: &BitsetBag, second_vertex: &BitsetBag) -> i32 {
+    first_vertex.symmetric_difference(second_vertex).len() as i32
 }
 
 /// Returns a tuple with [negative_intersection] in the first and [least_difference] in the second entry
@@ -70,10 +127,37 @@ pub fn negative_intersection_then_least_difference<S: BuildHasher + Default>(
     first_vertex: &HashSet<NodeIndex, S>,
     second_vertex: &HashSet<NodeIndex, S>,
 ) -> (i32, i32) {
-    (
-        negative_intersection(first_vertex, second_vertex),
-        least_difference(first_vertex, second_vertex),
-    )
+    match as_bitset_bags(first_vertex, second_vertex) {
+        Some((first_bag, second_bag)) => (
+            negative_intersection_bitset(&first_bag, &second_bag),
+            least_difference_bitset(&first_bag, &second_bag),
+        ),
+        None => (
+            negative_intersection(first_vertex, second_vertex),
+            least_difference(first_vertex, second_vertex),
+        ),
+    }
+}
+
+/// Returns a tuple with [negative_intersection] in the first, [least_difference] in the second and
+/// [union] in the third entry, which breaks ties of [negative_intersection_then_least_difference]
+/// by preferring smaller unions.
+pub fn negative_intersection_then_least_difference_then_union<S: BuildHasher + Default>(
+    first_vertex: &HashSet<NodeIndex, S>,
+    second_vertex: &HashSet<NodeIndex, S>,
+) -> (i32, i32, i32) {
+    match as_bitset_bags(first_vertex, second_vertex) {
+        Some((first_bag, second_bag)) => (
+            negative_intersection_bitset(&first_bag, &second_bag),
+            least_difference_bitset(&first_bag, &second_bag),
+            union_bitset(&first_bag, &second_bag),
+        ),
+        None => (
+            negative_intersection(first_vertex, second_vertex),
+            least_difference(first_vertex, second_vertex),
+            union(first_vertex, second_vertex),
+        ),
+    }
 }
 
 /// Returns a tuple with [least_difference] in the first and [negative_intersection] in the second entry.
@@ -81,8 +165,14 @@ pub fn least_difference_then_negative_intersection<S: BuildHasher + Default>(
     first_vertex: &HashSet<NodeIndex, S>,
     second_vertex: &HashSet<NodeIndex, S>,
 ) -> (i32, i32) {
-    (
-        least_difference(first_vertex, second_vertex),
-        negative_intersection(first_vertex, second_vertex),
-    )
+    match as_bitset_bags(first_vertex, second_vertex) {
+        Some((first_bag, second_bag)) => (
+            least_difference_bitset(&first_bag, &second_bag),
+            negative_intersection_bitset(&first_bag, &second_bag),
+        ),
+        None => (
+            least_difference(first_vertex, second_vertex),
+            negative_intersection(first_vertex, second_vertex),
+        ),
+    }
 }
@@ -1,7 +1,15 @@
 use petgraph::{graph::NodeIndex, Graph, Undirected};
-use std::{collections::HashSet, fmt::Debug, hash::BuildHasher};
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+    time::{Duration, Instant},
+};
 
 use crate::*;
+use check_tree_decomposition::{validate_tree_decomposition, TreeDecompositionFault};
 use construct_clique_graph::*;
 use fill_bags_along_paths::*;
 use find_maximal_cliques::*;
@@ -31,6 +39,18 @@ use find_width_of_tree_decomposition::find_width_of_tree_decomposition;
 /// edge heuristic trying to speed up filling up by using the tree structure
 ///
 /// FWBag Fills bags while constructing a spanning tree of the clique graph trying to minimize the maximum bag size in each step
+///
+/// FilWhKruskal Fills bags while constructing a spanning tree minimizing according to the edge
+/// heuristic using kruskal's instead of prim's algorithm, thus naturally handling clique graphs
+/// that are not connected
+///
+/// OrderingGuided Fills bags while constructing a spanning tree attaching the vertices of the
+/// clique graph in an order consistent with a given elimination ordering (a min fill ordering if
+/// none is given) instead of by the edge heuristic
+///
+/// MaxSpanningTreeAndFill Constructs a maximum spanning tree of the clique graph and fills up the
+/// bags afterwards. This suits edge heuristics where bigger weights are better, e.g.
+/// [positive_intersection]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SpanningTreeConstructionMethod {
     MSTre,
@@ -40,8 +60,67 @@ pub enum SpanningTreeConstructionMethod {
     FWhUE,
     FilWhIUseTr,
     FWBag,
+    FilWhKruskal,
+    OrderingGuided,
+    MaxSpanningTreeAndFill,
+}
+
+/// Different sets of cliques whose intersection graph is used as the clique graph.
+///
+/// Maximal Uses all maximal cliques, that is, the usual clique graph
+///
+/// Bounded(k) Uses all cliques that are maximal (and of size less than k) or of size k. See
+/// [find_maximal_cliques_bounded] for further information and negative values of k
+///
+/// AllOfSizeAtLeast(k) Uses all cliques of size at least k (and maximal cliques smaller than k).
+/// The cliques are enumerated via combinations of each maximal clique, see
+/// [find_all_cliques_of_size_at_least]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CliqueSource {
+    Maximal,
+    Bounded(i32),
+    AllOfSizeAtLeast(usize),
+}
+
+/// Errors returned by [compute_treewidth_upper_bound_checked] instead of panicking or running out
+/// of memory.
+///
+/// TooManyVertices The graph has more than the given maximum number of vertices
+///
+/// TooManyCliques The clique source yields more than the given maximum number of cliques, that
+/// is, the clique graph would have more than maximum vertices
+///
+/// InvalidTreeDecomposition The computed tree decomposition is not valid, see
+/// [TreeDecompositionFault]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TreewidthError {
+    TooManyVertices { maximum: usize },
+    TooManyCliques { maximum: usize },
+    InvalidTreeDecomposition(TreeDecompositionFault),
+}
+
+impl std::fmt::Display for TreewidthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreewidthError::TooManyVertices { maximum } => write!(
+                f,
+                "The graph has more than the maximum of {} vertices",
+                maximum
+            ),
+            TreewidthError::TooManyCliques { maximum } => write!(
+                f,
+                "The clique graph would have more than the maximum of {} vertices",
+                maximum
+            ),
+            TreewidthError::InvalidTreeDecomposition(fault) => {
+                write!(f, "Tree decomposition is invalid: {}", fault)
+            }
+        }
+    }
 }
 
+impl std::error::Error for TreewidthError {}
+
 /// Computes an upper bound for the treewidth using the clique graph operator.
 ///
 /// Does this by computing the clique graph of the given graph and then constructing a spanning
@@ -53,10 +132,16 @@ pub enum SpanningTreeConstructionMethod {
 /// Also see [edge weight functions][crate::clique_graph_edge_weight_functions] for the different
 /// weight options for the edges in the clique graph.
 ///
-/// It is possible to not use the clique graph but the clique graph with a bound on the
-/// size of the cliques instead. The resulting graph is the intersection graph of the set of all
-/// cliques that are maximal or have a size of clique_bound. For further information on this read the
-/// documentation of [find_maximal_cliques_bounded].
+/// It is possible to not use the clique graph but the intersection graph of a different set of
+/// cliques instead, for example the set of all cliques that are maximal or have a size of some
+/// bound. See [CliqueSource] for the different options.
+///
+/// The ordering is only used by [OrderingGuided][SpanningTreeConstructionMethod::OrderingGuided]
+/// and allows injecting a known good elimination ordering into the clique graph fill.
+///
+/// Graphs without edges have treewidth 0, so this returns 0 for them without enumerating any
+/// cliques. In particular this includes graphs consisting of a single vertex and, by convention,
+/// the empty graph without any vertices.
 ///
 /// Can also check the tree decomposition for correctness after computation which will on average at least double
 /// the running time. If so, will panic if the tree decomposition is incorrect returning the vertices
@@ -71,147 +156,116 @@ pub fn compute_treewidth_upper_bound<
     edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     treewidth_computation_method: SpanningTreeConstructionMethod,
     check_tree_decomposition_bool: bool,
-    clique_bound: Option<i32>,
+    clique_source: CliqueSource,
+    ordering: Option<Vec<NodeIndex>>,
 ) -> usize {
-    // Find cliques in initial graph
-    let cliques: Vec<Vec<_>> = if let Some(k) = clique_bound {
-        find_maximal_cliques_bounded::<Vec<_>, _, S>(graph, k)
-            // .sorted()
-            .collect()
-    } else {
-        find_maximal_cliques::<Vec<_>, _, S>(graph)
-            // .sorted()
-            .collect()
-    };
-
-    let (clique_graph_tree_after_filling_up, clique_graph_map, predecessor_map) =
-        match treewidth_computation_method {
-            SpanningTreeConstructionMethod::MSTre => {
-                let clique_graph: Graph<_, _, _> =
-                    construct_clique_graph(cliques, edge_weight_function);
-
-                let mut clique_graph_tree: Graph<
-                    std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
-                    O,
-                    petgraph::prelude::Undirected,
-                > = petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
-                    &clique_graph,
-                ));
-
-                fill_bags_along_paths(&mut clique_graph_tree);
-
-                (clique_graph_tree, None, None)
-            }
-            SpanningTreeConstructionMethod::MSTreIUseTr => {
-                let (clique_graph, clique_graph_map) =
-                    construct_clique_graph_with_bags(cliques, edge_weight_function);
-
-                let mut clique_graph_tree: Graph<
-                    std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
-                    O,
-                    petgraph::prelude::Undirected,
-                > = petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
-                    &clique_graph,
-                ));
-
-                let predecessor_map = fill_bags_along_paths_using_structure(
-                    &mut clique_graph_tree,
-                    &clique_graph_map,
-                );
+    width_of_computed_tree_decomposition(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_source,
+        ordering.as_deref(),
+        CliqueOrder::AsGiven,
+    )
+}
 
-                (
-                    clique_graph_tree,
-                    Some(clique_graph_map),
-                    Some(predecessor_map),
-                )
-            }
-            SpanningTreeConstructionMethod::FilWh => {
-                let (clique_graph, clique_graph_map) =
-                    construct_clique_graph_with_bags(cliques, edge_weight_function);
-
-                let clique_graph_tree: Graph<
-                    std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
-                    O,
-                    petgraph::prelude::Undirected,
-                > = fill_bags_while_generating_mst::<N, E, O, S>(
-                    &clique_graph,
-                    edge_weight_function,
-                    clique_graph_map,
-                    false,
-                );
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] adding the
+/// cliques to the clique graph in the given [CliqueOrder] instead of the order in which they are
+/// enumerated (see there for the remaining parameters).
+pub fn compute_treewidth_upper_bound_with_clique_order<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+    clique_order: CliqueOrder,
+) -> usize {
+    width_of_computed_tree_decomposition(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_source,
+        None,
+        clique_order,
+    )
+}
 
-                (clique_graph_tree, None, None)
-            }
-            SpanningTreeConstructionMethod::FilWhILogBagSize => {
-                let (clique_graph, clique_graph_map) =
-                    construct_clique_graph_with_bags(cliques, edge_weight_function);
-
-                let clique_graph_tree: Graph<
-                    std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
-                    O,
-                    petgraph::prelude::Undirected,
-                > = fill_bags_while_generating_mst::<N, E, O, S>(
-                    &clique_graph,
-                    edge_weight_function,
-                    clique_graph_map,
-                    true,
-                );
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] passing the given
+/// [FillOptions] to the construction of the tree decomposition, e.g. to record the [FillStats] of
+/// filling up the bags or to break ties randomly. Which of the options are used depends on the
+/// method, see [FillOptions].
+pub fn compute_treewidth_upper_bound_with_fill_options<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    clique_source: CliqueSource,
+    mut fill_options: FillOptions,
+) -> usize {
+    if has_trivial_treewidth(graph) {
+        return 0;
+    }
 
-                (clique_graph_tree, None, None)
-            }
-            SpanningTreeConstructionMethod::FWhUE => {
-                let (clique_graph, clique_graph_map) =
-                    construct_clique_graph_with_bags(cliques, edge_weight_function);
-
-                let clique_graph_tree: Graph<
-                    std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
-                    O,
-                    petgraph::prelude::Undirected,
-                > = fill_bags_while_generating_mst_update_edges::<N, E, O, S>(
-                    &clique_graph,
-                    edge_weight_function,
-                    clique_graph_map,
-                );
+    let (tree_decomposition, _, _) = compute_tree_decomposition(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        clique_source,
+        None,
+        CliqueOrder::AsGiven,
+        &mut fill_options,
+    );
 
-                (clique_graph_tree, None, None)
-            }
-            SpanningTreeConstructionMethod::FilWhIUseTr => {
-                let (clique_graph, clique_graph_map) =
-                    construct_clique_graph_with_bags(cliques, edge_weight_function);
-
-                let clique_graph_tree: Graph<
-                    std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
-                    O,
-                    petgraph::prelude::Undirected,
-                > = fill_bags_while_generating_mst_using_tree::<N, E, O, S>(
-                    &clique_graph,
-                    edge_weight_function,
-                    clique_graph_map,
-                );
+    find_width_of_tree_decomposition(&tree_decomposition)
+}
 
-                (clique_graph_tree, None, None)
-            }
-            SpanningTreeConstructionMethod::FWBag => {
-                let (clique_graph, clique_graph_map) =
-                    construct_clique_graph_with_bags(cliques, edge_weight_function);
-
-                let clique_graph_tree: Graph<
-                    std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
-                    O,
-                    petgraph::prelude::Undirected,
-                > = fill_bags_while_generating_mst_least_bag_size::<N, E, O, S>(
-                    &clique_graph,
-                    clique_graph_map,
-                );
+/// Computes the tree decomposition with [compute_tree_decomposition], checks it if requested and
+/// returns its width, see [compute_treewidth_upper_bound].
+fn width_of_computed_tree_decomposition<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+    ordering: Option<&[NodeIndex]>,
+    clique_order: CliqueOrder,
+) -> usize {
+    // Trivially small graphs have treewidth 0 and would make the clique machinery panic
+    if has_trivial_treewidth(graph) {
+        return 0;
+    }
 
-                (clique_graph_tree, None, None)
-            }
-        };
+    let (clique_graph_tree_after_filling_up, clique_graph_map, predecessor_map) =
+        compute_tree_decomposition(
+            graph,
+            edge_weight_function,
+            treewidth_computation_method,
+            clique_source,
+            ordering,
+            clique_order,
+            &mut FillOptions::default(),
+        );
 
     if check_tree_decomposition_bool {
         assert!(
             check_tree_decomposition(
-                &graph,
+                graph,
                 &clique_graph_tree_after_filling_up,
                 &predecessor_map,
                 &clique_graph_map
@@ -219,199 +273,2099 @@ pub fn compute_treewidth_upper_bound<
             "Tree decomposition is invalid. See previous print statements for reason."
         );
     }
-    let treewidth = find_width_of_tree_decomposition(&clique_graph_tree_after_filling_up);
 
-    treewidth
+    find_width_of_tree_decomposition(&clique_graph_tree_after_filling_up)
 }
 
-/// Computes an upper bound for the treewidth returning the maximum [compute_treewidth_upper_bound] on the
-/// components
-pub fn compute_treewidth_upper_bound_not_connected<
-    N: Clone + Debug,
-    E: Clone + Debug,
+/// Computes an upper bound for the treewidth with [SpanningTreeConstructionMethod::FilWh] and the
+/// maximal cliques once for each of the given seeds and returns the minimum width. The seed is the
+/// vertex of the clique graph the spanning tree is started from, where the vertex with NodeIndex i
+/// is the i-th enumerated maximal clique. The result for the seed 0 is the result of
+/// [compute_treewidth_upper_bound].
+///
+/// If no seeds are given, the seed 0 is used.
+///
+/// **Panics**
+/// Panics if a seed is not a vertex of the clique graph.
+pub fn treewidth_best_seed<
+    N: Clone,
+    E: Clone,
     O: Clone + Ord + Default + Debug,
     S: Default + BuildHasher + Clone,
 >(
     graph: &Graph<N, E, Undirected>,
     edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
-    treewidth_computation_method: SpanningTreeConstructionMethod,
-    check_tree_decomposition_bool: bool,
-    clique_bound: Option<i32>,
+    seeds: &[NodeIndex],
 ) -> usize {
-    let components = find_connected_components::<Vec<_>, _, _, S>(graph);
-    let mut computed_treewidth: usize = 0;
-
-    for component in components {
-        println!("Test");
-        let mut subgraph = graph.clone();
-        subgraph.retain_nodes(|_, v| component.contains(&v));
+    if has_trivial_treewidth(graph) {
+        return 0;
+    }
 
-        println!("Graph: {:?} \n Subgraph: {:?}", graph, subgraph);
+    let cliques: Vec<Vec<NodeIndex>> =
+        clique_iterator::<N, E, S>(graph, CliqueSource::Maximal).collect();
+    let default_seed = [NodeIndex::new(0)];
+    let seeds = if seeds.is_empty() {
+        &default_seed[..]
+    } else {
+        seeds
+    };
 
-        computed_treewidth = computed_treewidth.max(compute_treewidth_upper_bound(
-            &subgraph,
-            edge_weight_function,
-            treewidth_computation_method,
-            check_tree_decomposition_bool,
-            clique_bound,
-        ));
-    }
+    seeds
+        .iter()
+        .map(|seed| {
+            assert!(
+                seed.index() < cliques.len(),
+                "Seed {} should be a vertex of the clique graph with {} vertices",
+                seed.index(),
+                cliques.len()
+            );
+            // The spanning tree is started from the first vertex of the clique graph, so the seed
+            // clique is moved to the front keeping the order of the other cliques
+            let mut cliques_starting_with_seed = cliques.clone();
+            cliques_starting_with_seed[..=seed.index()].rotate_right(1);
 
-    computed_treewidth
+            let (tree_decomposition, _, _) = tree_decomposition_from_cliques(
+                graph,
+                cliques_starting_with_seed,
+                edge_weight_function,
+                SpanningTreeConstructionMethod::FilWh,
+                None,
+                CliqueOrder::AsGiven,
+                &mut FillOptions::default(),
+            );
+            find_width_of_tree_decomposition(&tree_decomposition)
+        })
+        .min()
+        .expect("There should be at least one seed")
 }
 
-#[cfg(test)]
-mod tests {
-    use std::hash::RandomState;
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] with the tree
+/// decomposition always being checked. Instead of panicking if the tree decomposition is invalid,
+/// the fault is returned, which allows e.g. batch runs to log the faulty instance and continue.
+pub fn compute_treewidth_upper_bound_verified<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    clique_source: CliqueSource,
+) -> Result<usize, TreeDecompositionFault> {
+    if has_trivial_treewidth(graph) {
+        return Ok(0);
+    }
 
-    use super::*;
-    use crate::tests::*;
+    let (clique_graph_tree_after_filling_up, _, _) = compute_tree_decomposition(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        clique_source,
+        None,
+        CliqueOrder::AsGiven,
+        &mut FillOptions::default(),
+    );
 
-    #[test]
-    fn test_treewidth_heuristic_check_tree_decomposition() {
-        for i in 0..3 {
-            let test_graph = setup_test_graph(i);
-            let _ = compute_treewidth_upper_bound_not_connected::<_, _, _, RandomState>(
-                &test_graph.graph,
-                constant,
-                SpanningTreeConstructionMethod::MSTreIUseTr,
-                true,
-                None,
-            );
+    width_of_valid_tree_decomposition(graph, &clique_graph_tree_after_filling_up)
+}
 
-            let _ = compute_treewidth_upper_bound_not_connected::<_, _, _, RandomState>(
-                &test_graph.graph,
-                constant,
-                SpanningTreeConstructionMethod::MSTre,
-                true,
-                None,
-            );
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] returning errors
+/// instead of panicking (see there for the remaining parameters).
+///
+/// If max_vertices is given and the graph has more vertices,
+/// [TooManyVertices][TreewidthError::TooManyVertices] is returned right away, which protects e.g.
+/// interactive tools from accidentally starting huge runs.
+///
+/// If max_clique_graph_nodes is given, at most that many cliques are enumerated. If the clique source
+/// yields more cliques, [TooManyCliques][TreewidthError::TooManyCliques] is returned before the
+/// clique graph is constructed, which guards against inputs whose clique graph wouldn't fit into
+/// memory. If the tree decomposition is checked and invalid,
+/// [InvalidTreeDecomposition][TreewidthError::InvalidTreeDecomposition] is returned.
+pub fn compute_treewidth_upper_bound_checked<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+    max_vertices: Option<usize>,
+    max_clique_graph_nodes: Option<usize>,
+) -> Result<usize, TreewidthError> {
+    if let Some(maximum) = max_vertices {
+        if graph.node_count() > maximum {
+            return Err(TreewidthError::TooManyVertices { maximum });
         }
     }
+    if has_trivial_treewidth(graph) {
+        return Ok(0);
+    }
 
-    #[test]
-    fn test_treewidth_heuristic_and_check_result_neutral_weight_heuristic() {
-        for i in 0..3 {
-            for computation_method in COMPUTATION_METHODS {
-                let test_graph = setup_test_graph(i);
-                let computed_treewidth =
-                    compute_treewidth_upper_bound_not_connected::<
-                        _,
-                        _,
-                        _,
-                        std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
-                    >(
-                        &test_graph.graph, constant, computation_method, false, None
-                    );
-                if !(i == 1
-                    && (computation_method == SpanningTreeConstructionMethod::MSTre
-                        || computation_method == SpanningTreeConstructionMethod::MSTreIUseTr))
-                {
-                    if i == 1 && computation_method == SpanningTreeConstructionMethod::FilWh {
-                        assert_eq!(computed_treewidth, 4);
-                    } else {
-                        assert_eq!(
-                            computed_treewidth, test_graph.treewidth,
-                            "Test graph number {} failed with computation method {:?}",
-                            i, computation_method
-                        );
-                    }
-                }
+    let cliques = clique_iterator::<N, E, S>(graph, clique_source);
+    let cliques: Vec<Vec<NodeIndex>> = match max_clique_graph_nodes {
+        Some(maximum) => {
+            let cliques: Vec<Vec<NodeIndex>> = cliques.take(maximum + 1).collect();
+            if cliques.len() > maximum {
+                return Err(TreewidthError::TooManyCliques { maximum });
             }
+            cliques
         }
+        None => cliques.collect(),
+    };
+
+    let (clique_graph_tree_after_filling_up, _, _) = tree_decomposition_from_cliques(
+        graph,
+        cliques,
+        edge_weight_function,
+        treewidth_computation_method,
+        None,
+        CliqueOrder::AsGiven,
+        &mut FillOptions::default(),
+    );
+
+    if check_tree_decomposition_bool {
+        width_of_valid_tree_decomposition(graph, &clique_graph_tree_after_filling_up)
+            .map_err(TreewidthError::InvalidTreeDecomposition)
+    } else {
+        Ok(find_width_of_tree_decomposition(
+            &clique_graph_tree_after_filling_up,
+        ))
     }
+}
 
-    #[test]
-    fn test_treewidth_heuristic_and_check_result_negative_intersection_weight_heuristic() {
-        for i in vec![0, 2] {
-            for computation_method in COMPUTATION_METHODS {
-                let test_graph = setup_test_graph(i);
-                let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
-                    _,
-                    _,
-                    _,
-                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
-                >(
-                    &test_graph.graph,
-                    negative_intersection,
-                    computation_method,
-                    true,
-                    None,
-                );
-                if !(i == 1
-                    && (computation_method == SpanningTreeConstructionMethod::MSTre
-                        || computation_method == SpanningTreeConstructionMethod::MSTreIUseTr))
-                {
-                    assert_eq!(
-                        computed_treewidth, test_graph.treewidth,
-                        "computation method: {:?}. Test graph {:?}",
-                        computation_method, i
-                    );
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] with each of the
+/// given methods using the maximal cliques and returns the best bound among the methods that
+/// succeeded (see there for the remaining parameters).
+///
+/// A method fails if it panics on the input or if its tree decomposition is invalid (see
+/// [compute_treewidth_upper_bound_checked]), in which case a warning is logged and the next method
+/// is tried. This shields e.g. batch jobs from failures of single methods.
+///
+/// **Panics**
+/// Panics if none of the methods succeeded, in particular if no methods are given.
+pub fn treewidth_with_fallback<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_methods: &[SpanningTreeConstructionMethod],
+) -> usize {
+    best_width_of_successful_methods(
+        treewidth_computation_methods,
+        |treewidth_computation_method| {
+            compute_treewidth_upper_bound_checked(
+                graph,
+                edge_weight_function,
+                treewidth_computation_method,
+                true,
+                CliqueSource::Maximal,
+                None,
+                None,
+            )
+        },
+    )
+}
+
+/// Calls compute_width with each of the given methods and returns the minimum width among the
+/// methods for which it neither panicked nor returned an error, see [treewidth_with_fallback].
+fn best_width_of_successful_methods(
+    treewidth_computation_methods: &[SpanningTreeConstructionMethod],
+    compute_width: impl Fn(SpanningTreeConstructionMethod) -> Result<usize, TreewidthError>,
+) -> usize {
+    treewidth_computation_methods
+        .iter()
+        .filter_map(|treewidth_computation_method| {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                compute_width(*treewidth_computation_method)
+            }));
+            match result {
+                Ok(Ok(width)) => Some(width),
+                Ok(Err(error)) => {
+                    log::warn!("{:?} failed: {}", treewidth_computation_method, error);
+                    None
+                }
+                Err(_) => {
+                    log::warn!("{:?} panicked", treewidth_computation_method);
+                    None
                 }
             }
-        }
+        })
+        .min()
+        .expect("At least one of the methods should succeed")
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] using the
+/// [FilWh][SpanningTreeConstructionMethod::FilWh] method. Additionally returns the size of the
+/// biggest bag of the spanning tree each time a vertex of the clique graph was added to it, which is
+/// what [FilWhILogBagSize][SpanningTreeConstructionMethod::FilWhILogBagSize] logs to a file.
+///
+/// The returned sizes are non-decreasing and the last one is the returned width plus one. For
+/// graphs with at most one vertex or without edges, the width 0 and an empty trace are returned.
+pub fn compute_treewidth_with_trace<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    clique_source: CliqueSource,
+) -> (usize, Vec<usize>) {
+    if has_trivial_treewidth(graph) {
+        return (0, Vec::new());
     }
 
-    #[test]
-    fn negative_intersection_weight_heuristic_does_not_fail_on_first_test_graph() {
-        let i = 1;
-        let computation_method = SpanningTreeConstructionMethod::MSTreIUseTr;
+    let cliques = clique_iterator::<N, E, S>(graph, clique_source);
+    let (clique_graph, clique_graph_map) =
+        construct_clique_graph_with_bags(cliques, edge_weight_function);
 
-        let test_graph = setup_test_graph(i);
-        let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
-            _,
+    let (clique_graph_tree, maximum_bag_sizes) = fill_bags_while_generating_mst_with_trace(
+        Cow::Owned(clique_graph),
+        edge_weight_function,
+        clique_graph_map,
+        &mut FillOptions::default(),
+        None,
+        false,
+    );
+
+    (
+        find_width_of_tree_decomposition(&clique_graph_tree),
+        maximum_bag_sizes,
+    )
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] using the
+/// [FilWh][SpanningTreeConstructionMethod::FilWh] method and passes each bag of the tree
+/// decomposition to on_bag_finalized together with its NodeIndex in the decomposition as soon as
+/// the construction can guarantee that the bag won't change anymore.
+///
+/// A bag is guaranteed not to change once it isn't on a path in the current spanning tree between
+/// vertices whose cliques still share vertices with cliques that haven't been added yet. Every bag
+/// is reported exactly once and at the latest when the spanning tree is complete. For graphs with
+/// at most one vertex or without edges, 0 is returned without reporting any bags.
+pub fn compute_treewidth_streaming_bags<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    clique_source: CliqueSource,
+    on_bag_finalized: &mut dyn FnMut(NodeIndex, &HashSet<NodeIndex, S>),
+) -> usize {
+    if has_trivial_treewidth(graph) {
+        return 0;
+    }
+
+    let cliques = clique_iterator::<N, E, S>(graph, clique_source);
+    let (clique_graph, clique_graph_map) =
+        construct_clique_graph_with_bags(cliques, edge_weight_function);
+
+    let (clique_graph_tree, _) = fill_bags_while_generating_mst_with_trace(
+        Cow::Owned(clique_graph),
+        edge_weight_function,
+        clique_graph_map,
+        &mut FillOptions::default(),
+        Some(on_bag_finalized),
+        false,
+    );
+
+    find_width_of_tree_decomposition(&clique_graph_tree)
+}
+
+/// Computes the same upper bound for the treewidth as [compute_treewidth_upper_bound] using the
+/// maximal cliques without keeping the tree decomposition. For
+/// [FilWh][SpanningTreeConstructionMethod::FilWh], the filled up bags of the spanning tree are
+/// discarded as soon as they can't change anymore (see [compute_treewidth_streaming_bags]) and
+/// only the size of the biggest bag is tracked.
+///
+/// The bags of the clique graph are moved into the spanning tree when their vertex is added and the
+/// vertices of the original graph are dropped from the map to the cliques containing them once all
+/// of these cliques were added. Thus, apart from the edges of the clique graph, only the bags of
+/// the cliques that haven't been added yet and the bags that can still change are kept in memory.
+/// Which bags can't change anymore is tracked incrementally, so this doesn't make the computation
+/// asymptotically slower.
+///
+/// The other methods need the whole spanning tree for filling up the bags, so for them the full
+/// computation is done.
+pub fn estimate_width_only<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> usize {
+    if treewidth_computation_method != SpanningTreeConstructionMethod::FilWh {
+        return compute_treewidth_upper_bound(
+            graph,
+            edge_weight_function,
+            treewidth_computation_method,
+            false,
+            CliqueSource::Maximal,
+            None,
+        );
+    }
+    if has_trivial_treewidth(graph) {
+        return 0;
+    }
+
+    let cliques = clique_iterator::<N, E, S>(graph, CliqueSource::Maximal);
+    let (clique_graph, clique_graph_map) =
+        construct_clique_graph_with_bags(cliques, edge_weight_function);
+
+    let (_, maximum_bag_sizes) = fill_bags_while_generating_mst_with_trace(
+        Cow::Owned(clique_graph),
+        edge_weight_function,
+        clique_graph_map,
+        &mut FillOptions::default(),
+        Some(&mut |_, _| {}),
+        true,
+    );
+
+    maximum_bag_sizes
+        .last()
+        .expect("There should be a bag size for each vertex of the clique graph")
+        - 1
+}
+
+/// The time spent in the phases of [compute_treewidth_timed].
+///
+/// clique_enumeration is the time spent enumerating the cliques
+///
+/// clique_graph_construction is the time spent constructing the clique graph
+///
+/// spanning_tree is the time spent constructing the spanning tree of the clique graph
+///
+/// fill is the time spent filling up the bags of the spanning tree
+///
+/// total is the time spent in the whole computation, which is at least the sum of the phases
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    pub clique_enumeration: Duration,
+    pub clique_graph_construction: Duration,
+    pub spanning_tree: Duration,
+    pub fill: Duration,
+    pub total: Duration,
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] and additionally
+/// returns the time spent in each phase of the computation (see [PhaseTimings]), which shows
+/// whether e.g. the clique enumeration or the filling up of the bags dominates on an instance.
+///
+/// For the methods filling up the bags while constructing the spanning tree, the time spent filling
+/// up is reported as fill and the remaining time as spanning tree. The maximal cliques are
+/// enumerated before constructing the clique graph instead of being streamed into it.
+pub fn compute_treewidth_timed<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    clique_source: CliqueSource,
+) -> (usize, PhaseTimings) {
+    let start = Instant::now();
+    let mut timings = PhaseTimings::default();
+    if has_trivial_treewidth(graph) {
+        timings.total = start.elapsed();
+        return (0, timings);
+    }
+
+    let phase_start = Instant::now();
+    let cliques: Vec<Vec<NodeIndex>> = clique_iterator::<N, E, S>(graph, clique_source).collect();
+    timings.clique_enumeration = phase_start.elapsed();
+
+    let (clique_graph_tree, _, _) = tree_decomposition_from_cliques(
+        graph,
+        cliques,
+        edge_weight_function,
+        treewidth_computation_method,
+        None,
+        CliqueOrder::AsGiven,
+        &mut FillOptions {
+            timings: Some(&mut timings),
+            ..Default::default()
+        },
+    );
+
+    let treewidth = find_width_of_tree_decomposition(&clique_graph_tree);
+    timings.total = start.elapsed();
+
+    (treewidth, timings)
+}
+
+/// Computes a lower and an upper bound for the treewidth of the graph. The upper bound is computed
+/// like [compute_treewidth_upper_bound] using the maximal cliques of the graph and the lower bound
+/// is omega(G) - 1 (see [clique_number_lower_bound][crate::clique_number_lower_bound]) computed
+/// from the same enumeration of the maximal cliques.
+///
+/// Returns (lower, upper). If lower == upper, the treewidth is exactly lower.
+pub fn treewidth_bounds<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> (usize, usize) {
+    if has_trivial_treewidth(graph) {
+        return (0, 0);
+    }
+
+    let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, S>(graph).collect();
+    let lower_bound = cliques.iter().map(|clique| clique.len()).max().unwrap_or(1) - 1;
+
+    let (tree_decomposition, _, _) = tree_decomposition_from_cliques(
+        graph,
+        cliques,
+        edge_weight_function,
+        treewidth_computation_method,
+        None,
+        CliqueOrder::AsGiven,
+        &mut FillOptions::default(),
+    );
+
+    (
+        lower_bound,
+        find_width_of_tree_decomposition(&tree_decomposition),
+    )
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] for each of the
+/// given clique bounds, using the intersection graph of the cliques given by
+/// [CliqueSource::Bounded] as the clique graph. Returns the pairs of bounds and resulting widths
+/// in the order of the given bounds.
+///
+/// This shows how the clique bound trades accuracy for the size of the clique graph.
+pub fn width_vs_clique_bound<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    bounds: &[i32],
+) -> Vec<(i32, usize)> {
+    bounds
+        .iter()
+        .map(|bound| {
+            (
+                *bound,
+                compute_treewidth_upper_bound(
+                    graph,
+                    edge_weight_function,
+                    treewidth_computation_method,
+                    false,
+                    CliqueSource::Bounded(*bound),
+                    None,
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Returns how tree-like the graph is according to the upper bound computed by
+/// [compute_treewidth_upper_bound] using the maximal cliques, namely 1 - width / (n - 1) where n is
+/// the number of vertices. Since the treewidth is at most n - 1, the score is between 0.0 (the
+/// graph is complete or the heuristic is bad) and 1.0, which is approached by trees (width 1) with
+/// many vertices.
+///
+/// Returns 1.0 for graphs with at most two vertices.
+pub fn tree_likeness<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> f64 {
+    if graph.node_count() <= 2 {
+        return 1.0;
+    }
+
+    let computed_width = compute_treewidth_upper_bound(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        false,
+        CliqueSource::Maximal,
+        None,
+    );
+
+    1.0 - computed_width as f64 / (graph.node_count() - 1) as f64
+}
+
+/// Computes the tree decomposition whose width is returned by [compute_treewidth_upper_bound] and
+/// returns it if its width is at most the given width and None otherwise. This is the constructive
+/// companion to asking whether the heuristic certifies treewidth at most width.
+///
+/// Since the cliques are bags of the decomposition, the computation is aborted before constructing
+/// the clique graph if one of the cliques has more than width + 1 vertices.
+///
+/// For graphs with at most one vertex or without edges, the decomposition consists of one bag per
+/// vertex.
+pub fn decomposition_if_width_at_most<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    width: usize,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    clique_source: CliqueSource,
+) -> Option<Graph<HashSet<NodeIndex, S>, O, Undirected>> {
+    if has_trivial_treewidth(graph) {
+        return Some(trivial_tree_decomposition(graph));
+    }
+
+    let cliques: Vec<Vec<NodeIndex>> = clique_iterator::<N, E, S>(graph, clique_source).collect();
+    if cliques.iter().any(|clique| clique.len() > width + 1) {
+        return None;
+    }
+
+    let (tree_decomposition, _, _) = tree_decomposition_from_cliques(
+        graph,
+        cliques,
+        edge_weight_function,
+        treewidth_computation_method,
+        None,
+        CliqueOrder::AsGiven,
+        &mut FillOptions::default(),
+    );
+
+    (find_width_of_tree_decomposition(&tree_decomposition) <= width).then_some(tree_decomposition)
+}
+
+/// The result of [full_treewidth_result].
+///
+/// width is the width of the tree decomposition
+///
+/// decomposition is the computed tree decomposition
+///
+/// elimination_order is an elimination ordering of the vertices of the graph derived from the
+/// decomposition, whose width is at most width
+#[derive(Clone, Debug)]
+pub struct TreewidthResult<O, S> {
+    pub width: usize,
+    pub decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    pub elimination_order: Vec<NodeIndex>,
+}
+
+/// Computes the tree decomposition whose width is returned by [compute_treewidth_upper_bound] using
+/// the maximal cliques and returns it together with its width and an elimination ordering derived
+/// from it (see [elimination_ordering_from_decomposition][crate::elimination_ordering_from_decomposition]).
+///
+/// For graphs with at most one vertex or without edges, the decomposition consists of one bag per
+/// vertex.
+pub fn full_treewidth_result<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> TreewidthResult<O, S> {
+    let decomposition = if has_trivial_treewidth(graph) {
+        trivial_tree_decomposition(graph)
+    } else {
+        let (decomposition, _, _) = compute_tree_decomposition(
+            graph,
+            edge_weight_function,
+            treewidth_computation_method,
+            CliqueSource::Maximal,
+            None,
+            CliqueOrder::AsGiven,
+            &mut FillOptions::default(),
+        );
+        decomposition
+    };
+
+    TreewidthResult {
+        width: find_width_of_tree_decomposition(&decomposition),
+        elimination_order: elimination_ordering_from_decomposition(&decomposition),
+        decomposition,
+    }
+}
+
+/// Returns the tree decomposition consisting of one bag per vertex of the graph where all bags are
+/// adjacent to the first one, which is a tree decomposition of width 0 for graphs without edges.
+fn trivial_tree_decomposition<N, E, O: Default, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let mut tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        Graph::new_undirected();
+    for vertex in graph.node_indices() {
+        let bag_index = tree_decomposition.add_node(HashSet::from_iter([vertex]));
+        if bag_index.index() > 0 {
+            tree_decomposition.add_edge(NodeIndex::new(0), bag_index, O::default());
+        }
+    }
+
+    tree_decomposition
+}
+
+/// Returns true if the graph has at most one vertex or no edges, in which case its treewidth is 0.
+fn has_trivial_treewidth<N, E>(graph: &Graph<N, E, Undirected>) -> bool {
+    graph.node_count() <= 1 || graph.edge_count() == 0
+}
+
+/// Returns the width of the tree decomposition if it is a valid tree decomposition of the graph
+/// and the fault otherwise.
+fn width_of_valid_tree_decomposition<N, E, O, S: BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Result<usize, TreeDecompositionFault> {
+    validate_tree_decomposition(graph, tree_decomposition)?;
+
+    Ok(find_width_of_tree_decomposition(tree_decomposition))
+}
+
+/// The tree decomposition computed by [compute_tree_decomposition] together with the clique graph
+/// map and predecessor map if they were used by the computation method (which gives additional
+/// information when checking the tree decomposition).
+pub(crate) type TreeDecompositionWithMaps<O, S> = (
+    Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    Option<CliqueGraphMap<S>>,
+    Option<HashMap<NodeIndex, (NodeIndex, usize), S>>,
+);
+
+/// Computes the tree decomposition whose width is returned by [compute_treewidth_upper_bound].
+pub(crate) fn compute_tree_decomposition<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    clique_source: CliqueSource,
+    ordering: Option<&[NodeIndex]>,
+    clique_order: CliqueOrder,
+    fill_options: &mut FillOptions,
+) -> TreeDecompositionWithMaps<O, S> {
+    // Find cliques in initial graph. They are streamed into the construction of the clique graph
+    // instead of being collected first
+    let cliques = clique_iterator::<N, E, S>(graph, clique_source);
+
+    tree_decomposition_from_cliques(
+        graph,
+        cliques,
+        edge_weight_function,
+        treewidth_computation_method,
+        ordering,
+        clique_order,
+        fill_options,
+    )
+}
+
+/// Computes the tree decomposition like [compute_tree_decomposition] using the intersection graph
+/// of the given cliques of the graph as the clique graph. The cliques are added to the clique graph
+/// in the given [CliqueOrder], for [AsGiven][CliqueOrder::AsGiven] they are streamed without
+/// collecting them first.
+fn tree_decomposition_from_cliques<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    cliques: impl IntoIterator<Item = Vec<NodeIndex>>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    ordering: Option<&[NodeIndex]>,
+    clique_order: CliqueOrder,
+    fill_options: &mut FillOptions,
+) -> TreeDecompositionWithMaps<O, S> {
+    let mut phase_timer = PhaseTimer::start(fill_options);
+    let cliques = match clique_order {
+        CliqueOrder::AsGiven => itertools::Either::Left(cliques.into_iter()),
+        clique_order => {
+            let mut cliques: Vec<Vec<NodeIndex>> = cliques.into_iter().collect();
+            order_cliques(&mut cliques, clique_order);
+            itertools::Either::Right(cliques.into_iter())
+        }
+    };
+
+    match treewidth_computation_method {
+        SpanningTreeConstructionMethod::MSTre => {
+            let clique_graph: Graph<_, _, _> =
+                construct_clique_graph(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let mut clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                &clique_graph,
+            ));
+            join_spanning_forest(&mut clique_graph_tree);
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            fill_bags_along_paths_with_options(&mut clique_graph_tree, fill_options);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::MSTreIUseTr => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let mut clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                &clique_graph,
+            ));
+            join_spanning_forest(&mut clique_graph_tree);
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            let predecessor_map =
+                fill_bags_along_paths_using_structure(&mut clique_graph_tree, &clique_graph_map);
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.fill);
+
+            (
+                clique_graph_tree,
+                Some(clique_graph_map),
+                Some(predecessor_map),
+            )
+        }
+        SpanningTreeConstructionMethod::FilWh => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = fill_bags_while_generating_mst::<N, E, O, S>(
+                &clique_graph,
+                edge_weight_function,
+                clique_graph_map,
+                false,
+                fill_options,
+            );
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::FilWhILogBagSize => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = fill_bags_while_generating_mst::<N, E, O, S>(
+                &clique_graph,
+                edge_weight_function,
+                clique_graph_map,
+                true,
+                fill_options,
+            );
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::FWhUE => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = fill_bags_while_generating_mst_update_edges::<N, E, O, S>(
+                &clique_graph,
+                edge_weight_function,
+                clique_graph_map,
+                fill_options,
+            );
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::FilWhIUseTr => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = fill_bags_while_generating_mst_using_tree::<N, E, O, S>(
+                &clique_graph,
+                edge_weight_function,
+                clique_graph_map,
+                fill_options,
+            );
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::FWBag => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = fill_bags_while_generating_mst_least_bag_size::<N, E, O, S>(
+                &clique_graph,
+                clique_graph_map,
+                fill_options,
+            );
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::FilWhKruskal => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = fill_bags_while_generating_mst_kruskal::<O, S>(
+                &clique_graph,
+                edge_weight_function,
+                clique_graph_map,
+                fill_options,
+            );
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::OrderingGuided => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+            let ordering = match ordering {
+                Some(ordering) => ordering.to_vec(),
+                None => min_fill_ordering(graph),
+            };
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = fill_bags_while_generating_mst_ordering_guided::<O, S>(
+                &clique_graph,
+                edge_weight_function,
+                clique_graph_map,
+                &ordering,
+                fill_options,
+            );
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            (clique_graph_tree, None, None)
+        }
+        SpanningTreeConstructionMethod::MaxSpanningTreeAndFill => {
+            let clique_graph: Graph<_, _, _> =
+                construct_clique_graph(cliques, edge_weight_function);
+            phase_timer.finish_phase(fill_options, |timings| {
+                &mut timings.clique_graph_construction
+            });
+            // Reversing the order of the weights turns a minimum into a maximum spanning tree
+            let reversed_clique_graph =
+                clique_graph.map(|_, bag| bag.clone(), |_, weight| Reverse(weight.clone()));
+
+            let reversed_clique_graph_tree: Graph<HashSet<NodeIndex, S>, Reverse<O>, Undirected> =
+                petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                    &reversed_clique_graph,
+                ));
+            let mut clique_graph_tree: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+                reversed_clique_graph_tree.map(|_, bag| bag.clone(), |_, weight| weight.0.clone());
+            join_spanning_forest(&mut clique_graph_tree);
+            phase_timer.finish_phase(fill_options, |timings| &mut timings.spanning_tree);
+
+            fill_bags_along_paths_with_options(&mut clique_graph_tree, fill_options);
+
+            (clique_graph_tree, None, None)
+        }
+    }
+}
+
+/// Measures the phases of the computation of a tree decomposition for the timings of the fill
+/// options (see [FillOptions] and [PhaseTimings]).
+struct PhaseTimer {
+    start: Instant,
+    fill_at_start: Duration,
+}
+
+impl PhaseTimer {
+    fn start(fill_options: &FillOptions) -> Self {
+        PhaseTimer {
+            start: Instant::now(),
+            fill_at_start: fill_options
+                .timings
+                .as_ref()
+                .map_or(Duration::ZERO, |timings| timings.fill),
+        }
+    }
+
+    /// Adds the time since the start of the current phase to the given phase of the timings and
+    /// starts the next phase. The time that the constructions recorded for filling up the bags in
+    /// the meantime is not added again.
+    fn finish_phase(
+        &mut self,
+        fill_options: &mut FillOptions,
+        phase: fn(&mut PhaseTimings) -> &mut Duration,
+    ) {
+        if let Some(timings) = fill_options.timings.as_deref_mut() {
+            let fill_in_phase = timings.fill - self.fill_at_start;
+            *phase(timings) += self.start.elapsed().saturating_sub(fill_in_phase);
+            self.fill_at_start = timings.fill;
+        }
+        self.start = Instant::now();
+    }
+}
+
+/// Returns an iterator over the cliques of the given clique source (see [CliqueSource]).
+fn clique_iterator<'a, N, E, S: Default + BuildHasher + Clone + 'a>(
+    graph: &'a Graph<N, E, Undirected>,
+    clique_source: CliqueSource,
+) -> Box<dyn Iterator<Item = Vec<NodeIndex>> + 'a> {
+    match clique_source {
+        CliqueSource::Maximal => Box::new(find_maximal_cliques::<Vec<_>, _, S>(graph)),
+        CliqueSource::Bounded(k) => {
+            Box::new(find_maximal_cliques_bounded::<Vec<_>, _, S>(graph, k))
+        }
+        CliqueSource::AllOfSizeAtLeast(k) => {
+            Box::new(find_all_cliques_of_size_at_least::<Vec<_>, _, S>(graph, k))
+        }
+    }
+}
+
+/// Joins the trees of a spanning forest (the minimum spanning tree of a clique graph that is not
+/// connected) into one tree by connecting the first vertex of each tree with the first vertex of the
+/// graph. Bags from different trees are disjoint, so the result is still a valid tree decomposition
+/// once the bags are filled up.
+pub(crate) fn join_spanning_forest<O: Default, S>(
+    spanning_forest: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) {
+    let mut seen_vertices: HashSet<NodeIndex> = HashSet::new();
+    let mut roots = Vec::new();
+
+    for vertex in spanning_forest.node_indices() {
+        if seen_vertices.insert(vertex) {
+            roots.push(vertex);
+            let mut dfs = petgraph::visit::Dfs::new(&*spanning_forest, vertex);
+            while let Some(reachable_vertex) = dfs.next(&*spanning_forest) {
+                seen_vertices.insert(reachable_vertex);
+            }
+        }
+    }
+
+    if let Some((first_root, other_roots)) = roots.split_first() {
+        for root in other_roots {
+            spanning_forest.add_edge(*first_root, *root, O::default());
+        }
+    }
+}
+
+/// Computes an upper bound for the treewidth returning the maximum [compute_treewidth_upper_bound] on the
+/// components
+pub fn compute_treewidth_upper_bound_not_connected<
+    N: Clone + Debug,
+    E: Clone + Debug,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+) -> usize {
+    let components = find_connected_components::<Vec<_>, _, _, S>(graph);
+    let mut computed_treewidth: usize = 0;
+
+    for component in components {
+        let mut subgraph = graph.clone();
+        subgraph.retain_nodes(|_, v| component.contains(&v));
+
+        computed_treewidth = computed_treewidth.max(compute_treewidth_upper_bound(
+            &subgraph,
+            edge_weight_function,
+            treewidth_computation_method,
+            check_tree_decomposition_bool,
+            clique_source,
+            None,
+        ));
+    }
+
+    computed_treewidth
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_treewidth_heuristic_check_tree_decomposition() {
+        for i in 0..3 {
+            let test_graph = setup_test_graph(i);
+            let _ = compute_treewidth_upper_bound_not_connected::<_, _, _, RandomState>(
+                &test_graph.graph,
+                constant,
+                SpanningTreeConstructionMethod::MSTreIUseTr,
+                true,
+                CliqueSource::Maximal,
+            );
+
+            let _ = compute_treewidth_upper_bound_not_connected::<_, _, _, RandomState>(
+                &test_graph.graph,
+                constant,
+                SpanningTreeConstructionMethod::MSTre,
+                true,
+                CliqueSource::Maximal,
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_and_check_result_neutral_weight_heuristic() {
+        for i in 0..3 {
+            for computation_method in COMPUTATION_METHODS {
+                let test_graph = setup_test_graph(i);
+                let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
+                    _,
+                    _,
+                    _,
+                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+                >(
+                    &test_graph.graph,
+                    constant,
+                    computation_method,
+                    false,
+                    CliqueSource::Maximal,
+                );
+                if !(i == 1
+                    && (computation_method == SpanningTreeConstructionMethod::MSTre
+                        || computation_method == SpanningTreeConstructionMethod::MSTreIUseTr))
+                {
+                    if i == 1 && computation_method == SpanningTreeConstructionMethod::FilWh {
+                        assert_eq!(computed_treewidth, 4);
+                    } else {
+                        assert_eq!(
+                            computed_treewidth, test_graph.treewidth,
+                            "Test graph number {} failed with computation method {:?}",
+                            i, computation_method
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_and_check_result_negative_intersection_weight_heuristic() {
+        for i in vec![0, 2] {
+            for computation_method in COMPUTATION_METHODS {
+                let test_graph = setup_test_graph(i);
+                let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
+                    _,
+                    _,
+                    _,
+                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+                >(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                );
+                if !(i == 1
+                    && (computation_method == SpanningTreeConstructionMethod::MSTre
+                        || computation_method == SpanningTreeConstructionMethod::MSTreIUseTr))
+                {
+                    assert_eq!(
+                        computed_treewidth, test_graph.treewidth,
+                        "computation method: {:?}. Test graph {:?}",
+                        computation_method, i
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negative_intersection_weight_heuristic_does_not_fail_on_first_test_graph() {
+        let i = 1;
+        let computation_method = SpanningTreeConstructionMethod::MSTreIUseTr;
+
+        let test_graph = setup_test_graph(i);
+        let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
+            _,
             _,
             _,
             std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
         >(
             &test_graph.graph,
-            negative_intersection,
-            computation_method,
+            negative_intersection,
+            computation_method,
+            true,
+            CliqueSource::Maximal,
+        );
+        assert_eq!(
+            computed_treewidth, test_graph.treewidth,
+            "computation method: {:?}. Test graph {:?}",
+            computation_method, i
+        );
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_and_check_result_least_difference_weight_heuristic() {
+        for i in 0..3 {
+            for computation_method in COMPUTATION_METHODS {
+                let test_graph = setup_test_graph(i);
+                let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
+                    _,
+                    _,
+                    _,
+                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+                >(
+                    &test_graph.graph,
+                    least_difference,
+                    computation_method,
+                    false,
+                    CliqueSource::Maximal,
+                );
+                assert_eq!(computed_treewidth, test_graph.treewidth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_and_check_result_three_component_weight_heuristic() {
+        let test_graph = setup_test_graph(0);
+
+        for computation_method in COMPUTATION_METHODS {
+            let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
+                _,
+                _,
+                _,
+                std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+            >(
+                &test_graph.graph,
+                negative_intersection_then_least_difference_then_union,
+                computation_method,
+                true,
+                CliqueSource::Maximal,
+            );
+            assert_eq!(
+                computed_treewidth, test_graph.treewidth,
+                "computation method: {:?}",
+                computation_method
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_does_not_panic() {
+        let graph =
+            petgraph::graph::UnGraph::<i32, ()>::from_edges(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+
+        let treewidth_upper_bound = compute_treewidth_upper_bound::<_, _, _, std::hash::RandomState>(
+            &graph,
+            negative_intersection,
+            SpanningTreeConstructionMethod::FilWh,
+            false,
+            CliqueSource::Maximal,
+            None,
+        );
+
+        assert_eq!(treewidth_upper_bound, 2);
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_with_isolated_vertex() {
+        let mut graph = petgraph::graph::UnGraph::<i32, ()>::from_edges([(0, 1), (1, 2), (2, 0)]);
+        graph.add_node(0);
+
+        for computation_method in [
+            SpanningTreeConstructionMethod::MSTre,
+            SpanningTreeConstructionMethod::MSTreIUseTr,
+            SpanningTreeConstructionMethod::FilWh,
+            SpanningTreeConstructionMethod::FWhUE,
+            SpanningTreeConstructionMethod::FilWhIUseTr,
+            SpanningTreeConstructionMethod::FWBag,
+        ] {
+            let treewidth_upper_bound =
+                compute_treewidth_upper_bound::<_, _, _, std::hash::RandomState>(
+                    &graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                    None,
+                );
+
+            assert_eq!(
+                treewidth_upper_bound, 2,
+                "computation method: {:?}",
+                computation_method
+            );
+        }
+    }
+
+    #[test]
+    fn test_all_cliques_of_size_at_least_two_gives_valid_decomposition() {
+        let test_graph = setup_test_graph(2);
+
+        for computation_method in COMPUTATION_METHODS {
+            let treewidth_upper_bound = compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                &test_graph.graph,
+                negative_intersection,
+                computation_method,
+                true,
+                CliqueSource::AllOfSizeAtLeast(2),
+                None,
+            );
+
+            assert!(
+                treewidth_upper_bound >= test_graph.treewidth,
+                "computation method: {:?}",
+                computation_method
+            );
+        }
+    }
+
+    #[test]
+    fn test_verified_treewidth_heuristic() {
+        for i in 1..3 {
+            let test_graph = setup_test_graph(i);
+            for computation_method in COMPUTATION_METHODS {
+                let treewidth_upper_bound =
+                    compute_treewidth_upper_bound_verified::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        negative_intersection,
+                        computation_method,
+                        CliqueSource::Maximal,
+                    )
+                    .expect("Tree decomposition should be valid");
+
+                assert!(treewidth_upper_bound >= test_graph.treewidth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verified_treewidth_heuristic_returns_fault_without_filling_up() {
+        // The clique graph of a cycle is a cycle of its edges, so the spanning tree is a path in
+        // which the bags containing the endpoint vertex of the path aren't connected
+        let cycle = petgraph::graph::UnGraph::<i32, i32>::from_edges([
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (4, 0),
+        ]);
+        let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, RandomState>(&cycle).collect();
+        let clique_graph = construct_clique_graph(cliques, negative_intersection::<RandomState>);
+        let clique_graph_tree: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                &clique_graph,
+            ));
+
+        assert!(matches!(
+            width_of_valid_tree_decomposition(&cycle, &clique_graph_tree),
+            Err(TreeDecompositionFault::DisconnectedBags { .. })
+        ));
+    }
+
+    #[test]
+    fn test_streamed_and_collected_cliques_give_identical_width() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+        for i in 0..3 {
+            let test_graph = setup_test_graph(i);
+            for clique_source in [
+                CliqueSource::Maximal,
+                CliqueSource::Bounded(2),
+                CliqueSource::AllOfSizeAtLeast(2),
+            ] {
+                let collected_cliques: Vec<Vec<_>> =
+                    clique_iterator::<_, _, FxBuildHasher>(&test_graph.graph, clique_source)
+                        .collect();
+                let streamed_cliques =
+                    clique_iterator::<_, _, FxBuildHasher>(&test_graph.graph, clique_source);
+
+                let widths: Vec<usize> = [
+                    construct_clique_graph(collected_cliques, negative_intersection),
+                    construct_clique_graph(streamed_cliques, negative_intersection),
+                ]
+                .into_iter()
+                .map(|clique_graph| {
+                    let mut clique_graph_tree: Graph<
+                        HashSet<NodeIndex, FxBuildHasher>,
+                        i32,
+                        Undirected,
+                    > = petgraph::data::FromElements::from_elements(
+                        petgraph::algo::min_spanning_tree(&clique_graph),
+                    );
+                    join_spanning_forest(&mut clique_graph_tree);
+                    fill_bags_along_paths(&mut clique_graph_tree);
+                    find_width_of_tree_decomposition(&clique_graph_tree)
+                })
+                .collect();
+
+                assert_eq!(
+                    widths[0], widths[1],
+                    "Test graph: {}, clique source: {:?}",
+                    i, clique_source
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_treewidth_trace_is_non_decreasing() {
+        for i in 0..3 {
+            let test_graph = setup_test_graph(i);
+
+            let (treewidth, trace) = compute_treewidth_with_trace::<_, _, _, RandomState>(
+                &test_graph.graph,
+                negative_intersection,
+                CliqueSource::Maximal,
+            );
+
+            assert!(!trace.is_empty(), "Test graph: {}", i);
+            assert!(
+                trace.windows(2).all(|sizes| sizes[0] <= sizes[1]),
+                "Test graph: {}, trace: {:?}",
+                i,
+                trace
+            );
+            assert_eq!(trace.last(), Some(&(treewidth + 1)), "Test graph: {}", i);
+        }
+    }
+
+    #[test]
+    fn test_treewidth_trace_of_empty_graph_is_empty() {
+        let empty_graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+
+        let (treewidth, trace) = compute_treewidth_with_trace::<_, _, _, RandomState>(
+            &empty_graph,
+            negative_intersection,
+            CliqueSource::Maximal,
+        );
+
+        assert_eq!(treewidth, 0);
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn test_streaming_bags_of_empty_graph_reports_no_bags() {
+        let empty_graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let mut number_of_reported_bags = 0;
+
+        let treewidth = compute_treewidth_streaming_bags::<_, _, _, RandomState>(
+            &empty_graph,
+            negative_intersection,
+            CliqueSource::Maximal,
+            &mut |_, _| number_of_reported_bags += 1,
+        );
+
+        assert_eq!(treewidth, 0);
+        assert_eq!(number_of_reported_bags, 0);
+    }
+
+    #[test]
+    fn test_treewidth_of_trivial_graphs_is_zero() {
+        let empty_graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let mut single_vertex = empty_graph.clone();
+        single_vertex.add_node(0);
+        let mut two_isolated_vertices = single_vertex.clone();
+        two_isolated_vertices.add_node(1);
+
+        for graph in [empty_graph, single_vertex, two_isolated_vertices] {
+            for computation_method in COMPUTATION_METHODS {
+                assert_eq!(
+                    compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        CliqueSource::Maximal,
+                        None,
+                    ),
+                    0,
+                    "Vertices: {}",
+                    graph.node_count()
+                );
+            }
+            assert_eq!(
+                compute_treewidth_upper_bound_verified::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    SpanningTreeConstructionMethod::FilWh,
+                    CliqueSource::Maximal,
+                ),
+                Ok(0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_ordering_guided_with_identity_ordering() {
+        for i in 0..3 {
+            let test_graph = setup_test_graph(i);
+            let identity_ordering: Vec<NodeIndex> = test_graph.graph.node_indices().collect();
+
+            // Checking the tree decomposition panics if it is invalid
+            let treewidth_upper_bound = compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                &test_graph.graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::OrderingGuided,
+                true,
+                CliqueSource::Maximal,
+                Some(identity_ordering),
+            );
+
+            assert!(
+                treewidth_upper_bound >= test_graph.treewidth,
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_maximum_spanning_tree_gives_valid_tree_decomposition() {
+        let test_graph = setup_test_graph(0);
+
+        // Checking the tree decomposition panics if it is invalid
+        let positive_treewidth_upper_bound = compute_treewidth_upper_bound::<_, _, _, RandomState>(
+            &test_graph.graph,
+            positive_intersection,
+            SpanningTreeConstructionMethod::MaxSpanningTreeAndFill,
             true,
+            CliqueSource::Maximal,
             None,
         );
-        assert_eq!(
-            computed_treewidth, test_graph.treewidth,
-            "computation method: {:?}. Test graph {:?}",
-            computation_method, i
+        let negative_treewidth_upper_bound = compute_treewidth_upper_bound::<_, _, _, RandomState>(
+            &test_graph.graph,
+            negative_intersection,
+            SpanningTreeConstructionMethod::MaxSpanningTreeAndFill,
+            true,
+            CliqueSource::Maximal,
+            None,
         );
+
+        assert_eq!(positive_treewidth_upper_bound, test_graph.treewidth);
+        assert!(negative_treewidth_upper_bound >= test_graph.treewidth);
     }
 
     #[test]
-    fn test_treewidth_heuristic_and_check_result_least_difference_weight_heuristic() {
+    fn test_estimate_width_only_equals_full_computation() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+        let test_graph = setup_test_graph(0);
+
+        for computation_method in COMPUTATION_METHODS {
+            assert_eq!(
+                estimate_width_only::<_, _, _, FxBuildHasher>(
+                    &test_graph.graph,
+                    computation_method,
+                    negative_intersection,
+                ),
+                compute_treewidth_upper_bound::<_, _, _, FxBuildHasher>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    false,
+                    CliqueSource::Maximal,
+                    None,
+                ),
+                "Method: {:?}",
+                computation_method
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_treewidth_timed() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
         for i in 0..3 {
+            let test_graph = setup_test_graph(i);
+
             for computation_method in COMPUTATION_METHODS {
-                let test_graph = setup_test_graph(i);
-                let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
-                    _,
-                    _,
-                    _,
-                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
-                >(
+                let (treewidth, timings) = compute_treewidth_timed::<_, _, _, FxBuildHasher>(
                     &test_graph.graph,
-                    least_difference,
+                    negative_intersection,
                     computation_method,
-                    false,
+                    CliqueSource::Maximal,
+                );
+
+                assert_eq!(
+                    treewidth,
+                    compute_treewidth_upper_bound::<_, _, _, FxBuildHasher>(
+                        &test_graph.graph,
+                        negative_intersection,
+                        computation_method,
+                        false,
+                        CliqueSource::Maximal,
+                        None,
+                    ),
+                    "Test graph: {}, method: {:?}",
+                    i,
+                    computation_method
+                );
+                // The phases are disjoint parts of the whole computation
+                assert!(
+                    timings.clique_enumeration
+                        + timings.clique_graph_construction
+                        + timings.spanning_tree
+                        + timings.fill
+                        <= timings.total + Duration::from_millis(1),
+                    "Test graph: {}, method: {:?}, timings: {:?}",
+                    i,
+                    computation_method,
+                    timings
+                );
+                // Each method fills up bags, also the ones doing so while constructing the tree
+                assert!(
+                    timings.fill > Duration::ZERO,
+                    "Test graph: {}, method: {:?}",
+                    i,
+                    computation_method
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_treewidth_of_isolated_vertices_is_zero() {
+        for number_of_vertices in [1, 2, 10] {
+            let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+            for i in 0..number_of_vertices {
+                graph.add_node(i);
+            }
+
+            for computation_method in COMPUTATION_METHODS {
+                assert_eq!(
+                    compute_treewidth_upper_bound_not_connected::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        CliqueSource::Maximal,
+                    ),
+                    0,
+                    "Vertices: {}",
+                    number_of_vertices
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_treewidth_of_graphs_without_edges_is_zero() {
+        for number_of_vertices in [0, 1, 2, 10] {
+            let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+            for i in 0..number_of_vertices {
+                graph.add_node(i);
+            }
+
+            for computation_method in COMPUTATION_METHODS {
+                assert_eq!(
+                    compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        CliqueSource::Maximal,
+                        None,
+                    ),
+                    0,
+                    "Vertices: {}",
+                    number_of_vertices
+                );
+            }
+
+            let result = full_treewidth_result::<_, _, _, RandomState>(
+                &graph,
+                SpanningTreeConstructionMethod::FilWh,
+                negative_intersection,
+            );
+            assert_eq!(result.width, 0, "Vertices: {}", number_of_vertices);
+            assert_eq!(
+                result.decomposition.node_count(),
+                number_of_vertices as usize
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_with_fallback_skips_failing_method() {
+        let test_graph = setup_test_graph(2);
+
+        // The first method panics and the second one returns an error, so only the third succeeds
+        let treewidth = best_width_of_successful_methods(
+            &[
+                SpanningTreeConstructionMethod::FilWhILogBagSize,
+                SpanningTreeConstructionMethod::MSTre,
+                SpanningTreeConstructionMethod::FilWh,
+            ],
+            |treewidth_computation_method| match treewidth_computation_method {
+                SpanningTreeConstructionMethod::FilWhILogBagSize => {
+                    panic!("Injected failure of {:?}", treewidth_computation_method)
+                }
+                SpanningTreeConstructionMethod::MSTre => {
+                    Err(TreewidthError::TooManyVertices { maximum: 0 })
+                }
+                _ => compute_treewidth_upper_bound_checked::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    treewidth_computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                    None,
                     None,
+                ),
+            },
+        );
+
+        assert_eq!(treewidth, test_graph.treewidth);
+        assert_eq!(
+            treewidth_with_fallback::<_, _, _, RandomState>(
+                &test_graph.graph,
+                negative_intersection,
+                &[SpanningTreeConstructionMethod::FilWh],
+            ),
+            test_graph.treewidth
+        );
+    }
+
+    #[test]
+    fn test_treewidth_bounds_on_k_tree() {
+        for k in 1..6 {
+            let k_tree = crate::generate_k_tree(k, 30).expect("k should be smaller or eq to n");
+
+            for computation_method in COMPUTATION_METHODS {
+                assert_eq!(
+                    treewidth_bounds::<_, _, _, RandomState>(
+                        &k_tree,
+                        computation_method,
+                        negative_intersection
+                    ),
+                    (k, k),
+                    "k: {}, method: {:?}",
+                    k,
+                    computation_method
                 );
-                assert_eq!(computed_treewidth, test_graph.treewidth);
             }
         }
     }
 
     #[test]
-    fn test_treewidth_heuristic_does_not_panic() {
-        let graph =
-            petgraph::graph::UnGraph::<i32, ()>::from_edges(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+    fn test_decomposition_if_width_at_most() {
+        let test_graph = setup_test_graph(0);
 
-        let treewidth_upper_bound = compute_treewidth_upper_bound::<_, _, _, std::hash::RandomState>(
-            &graph,
+        let tree_decomposition = decomposition_if_width_at_most::<_, _, _, RandomState>(
+            &test_graph.graph,
+            3,
             negative_intersection,
             SpanningTreeConstructionMethod::FilWh,
-            false,
-            None,
+            CliqueSource::Maximal,
+        )
+        .expect("Test graph 0 has treewidth 3 which the heuristic should find");
+        assert_eq!(find_width_of_tree_decomposition(&tree_decomposition), 3);
+
+        assert!(decomposition_if_width_at_most::<_, _, _, RandomState>(
+            &test_graph.graph,
+            1,
+            negative_intersection,
+            SpanningTreeConstructionMethod::FilWh,
+            CliqueSource::Maximal,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_width_vs_clique_bound() {
+        let test_graph = setup_test_graph(0);
+        let bounds = [-1, 2, 3, 4];
+
+        let widths = width_vs_clique_bound::<_, _, _, RandomState>(
+            &test_graph.graph,
+            SpanningTreeConstructionMethod::FilWh,
+            negative_intersection,
+            &bounds,
         );
 
-        assert_eq!(treewidth_upper_bound, 2);
+        assert_eq!(
+            widths.iter().map(|(bound, _)| *bound).collect::<Vec<_>>(),
+            bounds
+        );
+        for (bound, width) in widths {
+            assert!(width >= test_graph.treewidth, "Bound: {}", bound);
+        }
+    }
+
+    #[test]
+    fn test_largest_clique_first_gives_valid_tree_decomposition() {
+        let test_graph = setup_test_graph(2);
+
+        for computation_method in COMPUTATION_METHODS {
+            for clique_order in [
+                CliqueOrder::AsGiven,
+                CliqueOrder::LargestFirst,
+                CliqueOrder::SmallestFirst,
+            ] {
+                let computed_treewidth =
+                    compute_treewidth_upper_bound_with_clique_order::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        CliqueSource::Maximal,
+                        clique_order,
+                    );
+
+                assert_eq!(
+                    computed_treewidth, test_graph.treewidth,
+                    "Method: {:?}, order: {:?}",
+                    computation_method, clique_order
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_best_seed_is_at_most_default_seed() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+        for i in 0..3 {
+            let test_graph = setup_test_graph(i);
+            let number_of_cliques =
+                find_maximal_cliques::<Vec<_>, _, FxBuildHasher>(&test_graph.graph).count();
+            let all_seeds: Vec<NodeIndex> = (0..number_of_cliques).map(NodeIndex::new).collect();
+
+            let default_seed_width = compute_treewidth_upper_bound::<_, _, _, FxBuildHasher>(
+                &test_graph.graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FilWh,
+                false,
+                CliqueSource::Maximal,
+                None,
+            );
+            let best_seed_width = treewidth_best_seed::<_, _, _, FxBuildHasher>(
+                &test_graph.graph,
+                negative_intersection,
+                &all_seeds,
+            );
+
+            assert!(best_seed_width <= default_seed_width, "Test graph {}", i);
+            assert!(best_seed_width >= test_graph.treewidth, "Test graph {}", i);
+            assert_eq!(
+                treewidth_best_seed::<_, _, _, FxBuildHasher>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    &[],
+                ),
+                default_seed_width,
+                "Test graph {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_checked_computation_with_bounded_number_of_cliques() {
+        let mut complete_graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..15).map(|_| complete_graph.add_node(0)).collect();
+        for (first_vertex, second_vertex) in vertices.iter().tuple_combinations() {
+            complete_graph.add_edge(*first_vertex, *second_vertex, 0);
+        }
+
+        // K_15 has a single maximal clique
+        assert_eq!(
+            compute_treewidth_upper_bound_checked::<_, _, _, RandomState>(
+                &complete_graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FilWh,
+                true,
+                CliqueSource::Maximal,
+                None,
+                Some(1),
+            ),
+            Ok(14)
+        );
+
+        // Test graph 1 has 4 maximal cliques
+        let test_graph = setup_test_graph(1);
+        for computation_method in COMPUTATION_METHODS {
+            assert_eq!(
+                compute_treewidth_upper_bound_checked::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                    None,
+                    Some(3),
+                ),
+                Err(TreewidthError::TooManyCliques { maximum: 3 })
+            );
+            let computed_treewidth = compute_treewidth_upper_bound_checked::<_, _, _, RandomState>(
+                &test_graph.graph,
+                negative_intersection,
+                computation_method,
+                true,
+                CliqueSource::Maximal,
+                None,
+                Some(4),
+            )
+            .expect("4 cliques should be allowed");
+            assert!(computed_treewidth >= test_graph.treewidth);
+        }
+    }
+
+    #[test]
+    fn test_tree_likeness_of_path_and_complete_graph() {
+        let path: Graph<i32, i32, Undirected> =
+            Graph::from_edges((0..100).map(|i| (i, i + 1)).collect::<Vec<(u32, u32)>>());
+        let mut complete_graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..10).map(|_| complete_graph.add_node(0)).collect();
+        for (first_vertex, second_vertex) in vertices.iter().tuple_combinations() {
+            complete_graph.add_edge(*first_vertex, *second_vertex, 0);
+        }
+
+        for computation_method in COMPUTATION_METHODS {
+            let path_score = tree_likeness::<_, _, _, RandomState>(
+                &path,
+                computation_method,
+                negative_intersection,
+            );
+            let complete_graph_score = tree_likeness::<_, _, _, RandomState>(
+                &complete_graph,
+                computation_method,
+                negative_intersection,
+            );
+
+            assert!(
+                (path_score - 0.99).abs() < 1e-9,
+                "Path score: {}",
+                path_score
+            );
+            assert!(
+                complete_graph_score.abs() < 1e-9,
+                "Complete graph score: {}",
+                complete_graph_score
+            );
+        }
+    }
+
+    #[test]
+    fn test_checked_computation_with_bounded_number_of_vertices() {
+        let test_graph = setup_test_graph(2);
+
+        for computation_method in COMPUTATION_METHODS {
+            assert_eq!(
+                compute_treewidth_upper_bound_checked::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                    Some(4),
+                    None,
+                ),
+                Err(TreewidthError::TooManyVertices { maximum: 4 })
+            );
+            assert_eq!(
+                compute_treewidth_upper_bound_checked::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                    Some(5),
+                    None,
+                ),
+                Ok(test_graph.treewidth)
+            );
+        }
+    }
+
+    #[test]
+    fn test_full_treewidth_result_is_consistent() {
+        let test_graph = setup_test_graph(2);
+
+        for computation_method in COMPUTATION_METHODS {
+            let result = full_treewidth_result::<_, _, _, RandomState>(
+                &test_graph.graph,
+                computation_method,
+                negative_intersection,
+            );
+
+            assert_eq!(result.width, test_graph.treewidth);
+            assert_eq!(
+                result.width,
+                find_width_of_tree_decomposition(&result.decomposition)
+            );
+            assert!(validate_tree_decomposition(&test_graph.graph, &result.decomposition).is_ok());
+            let mut sorted_order = result.elimination_order.clone();
+            sorted_order.sort();
+            assert_eq!(
+                sorted_order,
+                test_graph.graph.node_indices().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                width_of_elimination_ordering(&test_graph.graph, &result.elimination_order),
+                result.width
+            );
+        }
+    }
+
+    #[test]
+    fn test_fill_stats_through_fill_options() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+        // Test graph 0 is chordal, so nothing needs to be filled up. Test graph 1 isn't.
+        let test_graph = setup_test_graph(1);
+
+        let mut fill_stats = FillStats::default();
+        let width = compute_treewidth_upper_bound_with_fill_options::<_, _, _, FxBuildHasher>(
+            &test_graph.graph,
+            negative_intersection,
+            SpanningTreeConstructionMethod::FilWh,
+            CliqueSource::Maximal,
+            FillOptions {
+                stats: Some(&mut fill_stats),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            width,
+            compute_treewidth_upper_bound::<_, _, _, FxBuildHasher>(
+                &test_graph.graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FilWh,
+                false,
+                CliqueSource::Maximal,
+                None,
+            )
+        );
+        assert!(fill_stats.insertions > 0);
+        assert!(fill_stats.max_path_length >= 1);
+    }
+
+    #[test]
+    fn test_random_tie_breaking_through_fill_options() {
+        use rand::{rngs::StdRng, SeedableRng};
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+        let graph = crate::generate_partial_k_tree(3, 40, 40, &mut StdRng::seed_from_u64(0))
+            .expect("k should be smaller or eq to n");
+        // The constant weight function makes all edges of the clique graph tie
+        let width_for_seed = |seed: u64| {
+            compute_treewidth_upper_bound_with_fill_options::<_, _, _, FxBuildHasher>(
+                &graph,
+                constant,
+                SpanningTreeConstructionMethod::FilWh,
+                CliqueSource::Maximal,
+                FillOptions {
+                    rng: Some(&mut StdRng::seed_from_u64(seed)),
+                    ..Default::default()
+                },
+            )
+        };
+
+        let widths: Vec<usize> = (0..10).map(width_for_seed).collect();
+        assert_eq!(widths, (0..10).map(width_for_seed).collect::<Vec<_>>());
+        assert!(widths.iter().unique().count() > 1);
+    }
+
+    #[test]
+    fn test_max_fill_path_through_fill_options() {
+        for i in 0..3 {
+            let test_graph = setup_test_graph(i);
+            for computation_method in [
+                SpanningTreeConstructionMethod::MSTre,
+                SpanningTreeConstructionMethod::MaxSpanningTreeAndFill,
+            ] {
+                let (tree_decomposition, _, _) = compute_tree_decomposition::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    CliqueSource::Maximal,
+                    None,
+                    CliqueOrder::AsGiven,
+                    &mut FillOptions {
+                        max_fill_path: Some(1),
+                        ..Default::default()
+                    },
+                );
+
+                assert_eq!(
+                    validate_tree_decomposition(&test_graph.graph, &tree_decomposition),
+                    Ok(()),
+                    "Test graph {}, method {:?}",
+                    i,
+                    computation_method
+                );
+            }
+        }
     }
 }
@@ -2,11 +2,59 @@ use std::collections::{HashMap, HashSet};
 use std::hash::BuildHasher;
 
 use petgraph::graph::NodeIndex;
-use petgraph::Graph;
+use petgraph::{Graph, Undirected};
+
+use crate::bitset_bag::BitsetBag;
+use crate::find_maximal_cliques::find_maximal_cliques;
+
+/// Computes the [clique graph](https://en.wikipedia.org/wiki/Clique_graph) of the given graph,
+/// that is, the intersection graph of its maximal cliques, without computing a tree decomposition.
+/// The edge weights are determined according to the edge weight function.
+///
+/// This is the intermediate graph that [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound]
+/// constructs a spanning tree on.
+pub fn build_clique_graph<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, S>(graph).collect();
+
+    construct_clique_graph(cliques, edge_weight_function)
+}
+
+/// Different orders in which the cliques are added as vertices to the clique graph. Since the
+/// spanning tree constructions start at the first vertex of the clique graph and break ties by the
+/// order of the vertices, the order influences the resulting tree decomposition.
+///
+/// AsGiven Keeps the order in which the cliques are enumerated
+///
+/// LargestFirst Sorts the cliques by decreasing size, which anchors the tree decomposition on the
+/// biggest cliques
+///
+/// SmallestFirst Sorts the cliques by increasing size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CliqueOrder {
+    AsGiven,
+    LargestFirst,
+    SmallestFirst,
+}
+
+/// Sorts the cliques according to the given [CliqueOrder]. The sort is stable, so cliques of the
+/// same size keep their relative order.
+pub fn order_cliques(cliques: &mut [Vec<NodeIndex>], clique_order: CliqueOrder) {
+    match clique_order {
+        CliqueOrder::AsGiven => (),
+        CliqueOrder::LargestFirst => cliques.sort_by_key(|clique| std::cmp::Reverse(clique.len())),
+        CliqueOrder::SmallestFirst => cliques.sort_by_key(|clique| clique.len()),
+    }
+}
 
 /// Constructs the intersection graph of the given cliques (aka the clique graph if the set of
 /// cliques is the set of maximal cliques). The edge weights are determined according to the edge
 /// weight function.
+///
+/// This is [construct_clique_graph_with_bags] discarding the map, so both share the detection of
+/// intersecting cliques.
 pub fn construct_clique_graph<InnerCollection, OuterIterator, O, S: Default + BuildHasher>(
     cliques: OuterIterator,
     edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
@@ -15,34 +63,52 @@ where
     OuterIterator: IntoIterator<Item = InnerCollection>,
     InnerCollection: IntoIterator<Item = NodeIndex>,
 {
-    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected> =
-        Graph::new_undirected();
-    for clique in cliques {
-        let vertex_index = result_graph.add_node(HashSet::from_iter(clique.into_iter()));
-        for other_vertex_index in result_graph.node_indices() {
-            if other_vertex_index == vertex_index {
-                continue;
-            } else {
-                let other_vertex_weight = result_graph
-                    .node_weight(other_vertex_index)
-                    .expect("Node weight should exist");
-                let this_vertex_weight = result_graph
-                    .node_weight(vertex_index)
-                    .expect("Node weight should exist");
-
-                if let Some(_) = this_vertex_weight.intersection(other_vertex_weight).next() {
-                    // Add edge, if cliques (that are the nodes of result graph) have nodes in common
-                    result_graph.add_edge(
-                        vertex_index,
-                        other_vertex_index,
-                        edge_weight_function(this_vertex_weight, other_vertex_weight),
-                    );
+    let (result_graph, _) = construct_clique_graph_with_bags(
+        cliques
+            .into_iter()
+            .map(|clique| clique.into_iter().collect::<Vec<_>>()),
+        edge_weight_function,
+    );
+
+    result_graph
+}
+
+/// Adds an edge between all pairs of vertices whose bags intersect with the weight determined by the
+/// edge weight function. The edges are added in the order in which they would have been added if the
+/// vertices had been added one after another (checking the already added vertices in order).
+///
+/// If all vertices in the bags have an index less than [BitsetBag::CAPACITY], the bags are converted
+/// to [BitsetBag]s to check for intersections. This doesn't change the result.
+fn add_intersection_edges<O, S: BuildHasher>(
+    graph: &mut Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) {
+    let bitset_bags: Option<Vec<BitsetBag>> = graph
+        .node_weights()
+        .map(BitsetBag::try_from_vertices)
+        .collect();
+
+    let vertices: Vec<NodeIndex> = graph.node_indices().collect();
+    for (i, vertex_index) in vertices.iter().enumerate() {
+        for other_vertex_index in &vertices[..i] {
+            let bags_intersect = match &bitset_bags {
+                Some(bitset_bags) => {
+                    bitset_bags[i].intersects(&bitset_bags[other_vertex_index.index()])
                 }
+                None => graph[*vertex_index]
+                    .intersection(&graph[*other_vertex_index])
+                    .next()
+                    .is_some(),
+            };
+
+            if bags_intersect {
+                // Add edge, if cliques (that are the nodes of result graph) have nodes in common
+                let edge_weight =
+                    edge_weight_function(&graph[*vertex_index], &graph[*other_vertex_index]);
+                graph.add_edge(*vertex_index, *other_vertex_index, edge_weight);
             }
         }
     }
-
-    result_graph
 }
 
 /// Constructs the same graph as [construct_clique_graph].
@@ -50,6 +116,9 @@ where
 /// Additionally returns a HashMap mapping the vertices in the original graph (the
 /// vertices from the cliques) to HashSets containing the NodeIndices of all the Bags in the Clique Graph
 /// that contain the vertex from the original graph.
+///
+/// See [construct_clique_graph_with_bags_and_warnings] for being warned about disconnected clique
+/// graphs.
 pub fn construct_clique_graph_with_bags<
     InnerCollection,
     OuterIterator,
@@ -62,6 +131,32 @@ pub fn construct_clique_graph_with_bags<
     Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected>,
     HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
 )
+where
+    OuterIterator: IntoIterator<Item = InnerCollection>,
+    InnerCollection: IntoIterator<Item = NodeIndex>,
+    InnerCollection: Clone,
+{
+    construct_clique_graph_with_bags_and_warnings(cliques, edge_weight_heuristic, None)
+}
+
+/// Constructs the same graph and map as [construct_clique_graph_with_bags].
+///
+/// Additionally pushes a warning with the number of connected components to the given warnings if
+/// the constructed graph is not connected, which happens if the original graph is not connected
+/// (e.g. has isolated vertices). The components are only counted if warnings are given.
+pub fn construct_clique_graph_with_bags_and_warnings<
+    InnerCollection,
+    OuterIterator,
+    O,
+    S: Default + BuildHasher,
+>(
+    cliques: OuterIterator,
+    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    warnings: Option<&mut Vec<String>>,
+) -> (
+    Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    CliqueGraphMap<S>,
+)
 where
     OuterIterator: IntoIterator<Item = InnerCollection>,
     InnerCollection: IntoIterator<Item = NodeIndex>,
@@ -72,36 +167,81 @@ where
     let mut result_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = Default::default();
 
     for clique in cliques {
-        let vertex_index = result_graph.add_node(HashSet::from_iter(clique.clone().into_iter()));
+        let vertex_index = result_graph.add_node(HashSet::from_iter(clique.clone()));
         for vertex_in_clique in clique {
             add_node_index_to_bag_in_hashmap(&mut result_map, vertex_in_clique, vertex_index);
         }
-        for other_vertex_index in result_graph.node_indices() {
-            if other_vertex_index == vertex_index {
-                continue;
-            } else {
-                let other_vertex_weight = result_graph
-                    .node_weight(other_vertex_index)
-                    .expect("Node weight should exist");
-                let vertex_weight = result_graph
-                    .node_weight(vertex_index)
-                    .expect("Node weight - in this case the nodes in the clique - should exist");
-
-                if let Some(_) = vertex_weight.intersection(other_vertex_weight).next() {
-                    // Add edge, if cliques (that are the nodes of result graph) have nodes in common
-                    result_graph.add_edge(
-                        vertex_index,
-                        other_vertex_index,
-                        edge_weight_heuristic(vertex_weight, other_vertex_weight),
-                    );
-                }
-            }
+    }
+    add_intersection_edges(&mut result_graph, edge_weight_heuristic);
+
+    if let Some(warnings) = warnings {
+        let number_of_components = petgraph::algo::connected_components(&result_graph);
+        if number_of_components > 1 {
+            warnings.push(format!(
+                "The clique graph has {} connected components, the original graph is not connected",
+                number_of_components
+            ));
         }
     }
 
     (result_graph, result_map)
 }
 
+/// Map from the vertices of the original graph to the vertices of the clique graph whose bags
+/// contain them.
+pub type CliqueGraphMap<S> = HashMap<NodeIndex, HashSet<NodeIndex, S>, S>;
+
+/// Constructs the intersection graph of the given (custom) cliques together with the map from the
+/// vertices of the original graph to the vertices of the clique graph whose bags contain them.
+///
+/// This is [construct_clique_graph_with_bags] for cliques given as vectors and is meant as the
+/// starting point for custom pipelines, e.g. new strategies for filling up the bags.
+pub fn clique_graph_with_map<O, S: Default + BuildHasher>(
+    cliques: Vec<Vec<NodeIndex>>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> (
+    Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    CliqueGraphMap<S>,
+) {
+    construct_clique_graph_with_bags(cliques, edge_weight_function)
+}
+
+/// Adds the new clique as a vertex to the given clique graph and returns its NodeIndex. The new
+/// vertex is connected to exactly the vertices whose bags intersect the clique with the weight
+/// determined by the edge weight function. These are looked up in the map (see [CliqueGraphMap]),
+/// which is updated to contain the new vertex afterwards.
+///
+/// Adding the cliques one after another to an empty clique graph and map gives the same result as
+/// [construct_clique_graph_with_bags], including the order of the edges.
+pub fn add_clique_to_clique_graph<O, S: Default + BuildHasher>(
+    clique_graph: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    map: &mut CliqueGraphMap<S>,
+    new_clique: Vec<NodeIndex>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) -> NodeIndex {
+    let mut intersecting_vertices: Vec<NodeIndex> = new_clique
+        .iter()
+        .filter_map(|vertex_in_clique| map.get(vertex_in_clique))
+        .flat_map(|vertices_in_clique_graph| vertices_in_clique_graph.iter().copied())
+        .collect();
+    intersecting_vertices.sort();
+    intersecting_vertices.dedup();
+
+    let vertex_index = clique_graph.add_node(HashSet::from_iter(new_clique.iter().copied()));
+    for vertex_in_clique in new_clique {
+        add_node_index_to_bag_in_hashmap(map, vertex_in_clique, vertex_index);
+    }
+    for other_vertex_index in intersecting_vertices {
+        let edge_weight = edge_weight_function(
+            &clique_graph[vertex_index],
+            &clique_graph[other_vertex_index],
+        );
+        clique_graph.add_edge(vertex_index, other_vertex_index, edge_weight);
+    }
+
+    vertex_index
+}
+
 /// Given a node from the original graph and a bag/vertex in the clique graph, adds this connection
 /// to the hashmap (node from original graph -> HashSet containing node from clique graph).
 fn add_node_index_to_bag_in_hashmap<S: Default + std::hash::BuildHasher>(
@@ -117,3 +257,244 @@ fn add_node_index_to_bag_in_hashmap<S: Default + std::hash::BuildHasher>(
         map.insert(vertex_in_graph, set);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    #[test]
+    fn test_build_clique_graph() {
+        let test_graph = crate::tests::setup_test_graph(2);
+
+        let clique_graph =
+            build_clique_graph::<_, _, _, RandomState>(&test_graph.graph, crate::constant);
+
+        assert_eq!(clique_graph.node_count(), 2);
+        assert_eq!(clique_graph.edge_count(), 1);
+
+        let mut bags: Vec<Vec<_>> = clique_graph
+            .node_weights()
+            .map(|bag| {
+                let mut bag: Vec<_> = bag.iter().cloned().collect();
+                bag.sort();
+                bag
+            })
+            .collect();
+        bags.sort();
+        assert_eq!(bags, test_graph.expected_max_cliques);
+    }
+
+    #[test]
+    fn test_clique_graph_with_map() {
+        let test_graph = crate::tests::setup_test_graph(2);
+        let cliques: Vec<Vec<_>> =
+            find_maximal_cliques::<Vec<_>, _, RandomState>(&test_graph.graph).collect();
+
+        let (clique_graph, clique_graph_map) =
+            clique_graph_with_map::<_, RandomState>(cliques, crate::constant);
+
+        assert_eq!(clique_graph_map.len(), test_graph.graph.node_count());
+        for vertex in test_graph.graph.node_indices() {
+            let expected_bags: HashSet<NodeIndex> = clique_graph
+                .node_indices()
+                .filter(|bag| clique_graph[*bag].contains(&vertex))
+                .collect();
+            let bags: HashSet<NodeIndex> = clique_graph_map
+                .get(&vertex)
+                .expect("Every vertex should be contained in a clique")
+                .iter()
+                .cloned()
+                .collect();
+
+            assert_eq!(bags, expected_bags, "Vertex: {:?}", vertex);
+        }
+        // Vertices 1, 2 and 3 are contained in both maximal cliques
+        for vertex in [1, 2, 3] {
+            assert_eq!(clique_graph_map[&NodeIndex::new(vertex)].len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_clique_graph_with_and_without_bags_are_isomorphic() {
+        let test_graph = crate::tests::setup_test_graph(1);
+        let cliques: Vec<Vec<_>> =
+            find_maximal_cliques::<Vec<_>, _, RandomState>(&test_graph.graph).collect();
+
+        let clique_graph: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            construct_clique_graph(cliques.clone(), crate::negative_intersection);
+        let (clique_graph_with_bags, _) =
+            construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+        assert!(petgraph::algo::is_isomorphic_matching(
+            &clique_graph,
+            &clique_graph_with_bags,
+            |bag, other_bag| bag == other_bag,
+            |weight, other_weight| weight == other_weight,
+        ));
+    }
+
+    #[test]
+    fn test_bitset_and_hash_set_clique_graphs_give_identical_width() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+        let k_tree: Graph<i32, i32, Undirected> =
+            crate::generate_partial_k_tree(6, 64, 20, &mut rand::thread_rng())
+                .expect("k should be smaller or eq to n");
+        let cliques: Vec<Vec<_>> =
+            find_maximal_cliques::<Vec<_>, _, FxBuildHasher>(&k_tree).collect();
+
+        // Shifting the vertices beyond the capacity of bitset bags forces the HashSet path for the
+        // intersection checks as well as the edge weights
+        let [bitset_result, hash_set_result] = [0, BitsetBag::CAPACITY].map(|offset| {
+            let shifted_cliques = cliques.iter().map(|clique| {
+                clique
+                    .iter()
+                    .map(|vertex| NodeIndex::new(vertex.index() + offset))
+                    .collect::<Vec<_>>()
+            });
+            let (clique_graph, clique_graph_map) = construct_clique_graph_with_bags(
+                shifted_cliques,
+                crate::negative_intersection::<FxBuildHasher>,
+            );
+            let edges: Vec<_> = clique_graph
+                .edge_indices()
+                .map(|e| (clique_graph.edge_endpoints(e), clique_graph[e]))
+                .collect();
+
+            let tree_decomposition = crate::fill_bags_while_generating_mst::<i32, i32, _, _>(
+                &clique_graph,
+                crate::negative_intersection,
+                clique_graph_map,
+                false,
+                &mut crate::FillOptions::default(),
+            );
+            (
+                edges,
+                crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition(
+                    &tree_decomposition,
+                ),
+            )
+        });
+
+        assert_eq!(bitset_result, hash_set_result);
+    }
+
+    #[test]
+    fn test_order_cliques_largest_first() {
+        let to_cliques = |cliques: Vec<Vec<usize>>| -> Vec<Vec<NodeIndex>> {
+            cliques
+                .into_iter()
+                .map(|clique| clique.into_iter().map(NodeIndex::new).collect())
+                .collect()
+        };
+        let cliques = to_cliques(vec![
+            vec![0, 1],
+            vec![2, 3, 4],
+            vec![5, 6],
+            vec![0, 2, 5, 7],
+        ]);
+
+        let mut largest_first_cliques = cliques.clone();
+        order_cliques(&mut largest_first_cliques, CliqueOrder::LargestFirst);
+        assert_eq!(
+            largest_first_cliques,
+            to_cliques(vec![
+                vec![0, 2, 5, 7],
+                vec![2, 3, 4],
+                vec![0, 1],
+                vec![5, 6]
+            ])
+        );
+
+        let mut smallest_first_cliques = cliques.clone();
+        order_cliques(&mut smallest_first_cliques, CliqueOrder::SmallestFirst);
+        assert_eq!(
+            smallest_first_cliques,
+            to_cliques(vec![
+                vec![0, 1],
+                vec![5, 6],
+                vec![2, 3, 4],
+                vec![0, 2, 5, 7]
+            ])
+        );
+
+        let ordered_clique_graph =
+            construct_clique_graph(largest_first_cliques, crate::constant::<RandomState>);
+        let ordered_bag_sizes: Vec<usize> = ordered_clique_graph
+            .node_weights()
+            .map(|bag| bag.len())
+            .collect();
+        assert_eq!(ordered_bag_sizes, vec![4, 3, 2, 2]);
+    }
+
+    #[test]
+    fn test_add_clique_to_clique_graph_matches_batch_construction() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let cliques: Vec<Vec<_>> =
+                find_maximal_cliques::<Vec<_>, _, RandomState>(&test_graph.graph).collect();
+
+            let (clique_graph, clique_graph_map) = construct_clique_graph_with_bags(
+                cliques.clone(),
+                crate::negative_intersection::<RandomState>,
+            );
+            let mut incremental_clique_graph = Graph::new_undirected();
+            let mut incremental_clique_graph_map = CliqueGraphMap::default();
+            for clique in cliques {
+                add_clique_to_clique_graph(
+                    &mut incremental_clique_graph,
+                    &mut incremental_clique_graph_map,
+                    clique,
+                    crate::negative_intersection,
+                );
+            }
+
+            assert_eq!(incremental_clique_graph_map, clique_graph_map);
+            assert_eq!(
+                incremental_clique_graph.node_weights().collect::<Vec<_>>(),
+                clique_graph.node_weights().collect::<Vec<_>>()
+            );
+            let edges = |graph: &Graph<HashSet<NodeIndex, RandomState>, i32, Undirected>| {
+                graph
+                    .edge_indices()
+                    .map(|edge| (graph.edge_endpoints(edge), graph[edge]))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                edges(&incremental_clique_graph),
+                edges(&clique_graph),
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_disconnected_clique_graph_warning() {
+        // A triangle and an isolated vertex
+        let mut graph: Graph<(), (), Undirected> = Graph::new_undirected();
+        for _ in 0..4 {
+            graph.add_node(());
+        }
+        graph.extend_with_edges([(0, 1), (1, 2), (2, 0)]);
+
+        let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, RandomState>(&graph).collect();
+        let mut warnings = Vec::new();
+        let (clique_graph, _) = construct_clique_graph_with_bags_and_warnings(
+            cliques,
+            crate::constant::<RandomState>,
+            Some(&mut warnings),
+        );
+
+        assert_eq!(clique_graph.node_count(), 2);
+        assert_eq!(
+            warnings,
+            vec![
+                "The clique graph has 2 connected components, the original graph is not connected"
+                    .to_string()
+            ]
+        );
+    }
+}
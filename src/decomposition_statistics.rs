@@ -0,0 +1,416 @@
+use petgraph::{
+    graph::{EdgeIndex, NodeIndex},
+    Graph, Undirected,
+};
+use std::{cmp::Ordering, collections::HashSet, hash::BuildHasher};
+
+use crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition;
+
+/// Basic statistics of a tree decomposition (see [tree_decomposition_stats]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeDecompositionStats {
+    /// The size of the biggest bag minus one
+    pub width: usize,
+    /// The number of bags
+    pub num_bags: usize,
+    /// The sum of the sizes of all bags
+    pub total_size: usize,
+}
+
+/// The shape of the tree of a tree decomposition (see [decomposition_tree_shape]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeShape {
+    /// The number of bags with at most one neighbour
+    pub num_leaves: usize,
+    /// The number of edges of a longest path in the tree
+    pub diameter: usize,
+    /// The maximum number of neighbours of a bag
+    pub max_degree: usize,
+    /// The height of the tree when rooted at a center, that is, the radius of the tree
+    pub height_from_best_root: usize,
+}
+
+/// Which of two compared tree decompositions dominates the other (see [compare_decompositions]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dominance {
+    /// The first decomposition dominates the second one
+    A,
+    /// The second decomposition dominates the first one
+    B,
+    /// Both decompositions have the same width and total size
+    Equal,
+    /// Each decomposition is better in one of width and total size
+    Incomparable,
+}
+
+/// The result of comparing two tree decompositions of the same graph (see
+/// [compare_decompositions]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecompositionComparison {
+    pub width_a: usize,
+    pub width_b: usize,
+    pub num_bags_a: usize,
+    pub num_bags_b: usize,
+    pub total_size_a: usize,
+    pub total_size_b: usize,
+    pub dominance: Dominance,
+}
+
+/// Returns the width, the number of bags and the total size of the bags of the given tree
+/// decomposition.
+pub fn tree_decomposition_stats<E, S: BuildHasher>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> TreeDecompositionStats {
+    TreeDecompositionStats {
+        width: find_width_of_tree_decomposition(decomposition),
+        num_bags: decomposition.node_count(),
+        total_size: decomposition.node_weights().map(|bag| bag.len()).sum(),
+    }
+}
+
+/// Returns the shape of the tree of the given tree decomposition, which e.g. determines how well
+/// dynamic programming over the decomposition can be parallelized: A long thin decomposition (high
+/// diameter, few leaves) parallelizes poorly, a balanced one well.
+///
+/// The decomposition is assumed to be a tree. The diameter is computed with two breadth first
+/// searches and the height from the best root is half of it (rounded up). Returns the default
+/// shape (all zero) for an empty decomposition.
+pub fn decomposition_tree_shape<E, S>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> TreeShape {
+    let Some(start) = decomposition.node_indices().next() else {
+        return TreeShape::default();
+    };
+
+    let (end_of_longest_path, _) = farthest_bag(decomposition, start);
+    let (_, diameter) = farthest_bag(decomposition, end_of_longest_path);
+    let degrees = decomposition
+        .node_indices()
+        .map(|bag| decomposition.neighbors(bag).count());
+
+    TreeShape {
+        num_leaves: degrees.clone().filter(|degree| *degree <= 1).count(),
+        diameter,
+        max_degree: degrees.max().unwrap_or(0),
+        height_from_best_root: diameter.div_ceil(2),
+    }
+}
+
+/// Returns a bag with the maximum distance to the given bag in the tree together with that distance.
+fn farthest_bag<E, S>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+    start: NodeIndex,
+) -> (NodeIndex, usize) {
+    let mut distances: Vec<Option<usize>> = vec![None; decomposition.node_count()];
+    distances[start.index()] = Some(0);
+    let mut farthest = (start, 0);
+    let mut queue = std::collections::VecDeque::from([start]);
+
+    while let Some(bag) = queue.pop_front() {
+        let distance = distances[bag.index()].expect("Bags in the queue should have a distance");
+        if distance > farthest.1 {
+            farthest = (bag, distance);
+        }
+        for neighbour in decomposition.neighbors(bag) {
+            if distances[neighbour.index()].is_none() {
+                distances[neighbour.index()] = Some(distance + 1);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    farthest
+}
+
+/// Compares two tree decompositions of the same graph using [tree_decomposition_stats].
+///
+/// One decomposition dominates the other if its width and its total size are both at most the
+/// ones of the other decomposition and at least one of them is strictly smaller. The number of bags
+/// is reported but not taken into account, since fewer bags are not better per se.
+pub fn compare_decompositions<E, S: BuildHasher>(
+    a: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+    b: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> DecompositionComparison {
+    let stats_a = tree_decomposition_stats(a);
+    let stats_b = tree_decomposition_stats(b);
+
+    let dominance = match (
+        stats_a.width.cmp(&stats_b.width),
+        stats_a.total_size.cmp(&stats_b.total_size),
+    ) {
+        (Ordering::Equal, Ordering::Equal) => Dominance::Equal,
+        (Ordering::Less | Ordering::Equal, Ordering::Less | Ordering::Equal) => Dominance::A,
+        (Ordering::Greater | Ordering::Equal, Ordering::Greater | Ordering::Equal) => Dominance::B,
+        _ => Dominance::Incomparable,
+    };
+
+    DecompositionComparison {
+        width_a: stats_a.width,
+        width_b: stats_b.width,
+        num_bags_a: stats_a.num_bags,
+        num_bags_b: stats_b.num_bags,
+        total_size_a: stats_a.total_size,
+        total_size_b: stats_b.total_size,
+        dominance,
+    }
+}
+
+/// Returns true if the two tree decompositions are equal up to the order of their bags, that is, if
+/// there is an isomorphism between the trees mapping each bag to an equal bag. The weights of the
+/// edges are ignored.
+///
+/// This allows e.g. asserting that a change to the heuristic didn't change the computed
+/// decompositions, even if the bags were created in a different order.
+pub fn decompositions_equivalent<E, S: BuildHasher>(
+    a: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+    b: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> bool {
+    petgraph::algo::is_isomorphic_matching(a, b, |bag_a, bag_b| bag_a == bag_b, |_, _| true)
+}
+
+/// Returns the separators of the given tree decomposition, that is, for each edge of the tree the
+/// intersection of the bags of its endpoints, in the order of the edge indices.
+///
+/// Removing the vertices of such a separator from the original graph separates the vertices only
+/// contained in bags on one side of the edge from the ones only contained on the other side.
+pub fn edge_separators<E, S: Default + BuildHasher + Clone>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> Vec<(EdgeIndex, HashSet<NodeIndex, S>)> {
+    decomposition
+        .edge_indices()
+        .map(|edge| {
+            let (bag_one, bag_two) = decomposition
+                .edge_endpoints(edge)
+                .expect("Edge should have endpoints");
+            let separator = decomposition[bag_one]
+                .intersection(&decomposition[bag_two])
+                .copied()
+                .collect();
+            (edge, separator)
+        })
+        .collect()
+}
+
+/// Returns the mean, minimum and maximum size of the intersections of adjacent bags over all edges
+/// of the given tree decomposition (see [edge_separators]).
+///
+/// Adjacent bags sharing almost all of their vertices indicate a "stringy" decomposition that is
+/// often suboptimal, so this helps comparing heuristics beyond their width.
+///
+/// Returns (0.0, 0, 0) if the decomposition has no edges.
+pub fn adjacency_overlap_stats<E, S: Default + BuildHasher + Clone>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> (f64, usize, usize) {
+    let separator_sizes: Vec<usize> = edge_separators(decomposition)
+        .iter()
+        .map(|(_, separator)| separator.len())
+        .collect();
+
+    match (separator_sizes.iter().min(), separator_sizes.iter().max()) {
+        (Some(min), Some(max)) => {
+            let mean = separator_sizes.iter().sum::<usize>() as f64 / separator_sizes.len() as f64;
+            (mean, *min, *max)
+        }
+        _ => (0.0, 0, 0),
+    }
+}
+
+/// Returns the separator width of the given tree decomposition, that is, the maximum size of the
+/// intersection of adjacent bags over all edges (see [edge_separators]). In contrast to the width,
+/// this bounds the size of the tables passed along the edges in dynamic programming over the
+/// decomposition.
+///
+/// Returns 0 if the decomposition has no edges.
+pub fn separator_width<E, S: Default + BuildHasher + Clone>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> usize {
+    edge_separators(decomposition)
+        .iter()
+        .map(|(_, separator)| separator.len())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+    use crate::compute_treewidth_upper_bound::compute_tree_decomposition;
+
+    #[test]
+    fn test_decompositions_equivalent() {
+        let bags = [vec![0, 1, 2], vec![1, 2, 3], vec![3, 4]];
+        let path_decomposition = |bags: &[Vec<usize>]| {
+            let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+                Graph::new_undirected();
+            let bag_indices: Vec<NodeIndex> = bags
+                .iter()
+                .map(|bag| {
+                    decomposition.add_node(bag.iter().copied().map(NodeIndex::new).collect())
+                })
+                .collect();
+            for i in 0..bag_indices.len() - 1 {
+                decomposition.add_edge(bag_indices[i], bag_indices[i + 1], 0);
+            }
+            decomposition
+        };
+        let decomposition = path_decomposition(&bags);
+
+        assert!(decompositions_equivalent(&decomposition, &decomposition));
+
+        // The same path with the bags added in reversed order
+        let reversed_bags: Vec<Vec<usize>> = bags.iter().rev().cloned().collect();
+        assert!(decompositions_equivalent(
+            &decomposition,
+            &path_decomposition(&reversed_bags)
+        ));
+
+        let mut modified_decomposition = decomposition.clone();
+        modified_decomposition[NodeIndex::new(2)].insert(NodeIndex::new(5));
+        assert!(!decompositions_equivalent(
+            &decomposition,
+            &modified_decomposition
+        ));
+
+        // The same bags in a path with a different bag in the middle
+        let mut rearranged_decomposition = decomposition.clone();
+        rearranged_decomposition.clear_edges();
+        rearranged_decomposition.add_edge(NodeIndex::new(1), NodeIndex::new(0), 0);
+        rearranged_decomposition.add_edge(NodeIndex::new(0), NodeIndex::new(2), 0);
+        assert!(!decompositions_equivalent(
+            &decomposition,
+            &rearranged_decomposition
+        ));
+    }
+
+    #[test]
+    fn test_adjacency_overlap_stats_on_path_decomposition() {
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let bags: Vec<NodeIndex> = [vec![0, 1, 2], vec![1, 2, 3], vec![3, 4], vec![5, 6]]
+            .into_iter()
+            .map(|bag| decomposition.add_node(bag.into_iter().map(NodeIndex::new).collect()))
+            .collect();
+        for i in 0..bags.len() - 1 {
+            decomposition.add_edge(bags[i], bags[i + 1], 0);
+        }
+
+        let separators = edge_separators(&decomposition);
+        assert_eq!(separators.len(), 3);
+        assert_eq!(
+            separators[0].1,
+            [1, 2].into_iter().map(NodeIndex::new).collect()
+        );
+
+        let (mean, min, max) = adjacency_overlap_stats(&decomposition);
+        assert_eq!(mean, 1.0);
+        assert_eq!(min, 0);
+        assert_eq!(max, 2);
+    }
+
+    #[test]
+    fn test_decomposition_tree_shape_of_path_and_star() {
+        let mut path: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let bags: Vec<NodeIndex> = (0..7)
+            .map(|i| path.add_node([NodeIndex::new(i), NodeIndex::new(i + 1)].into()))
+            .collect();
+        for i in 0..bags.len() - 1 {
+            path.add_edge(bags[i], bags[i + 1], 0);
+        }
+        let mut star = path.clone();
+        star.clear_edges();
+        for leaf in bags.iter().skip(1) {
+            star.add_edge(bags[0], *leaf, 0);
+        }
+
+        assert_eq!(
+            decomposition_tree_shape(&path),
+            TreeShape {
+                num_leaves: 2,
+                diameter: 6,
+                max_degree: 2,
+                height_from_best_root: 3,
+            }
+        );
+        assert_eq!(
+            decomposition_tree_shape(&star),
+            TreeShape {
+                num_leaves: 6,
+                diameter: 2,
+                max_degree: 6,
+                height_from_best_root: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_separator_width_of_path_decomposition() {
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let bags: Vec<NodeIndex> = [vec![0, 1, 2, 3], vec![2, 3, 4, 5], vec![5, 6, 7]]
+            .into_iter()
+            .map(|bag| decomposition.add_node(bag.into_iter().map(NodeIndex::new).collect()))
+            .collect();
+        for i in 0..bags.len() - 1 {
+            decomposition.add_edge(bags[i], bags[i + 1], 0);
+        }
+
+        assert_eq!(separator_width(&decomposition), 2);
+        assert_eq!(find_width_of_tree_decomposition(&decomposition), 3);
+
+        decomposition.clear_edges();
+        assert_eq!(separator_width(&decomposition), 0);
+    }
+
+    #[test]
+    fn test_adjacency_overlap_stats_without_edges() {
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        decomposition.add_node([NodeIndex::new(0)].into_iter().collect());
+
+        assert_eq!(adjacency_overlap_stats(&decomposition), (0.0, 0, 0));
+    }
+
+    #[test]
+    fn test_compare_decompositions_of_mst_and_fill_while() {
+        let test_graph = crate::tests::setup_test_graph(0);
+        let [mst_decomposition, fill_while_decomposition] = [
+            crate::SpanningTreeConstructionMethod::MSTre,
+            crate::SpanningTreeConstructionMethod::FilWh,
+        ]
+        .map(|method| {
+            compute_tree_decomposition::<_, _, _, RandomState>(
+                &test_graph.graph,
+                crate::negative_intersection,
+                method,
+                crate::CliqueSource::Maximal,
+                None,
+                crate::CliqueOrder::AsGiven,
+                &mut crate::FillOptions::default(),
+            )
+            .0
+        });
+
+        let comparison = compare_decompositions(&mst_decomposition, &fill_while_decomposition);
+
+        // Test graph 0 is chordal, so both methods yield the clique tree
+        assert_eq!(comparison.width_a, test_graph.treewidth);
+        assert_eq!(comparison.width_b, test_graph.treewidth);
+        assert_eq!(comparison.num_bags_a, comparison.num_bags_b);
+        assert_eq!(comparison.total_size_a, comparison.total_size_b);
+        assert_eq!(comparison.dominance, Dominance::Equal);
+
+        let mut bigger_decomposition = fill_while_decomposition.clone();
+        bigger_decomposition
+            .node_weights_mut()
+            .next()
+            .expect("Decomposition should have bags")
+            .extend((0..test_graph.graph.node_count()).map(NodeIndex::new));
+        assert_eq!(
+            compare_decompositions(&mst_decomposition, &bigger_decomposition).dominance,
+            Dominance::A
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::min_fill_heuristic::adjacency_sets;
+
+/// Computes a [feedback vertex set](https://en.wikipedia.org/wiki/Feedback_vertex_set) of the
+/// graph, that is, a set of vertices whose removal leaves a forest, with the greedy heuristic of
+/// repeatedly removing a vertex of maximum degree until the graph is acyclic. Before each such
+/// removal the vertices of degree at most 1 are stripped, since they are not contained in any cycle.
+///
+/// Since a forest has treewidth at most 1 and adding a vertex to every bag increases the width by
+/// at most 1, fvs.len() + 1 is an upper bound for the treewidth of the graph. This is a cheap sanity
+/// bound to compare other heuristics against.
+pub fn feedback_vertex_set_greedy<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> Vec<NodeIndex> {
+    let mut adjacency = adjacency_sets(graph);
+    let mut removed = vec![false; adjacency.len()];
+    let mut feedback_vertex_set = Vec::new();
+
+    let mut stack: Vec<usize> = (0..adjacency.len()).collect();
+    loop {
+        // Strip vertices of degree at most 1
+        while let Some(vertex) = stack.pop() {
+            if !removed[vertex] && adjacency[vertex].len() <= 1 {
+                stack.extend(remove_vertex(&mut adjacency, &mut removed, vertex));
+            }
+        }
+
+        // The remaining vertices have degree at least 2, so the remaining graph contains a cycle
+        let Some(maximum_degree_vertex) = (0..adjacency.len())
+            .filter(|vertex| !removed[*vertex])
+            .max_by_key(|vertex| (adjacency[*vertex].len(), std::cmp::Reverse(*vertex)))
+        else {
+            break;
+        };
+        feedback_vertex_set.push(NodeIndex::new(maximum_degree_vertex));
+        stack.extend(remove_vertex(
+            &mut adjacency,
+            &mut removed,
+            maximum_degree_vertex,
+        ));
+    }
+
+    feedback_vertex_set
+}
+
+/// Removes all edges of the vertex, marks it as removed and returns its former neighbours.
+fn remove_vertex(
+    adjacency: &mut [HashSet<usize>],
+    removed: &mut [bool],
+    vertex: usize,
+) -> Vec<usize> {
+    let neighbours: Vec<usize> = std::mem::take(&mut adjacency[vertex]).into_iter().collect();
+    for neighbour in &neighbours {
+        adjacency[*neighbour].remove(&vertex);
+    }
+    removed[vertex] = true;
+    neighbours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feedback_vertex_set_of_graph_with_one_cycle() {
+        // A cycle of length 5 with a pendant path and a pendant vertex
+        let mut graph: Graph<(), (), Undirected> = Graph::new_undirected();
+        for _ in 0..8 {
+            graph.add_node(());
+        }
+        graph.extend_with_edges([
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (4, 0),
+            (2, 5),
+            (5, 6),
+            (4, 7),
+        ]);
+
+        let feedback_vertex_set = feedback_vertex_set_greedy(&graph);
+        assert_eq!(feedback_vertex_set.len(), 1);
+
+        let mut forest = graph.clone();
+        forest.remove_node(feedback_vertex_set[0]);
+        assert!(!petgraph::algo::is_cyclic_undirected(&forest));
+    }
+
+    #[test]
+    fn test_feedback_vertex_set_bounds_treewidth() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            assert!(
+                test_graph.treewidth <= feedback_vertex_set_greedy(&test_graph.graph).len() + 1,
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+}
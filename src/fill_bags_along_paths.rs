@@ -5,8 +5,11 @@ use std::{
     collections::{BTreeSet, HashMap, HashSet},
     fmt::Debug,
     hash::BuildHasher,
+    time::Instant,
 };
 
+use crate::fill_bags_while_generating_mst::FillOptions;
+
 /// Struct for keeping track of node_index (node identifier in the graph) and the level of the node
 /// in the rooted tree.
 #[derive(PartialEq, Eq, Debug)]
@@ -41,6 +44,34 @@ impl PartialOrd for Predecessor {
 pub fn fill_bags_along_paths<E, S: BuildHasher>(
     graph: &mut Graph<HashSet<NodeIndex, S>, E, petgraph::prelude::Undirected>,
 ) {
+    fill_bags_along_paths_with_options(graph, &mut FillOptions::default())
+}
+
+/// Fills up the bags like [fill_bags_along_paths] and records the filling up in the stats and churn
+/// of the given [FillOptions]. Each path between two intersecting bags is recorded as a walk, so
+/// [FillStats::max_path_length][crate::FillStats::max_path_length] is the length of the longest
+/// path between two bags sharing a vertex. Long paths indicate that the tree spreads the
+/// occurrences of vertices far apart, which correlates with wide decompositions.
+///
+/// If the max_fill_path of the options is given, the tree is restructured before filling up the
+/// bags: Whenever the path between two intersecting bags has more than max_fill_path edges, the
+/// bag at its end is attached directly to the bag at its start (together with the subtree behind
+/// it) by replacing the last edge of the path with a shortcut edge between them. The result is
+/// still a valid tree decomposition.
+///
+/// The limit is best-effort: The shortcuts ignore the edge heuristic used to construct the tree
+/// and can lengthen the paths of other pairs of bags again, so paths longer than max_fill_path may
+/// remain and the width can get worse as well as better. Finding the long paths needs another pass
+/// over all pairs of bags, so the limit trades time for fewer insertions along long paths.
+pub fn fill_bags_along_paths_with_options<E, S: BuildHasher>(
+    graph: &mut Graph<HashSet<NodeIndex, S>, E, petgraph::prelude::Undirected>,
+    fill_options: &mut FillOptions,
+) {
+    let fill_start = Instant::now();
+    if let Some(max_fill_path) = fill_options.max_fill_path {
+        shortcut_long_fill_paths(graph, max_fill_path);
+    }
+
     // Finding out which paths between bags have to be checked
     for mut vec in graph.node_indices().combinations(2) {
         let first_index = vec.pop().expect("Vec should contain two items");
@@ -66,6 +97,8 @@ pub fn fill_bags_along_paths<E, S: BuildHasher>(
             .next()
             .expect("There should be a path in the tree");
 
+            fill_options.record_path_walk(path.len() - 1);
+
             // Last element is the given end node
             path.pop();
 
@@ -73,14 +106,61 @@ pub fn fill_bags_along_paths<E, S: BuildHasher>(
             // of the vertices on the path between them
             for node_index in path {
                 if node_index != first_index {
-                    graph
+                    let bag = graph
                         .node_weight_mut(node_index)
-                        .expect("Bag for the vertex should exist")
-                        .extend(intersection_vec.iter().cloned());
+                        .expect("Bag for the vertex should exist");
+                    for vertex in intersection_vec.iter() {
+                        if bag.insert(*vertex) {
+                            fill_options.record_insertion(node_index);
+                        }
+                    }
                 }
             }
         }
     }
+
+    fill_options.record_fill_time(fill_start);
+}
+
+/// Checks all 2-combinations of bags of the given tree for non-empty intersection and if the path
+/// between them has more than max_fill_path edges, replaces the last edge of the path by an edge
+/// between the two bags (keeping its weight). Removing the edge splits the tree into the part
+/// containing the end bag and the part containing the start bag, so the tree stays a tree.
+///
+/// Since the bags aren't filled up yet, filling up the bags of the restructured tree (see
+/// [fill_bags_along_paths]) still yields a tree decomposition.
+fn shortcut_long_fill_paths<E, S: BuildHasher>(
+    graph: &mut Graph<HashSet<NodeIndex, S>, E, petgraph::prelude::Undirected>,
+    max_fill_path: usize,
+) {
+    for mut vec in graph.node_indices().combinations(2) {
+        let first_index = vec.pop().expect("Vec should contain two items");
+        let second_index = vec.pop().expect("Vec should contain two items");
+
+        if graph[first_index].is_disjoint(&graph[second_index]) {
+            continue;
+        }
+
+        let path: Vec<_> = petgraph::algo::simple_paths::all_simple_paths::<Vec<NodeIndex>, _>(
+            &*graph,
+            first_index,
+            second_index,
+            0,
+            None,
+        )
+        .next()
+        .expect("There should be a path in the tree");
+
+        if path.len() - 1 > max_fill_path {
+            let last_edge = graph
+                .find_edge(path[path.len() - 2], second_index)
+                .expect("Consecutive bags on the path should be adjacent");
+            let weight = graph
+                .remove_edge(last_edge)
+                .expect("Edge on the path should exist");
+            graph.add_edge(first_index, second_index, weight);
+        }
+    }
 }
 
 /// Given a tree graph with bags (HashSets) as Vertices, checks all 2-combinations of bags for non-empty-intersection
@@ -113,6 +193,39 @@ pub fn fill_bags_along_paths_using_structure<E: Default + Debug, S: Default + Bu
     tree_predecessor_map
 }
 
+/// Fills up the bags of the given spanning tree of the clique graph, such that it becomes a tree
+/// decomposition, and returns it. This decouples the construction of the spanning tree from filling
+/// up the bags, so any tree on the vertices of the clique graph can be used, e.g. one computed by an
+/// external tool. For a minimum spanning tree, this is the tree decomposition computed by
+/// [MSTre][crate::SpanningTreeConstructionMethod::MSTre].
+///
+/// The vertices of the spanning tree need to have the same NodeIndices as in the clique graph and
+/// clique_graph_map is the map from the vertices of the original graph to the vertices of the clique
+/// graph containing them (see [construct_clique_graph_with_bags][crate::construct_clique_graph::construct_clique_graph_with_bags]).
+///
+/// **Panics**
+/// Panics if the spanning tree doesn't have the vertices of the clique graph or is not a tree.
+pub fn fill_given_tree<O: Default + Debug, S: Default + BuildHasher>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected>,
+    mut spanning_tree: Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected>,
+    clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+) -> Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected> {
+    assert_eq!(
+        spanning_tree.node_count(),
+        clique_graph.node_count(),
+        "Spanning tree should have the vertices of the clique graph"
+    );
+    assert_eq!(
+        spanning_tree.edge_count() + 1,
+        spanning_tree.node_count(),
+        "Spanning tree should be a tree"
+    );
+
+    fill_bags_along_paths_using_structure(&mut spanning_tree, clique_graph_map);
+
+    spanning_tree
+}
+
 /// Sets up the predecessor map such that each node has a predecessor going back to the root node.
 /// Additionally there is an index, indicating the depth level at which the predecessor is
 /// (root is 0, neighbours of root are 1 and so on ...).
@@ -156,6 +269,27 @@ pub fn fill_bags_until_common_predecessor<E, S: BuildHasher>(
     vertex_in_initial_graph: &NodeIndex,
     vertices_in_clique_graph: &HashSet<NodeIndex, S>,
 ) {
+    fill_bags_until_common_predecessor_with_options(
+        clique_graph,
+        predecessors_map,
+        vertex_in_initial_graph,
+        vertices_in_clique_graph,
+        &mut FillOptions::default(),
+    )
+}
+
+/// Fills up the bags like [fill_bags_until_common_predecessor] and records the filling up in the
+/// stats and churn of the given [FillOptions]. The walk to the common ancestor is recorded with the
+/// number of visited bags besides the common ancestor as its length.
+pub fn fill_bags_until_common_predecessor_with_options<E, S: BuildHasher>(
+    clique_graph: &mut Graph<HashSet<NodeIndex, S>, E, petgraph::prelude::Undirected>,
+    predecessors_map: &HashMap<NodeIndex, (NodeIndex, usize), S>,
+    vertex_in_initial_graph: &NodeIndex,
+    vertices_in_clique_graph: &HashSet<NodeIndex, S>,
+    fill_options: &mut FillOptions,
+) {
+    let mut path_length = 0;
+
     // Maybe optimize by not filling up vertices_in_clique_graph, but inserting their predecessors already
     // NOTE: Keep in mind, that one of the vertices_in_clique_graph might be the greatest common ancestor,
     // so this can be done for all vertices_in_clique_graph that don't have the minimizing level (possible implementation)
@@ -192,10 +326,14 @@ pub fn fill_bags_until_common_predecessor<E, S: BuildHasher>(
 
         // Insert the vertex from the original graph in the bag of the current vertex in the clique graph
         // that is on the path to the common ancestor
-        clique_graph
+        if clique_graph
             .node_weight_mut(current_vertex_in_clique_graph.node_index)
             .expect("Bag for the vertex should exist")
-            .insert(*vertex_in_initial_graph);
+            .insert(*vertex_in_initial_graph)
+        {
+            fill_options.record_insertion(current_vertex_in_clique_graph.node_index);
+        }
+        path_length += 1;
 
         if let Some((predecessor_clique_graph_vertex, index)) =
             predecessors_map.get(&current_vertex_in_clique_graph.node_index)
@@ -209,10 +347,17 @@ pub fn fill_bags_until_common_predecessor<E, S: BuildHasher>(
     }
     // This is reached once the common ancestor is found and the only element left in the collection
     if let Some(common_predecessor) = predecessors.first() {
-        clique_graph
+        if clique_graph
             .node_weight_mut(common_predecessor.node_index)
             .expect("Bag for the vertex should exist")
-            .insert(*vertex_in_initial_graph);
+            .insert(*vertex_in_initial_graph)
+        {
+            fill_options.record_insertion(common_predecessor.node_index);
+        }
+    }
+
+    if path_length > 0 {
+        fill_options.record_path_walk(path_length);
     }
 }
 
@@ -237,4 +382,134 @@ mod tests {
 
         assert_eq!(predecessors.len(), 2);
     }
+
+    #[test]
+    fn test_max_path_length_is_at_most_diameter() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let clique_graph: Graph<HashSet<NodeIndex, std::hash::RandomState>, i32, _> =
+                crate::construct_clique_graph::construct_clique_graph(
+                    crate::find_maximal_cliques::find_maximal_cliques::<
+                        Vec<_>,
+                        _,
+                        std::hash::RandomState,
+                    >(&test_graph.graph),
+                    crate::negative_intersection,
+                );
+            let mut clique_graph_tree: Graph<_, i32, petgraph::prelude::Undirected> =
+                petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                    &clique_graph,
+                ));
+            // Test graph 0 is not connected
+            crate::compute_treewidth_upper_bound::join_spanning_forest(&mut clique_graph_tree);
+
+            let mut fill_stats = crate::FillStats::default();
+            fill_bags_along_paths_with_options(
+                &mut clique_graph_tree,
+                &mut FillOptions {
+                    stats: Some(&mut fill_stats),
+                    ..Default::default()
+                },
+            );
+
+            assert!(
+                fill_stats.max_path_length
+                    <= crate::decomposition_tree_shape(&clique_graph_tree).diameter,
+                "Test graph {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_fill_path_keeps_decomposition_valid() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let clique_graph: Graph<HashSet<NodeIndex, std::hash::RandomState>, i32, _> =
+                crate::construct_clique_graph::construct_clique_graph(
+                    crate::find_maximal_cliques::find_maximal_cliques::<
+                        Vec<_>,
+                        _,
+                        std::hash::RandomState,
+                    >(&test_graph.graph),
+                    crate::negative_intersection,
+                );
+            // Chaining the bags in a path makes the paths between intersecting bags long
+            let mut clique_graph_tree = clique_graph.clone();
+            clique_graph_tree.clear_edges();
+            for (first_bag, second_bag) in clique_graph.node_indices().tuple_windows() {
+                clique_graph_tree.add_edge(first_bag, second_bag, 0);
+            }
+
+            fill_bags_along_paths_with_options(
+                &mut clique_graph_tree,
+                &mut FillOptions {
+                    max_fill_path: Some(1),
+                    ..Default::default()
+                },
+            );
+
+            assert_eq!(
+                clique_graph_tree.edge_count() + 1,
+                clique_graph_tree.node_count(),
+                "Test graph {}",
+                i
+            );
+            assert_eq!(
+                crate::validate_tree_decomposition(&test_graph.graph, &clique_graph_tree),
+                Ok(()),
+                "Test graph {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_fill_given_minimum_spanning_tree_matches_mstre() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let (clique_graph, clique_graph_map) =
+                crate::construct_clique_graph::construct_clique_graph_with_bags(
+                    crate::find_maximal_cliques::find_maximal_cliques::<Vec<_>, _, FxBuildHasher>(
+                        &test_graph.graph,
+                    ),
+                    crate::negative_intersection::<FxBuildHasher>,
+                );
+            let minimum_spanning_tree: Graph<_, i32, petgraph::prelude::Undirected> =
+                petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                    &clique_graph,
+                ));
+            let mut expected_tree_decomposition = minimum_spanning_tree.clone();
+            fill_bags_along_paths(&mut expected_tree_decomposition);
+
+            let tree_decomposition =
+                fill_given_tree(&clique_graph, minimum_spanning_tree, &clique_graph_map);
+
+            assert_eq!(
+                tree_decomposition.node_weights().collect::<Vec<_>>(),
+                expected_tree_decomposition
+                    .node_weights()
+                    .collect::<Vec<_>>(),
+                "Test graph {}",
+                i
+            );
+            assert_eq!(
+                crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition(
+                    &tree_decomposition
+                ),
+                crate::compute_treewidth_upper_bound::<_, _, _, FxBuildHasher>(
+                    &test_graph.graph,
+                    crate::negative_intersection,
+                    crate::SpanningTreeConstructionMethod::MSTre,
+                    false,
+                    crate::CliqueSource::Maximal,
+                    None,
+                ),
+                "Test graph {}",
+                i
+            );
+        }
+    }
 }
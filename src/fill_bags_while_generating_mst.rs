@@ -1,10 +1,113 @@
 use csv::WriterBuilder;
-use petgraph::{graph::NodeIndex, Graph, Undirected};
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, unionfind::UnionFind, Graph, Undirected};
+use rand::{seq::SliceRandom, RngCore};
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
     hash::BuildHasher,
+    sync::Mutex,
+    time::Instant,
 };
 
+use crate::PhaseTimings;
+
+/// Statistics about filling up bags while constructing a spanning tree of the clique graph, which
+/// can be passed to the spanning tree constructions to see where the time goes.
+///
+/// insertions is the number of times a vertex was inserted into a bag not containing it yet
+///
+/// path_walks is the number of paths in the tree along which bags were filled up
+///
+/// max_path_length is the maximum number of edges of such a path
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FillStats {
+    pub insertions: usize,
+    pub path_walks: usize,
+    pub max_path_length: usize,
+}
+
+impl FillStats {
+    /// Records a walk along a path with the given number of edges
+    pub(crate) fn record_path_walk(&mut self, path_length: usize) {
+        self.path_walks += 1;
+        self.max_path_length = self.max_path_length.max(path_length);
+    }
+}
+
+/// Optional observers and options for the spanning tree constructions that fill up the bags. By
+/// default (see [Default]) nothing is recorded and the result is the same as without options.
+///
+/// stats records the filling up of the bags, see [FillStats]. This doesn't change the result.
+///
+/// separators records for each vertex added to the spanning tree (except for the first one) its
+/// NodeIndex in the result graph and the (sorted) vertices its bag shares with the bag it is
+/// attached to at the time of attaching. These are the separators the construction implicitly uses.
+///
+/// rng breaks ties between equally cheap edges to the current spanning tree randomly, so different
+/// seeds can lead to different spanning trees. Otherwise the tie breaking depends on the hasher.
+///
+/// churn records the number of vertices inserted into each bag of the result graph while filling
+/// up by adding it to the entry of its NodeIndex (bags without insertions get no entry). This shows
+/// where the filling up is expensive, the sum over all bags equals [FillStats::insertions].
+///
+/// max_fill_path restructures a given spanning tree before filling up its bags such that the paths
+/// between intersecting bags are (where possible) at most this long, see
+/// [fill_bags_along_paths_with_options][crate::fill_bags_along_paths::fill_bags_along_paths_with_options].
+/// This can change the result.
+///
+/// timings records the time spent filling up the bags in [PhaseTimings::fill]. When passed to
+/// [compute_treewidth_upper_bound_with_fill_options][crate::compute_treewidth_upper_bound_with_fill_options],
+/// the other phases of the computation are recorded as well, see [PhaseTimings]. Since the cliques
+/// are streamed into the clique graph there, their enumeration is part of its construction.
+///
+/// The separators are only recorded by [fill_bags_while_generating_mst] and the rng is only used by
+/// the constructions attaching the cheapest vertex according to the edge heuristic. The
+/// max_fill_path is only used when filling up the bags of a given spanning tree, which records the
+/// stats and churn as well.
+#[derive(Default)]
+pub struct FillOptions<'a> {
+    pub stats: Option<&'a mut FillStats>,
+    pub separators: Option<&'a mut Vec<(NodeIndex, Vec<NodeIndex>)>>,
+    pub rng: Option<&'a mut dyn RngCore>,
+    pub churn: Option<&'a mut HashMap<NodeIndex, usize>>,
+    pub max_fill_path: Option<usize>,
+    pub timings: Option<&'a mut PhaseTimings>,
+}
+
+impl FillOptions<'_> {
+    /// Records the insertion of a vertex into the bag of the given vertex of the result graph
+    pub(crate) fn record_insertion(&mut self, vertex_res: NodeIndex) {
+        if let Some(stats) = self.stats.as_deref_mut() {
+            stats.insertions += 1;
+        }
+        if let Some(churn) = self.churn.as_deref_mut() {
+            *churn.entry(vertex_res).or_insert(0) += 1;
+        }
+    }
+
+    /// Adds the time since fill_start to the time spent filling up the bags
+    pub(crate) fn record_fill_time(&mut self, fill_start: Instant) {
+        if let Some(timings) = self.timings.as_deref_mut() {
+            timings.fill += fill_start.elapsed();
+        }
+    }
+
+    /// Records a walk along a path with the given number of edges, see [FillStats::record_path_walk]
+    pub(crate) fn record_path_walk(&mut self, path_length: usize) {
+        if let Some(stats) = self.stats.as_deref_mut() {
+            stats.record_path_walk(path_length);
+        }
+    }
+}
+
+/// Callback receiving the NodeIndex in the tree decomposition and the bag of a vertex whose bag
+/// won't change anymore (see [fill_bags_while_generating_mst_with_trace])
+pub(crate) type OnBagFinalized<'a, S> = dyn FnMut(NodeIndex, &HashSet<NodeIndex, S>) + 'a;
+
+/// Serializes writing to the log file of the maximum bag sizes (see [fill_bags_while_generating_mst])
+static LOG_FILE_LOCK: Mutex<()> = Mutex::new(());
+
 /// The function computes a [tree decomposition][https://en.wikipedia.org/wiki/Tree_decomposition]
 /// with the vertices having bags (HashSets) as labels
 /// given a clique graph. For this a minimum spanning tree of the clique graph is constructed using
@@ -12,20 +115,103 @@ use std::{
 /// is added to the spanning tree, the bags of the current spanning tree are filled up/updated
 /// according to the [tree decomposition criteria][https://en.wikipedia.org/wiki/Tree_decomposition#Definition].
 ///
+/// If the clique graph is not connected, a spanning tree is constructed for each component and
+/// the trees are joined by an edge between their roots. Since bags from different components are
+/// disjoint, this results in a valid tree decomposition.
+///
+/// All of the [FillOptions] are supported.
+///
 /// **Panics**
 /// The log_bag_size parameter enables logging of the increase in size of the biggest bag of the spanning
 /// tree over time while the spanning tree is constructed (i.e. for each new vertex added to the spanning
 /// tree, logs the current size of the biggest bag). If log_bag_size == true the file
 /// k-tree-benchmarks/benchmark_results/k_tree_maximum_bag_size_over_time.csv (where k-tree-benchmarks
 /// is a subdirectory of the runtime directory) has to exist otherwise this function will panic.
-pub fn fill_bags_while_generating_mst<N, E, O: Ord, S: Default + BuildHasher + Clone>(
+pub fn fill_bags_while_generating_mst<N, E, O: Ord + Clone, S: Default + BuildHasher + Clone>(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
     log_bag_size: bool,
+    fill_options: &mut FillOptions,
 ) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
-    // For logging the size of the maximum bags. Stays empty if log_bag_size == False
-    let mut vector_for_logging = Vec::new();
+    let (result_graph, maximum_bag_sizes) = fill_bags_while_generating_mst_with_trace(
+        Cow::Borrowed(clique_graph),
+        edge_weight_heuristic,
+        clique_graph_map,
+        fill_options,
+        None,
+        false,
+    );
+
+    // Log bag size if log_bag_size == true
+    if log_bag_size {
+        // Computations may run in parallel (e.g. in benchmarks), so writes to the log are serialized
+        let _log_file_guard = LOG_FILE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .append(true)
+            .open("k-tree-benchmarks/benchmark_results/k_tree_maximum_bag_size_over_time.csv")
+            .unwrap();
+
+        let mut writer = WriterBuilder::new().flexible(false).from_writer(file);
+        // The maximum bag sizes are logged as the width of the current spanning tree
+        let vector_for_logging = maximum_bag_sizes
+            .into_iter()
+            .map(|v| v.saturating_sub(1).to_string());
+        writer
+            .write_record(vector_for_logging)
+            .expect("Writing to logs for maximum bag size for fill while should be possible");
+        writer
+            .flush()
+            .expect("Flushing logs for maximum bag size for fill while should be possible");
+    }
+
+    result_graph
+}
+
+/// Computes the same tree decomposition as [fill_bags_while_generating_mst] and additionally
+/// returns the size of the biggest bag of the current spanning tree each time a vertex of the
+/// clique graph was added to it (starting with the first vertex).
+///
+/// If the clique graph is owned, the bag of each of its vertices is moved into the spanning tree
+/// instead of being cloned, since it isn't needed anymore once the vertex was added.
+///
+/// If on_bag_finalized is given, it is called with the NodeIndex in the result graph and the bag of
+/// each vertex as soon as the bag can't change anymore, see [FinalizedBagTracker]. Each vertex of
+/// the result graph is reported exactly once and the reported bag is the bag in the returned graph.
+///
+/// If additionally discard_finalized_bags is true, the bags are replaced by empty bags once they
+/// were reported, so that only the bags that can still change are kept in memory. Similarly, the
+/// vertices of the original graph are removed from the clique graph map once all cliques containing
+/// them were added. The returned sizes of the biggest bags still account for the discarded bags.
+pub(crate) fn fill_bags_while_generating_mst_with_trace<
+    O: Ord + Clone,
+    S: Default + BuildHasher + Clone,
+>(
+    mut clique_graph: Cow<Graph<HashSet<NodeIndex, S>, O, Undirected>>,
+    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    mut clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    fill_options: &mut FillOptions,
+    mut on_bag_finalized: Option<&mut OnBagFinalized<S>>,
+    discard_finalized_bags: bool,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, Vec<usize>) {
+    // The size of the biggest bag each time a vertex is added to the spanning tree
+    let mut maximum_bag_sizes = Vec::new();
+    // Keeps track of the vertices of the result graph whose bags can't change anymore
+    let mut tracker = on_bag_finalized
+        .is_some()
+        .then(FinalizedBagTracker::default);
+    // The number of cliques containing each vertex of the original graph that haven't been added
+    // to the spanning tree yet, only needed for removing the vertices from the clique graph map
+    let mut remaining_clique_counts: Option<HashMap<NodeIndex, usize, S>> = discard_finalized_bags
+        .then(|| {
+            clique_graph_map
+                .iter()
+                .map(|(vertex, cliques)| (*vertex, cliques.len()))
+                .collect()
+        });
 
     let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
     // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
@@ -43,48 +229,93 @@ pub fn fill_bags_while_generating_mst<N, E, O: Ord, S: Default + BuildHasher + C
     // Second tuple entry is node_index from the clique graph that is the interesting vertex
     let mut currently_interesting_vertices: HashSet<(NodeIndex, NodeIndex), S> = Default::default();
 
-    let first_vertex_res = result_graph.add_node(
-        clique_graph
-            .node_weight(first_vertex_clique)
-            .expect("Vertices in clique graph should have bags as weights")
-            .clone(),
-    );
+    let first_vertex_res = result_graph.add_node(take_clique_graph_bag(
+        &mut clique_graph,
+        first_vertex_clique,
+    ));
 
     // Add vertices that are reachable from first vertex
     for neighbor in clique_graph.neighbors(first_vertex_clique) {
         currently_interesting_vertices.insert((first_vertex_res, neighbor));
     }
     node_index_map.insert(first_vertex_clique, first_vertex_res);
-
-    // Log current maximum bag size
-    if log_bag_size {
-        vector_for_logging.push(
-            crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition(
-                &result_graph,
-            ),
+    if let Some(tracker) = tracker.as_mut() {
+        tracker.add_vertex(first_vertex_res, None);
+        for (vertex_res, _) in currently_interesting_vertices.iter() {
+            tracker.add_interesting(*vertex_res);
+        }
+    }
+    if let Some(remaining_clique_counts) = remaining_clique_counts.as_mut() {
+        release_vertices_of_added_clique(
+            &result_graph[first_vertex_res],
+            &mut clique_graph_map,
+            remaining_clique_counts,
         );
     }
 
+    // Log current maximum bag size
+    push_maximum_bag_size(&mut maximum_bag_sizes, &result_graph);
+
     while !clique_graph_remaining_vertices.is_empty() {
         // The cheapest_old_vertex_res is one of the vertices from the already constructed tree that the new vertex
         // is being attached to
         // The cheapest_new_vertex_clique is the new vertex that is being added to the tree. The NodeIndex corresponds
         // to the vertex in the clique graph and not the result graph and thus still needs to be translated.
-        let (cheapest_old_vertex_res, cheapest_new_vertex_clique) = find_cheapest_vertex(
+        let Some((cheapest_old_vertex_res, cheapest_new_vertex_clique)) = find_cheapest_vertex(
             &clique_graph,
             &result_graph,
             edge_weight_heuristic,
             &currently_interesting_vertices,
-        );
+            fill_options.rng.as_deref_mut(),
+        ) else {
+            let new_root_res = start_spanning_tree_of_next_component(
+                &clique_graph,
+                &mut result_graph,
+                &mut clique_graph_remaining_vertices,
+                &mut currently_interesting_vertices,
+                &mut node_index_map,
+            );
+            let edge_weight =
+                edge_weight_heuristic(&result_graph[first_vertex_res], &result_graph[new_root_res]);
+            result_graph.add_edge(first_vertex_res, new_root_res, edge_weight);
+            // Bags from different components are disjoint
+            if let Some(separators) = fill_options.separators.as_deref_mut() {
+                separators.push((new_root_res, Vec::new()));
+            }
+            if let Some(remaining_clique_counts) = remaining_clique_counts.as_mut() {
+                release_vertices_of_added_clique(
+                    &result_graph[new_root_res],
+                    &mut clique_graph_map,
+                    remaining_clique_counts,
+                );
+            }
+            push_maximum_bag_size(&mut maximum_bag_sizes, &result_graph);
+            if let (Some(tracker), Some(on_bag_finalized)) =
+                (tracker.as_mut(), on_bag_finalized.as_deref_mut())
+            {
+                // The currently interesting vertices were empty before the new root was added
+                tracker.add_vertex(new_root_res, None);
+                for (vertex_res, _) in currently_interesting_vertices.iter() {
+                    tracker.add_interesting(*vertex_res);
+                }
+                tracker.report_finalized_bags(
+                    &mut result_graph,
+                    on_bag_finalized,
+                    discard_finalized_bags,
+                );
+            }
+            continue;
+        };
         clique_graph_remaining_vertices.remove(&cheapest_new_vertex_clique);
 
         // Update result graph
-        let cheapest_new_vertex_res = result_graph.add_node(
-            clique_graph
-                .node_weight(cheapest_new_vertex_clique)
-                .expect("Vertices in clique graph should have bags as weights")
-                .clone(),
-        );
+        let cheapest_new_vertex_res = result_graph.add_node(take_clique_graph_bag(
+            &mut clique_graph,
+            cheapest_new_vertex_clique,
+        ));
+        if let Some(tracker) = tracker.as_mut() {
+            tracker.add_vertex(cheapest_new_vertex_res, Some(cheapest_old_vertex_res));
+        }
 
         node_index_map.insert(cheapest_new_vertex_clique, cheapest_new_vertex_res);
         result_graph.add_edge(
@@ -104,47 +335,234 @@ pub fn fill_bags_while_generating_mst<N, E, O: Ord, S: Default + BuildHasher + C
         for neighbor in clique_graph.neighbors(cheapest_new_vertex_clique) {
             if clique_graph_remaining_vertices.contains(&neighbor) {
                 currently_interesting_vertices.insert((cheapest_new_vertex_res, neighbor));
+                if let Some(tracker) = tracker.as_mut() {
+                    tracker.add_interesting(cheapest_new_vertex_res);
+                }
             }
         }
 
-        currently_interesting_vertices
-            .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_new_vertex_clique));
+        currently_interesting_vertices.retain(|(vertex_res, vertex_clique)| {
+            let keep = !vertex_clique.eq(&cheapest_new_vertex_clique);
+            if let (false, Some(tracker)) = (keep, tracker.as_mut()) {
+                tracker.remove_interesting(*vertex_res);
+            }
+            keep
+        });
+
+        if let Some(separators) = fill_options.separators.as_deref_mut() {
+            let mut separator: Vec<NodeIndex> = result_graph[cheapest_new_vertex_res]
+                .intersection(&result_graph[cheapest_old_vertex_res])
+                .copied()
+                .collect();
+            separator.sort();
+            separators.push((cheapest_new_vertex_res, separator));
+        }
 
+        let fill_start = Instant::now();
         fill_bags_from_result_graph(
             &mut result_graph,
             cheapest_new_vertex_res,
             cheapest_old_vertex_res,
             &clique_graph_map,
             &node_index_map,
+            fill_options,
         );
+        fill_options.record_fill_time(fill_start);
+        if let Some(remaining_clique_counts) = remaining_clique_counts.as_mut() {
+            // The bag of the new vertex isn't filled up, so it still is the clique
+            release_vertices_of_added_clique(
+                &result_graph[cheapest_new_vertex_res],
+                &mut clique_graph_map,
+                remaining_clique_counts,
+            );
+        }
 
         // Log current maximum bag size
-        vector_for_logging.push(
-            crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition(
-                &result_graph,
-            ),
-        );
+        push_maximum_bag_size(&mut maximum_bag_sizes, &result_graph);
+        if let (Some(tracker), Some(on_bag_finalized)) =
+            (tracker.as_mut(), on_bag_finalized.as_deref_mut())
+        {
+            tracker.report_finalized_bags(
+                &mut result_graph,
+                on_bag_finalized,
+                discard_finalized_bags,
+            );
+        }
     }
 
-    // Log bag size if log_bag_size == true
-    if log_bag_size {
-        let file = std::fs::OpenOptions::new()
-            .write(true)
-            .append(true)
-            .open("k-tree-benchmarks/benchmark_results/k_tree_maximum_bag_size_over_time.csv")
-            .unwrap();
+    // All remaining bags are final once the spanning tree is complete
+    if let (Some(tracker), Some(on_bag_finalized)) = (tracker.as_mut(), on_bag_finalized) {
+        tracker.report_remaining_bags(&mut result_graph, on_bag_finalized, discard_finalized_bags);
+    }
 
-        let mut writer = WriterBuilder::new().flexible(false).from_writer(file);
-        let vector_for_logging = vector_for_logging.into_iter().map(|v| v.to_string());
-        writer
-            .write_record(vector_for_logging)
-            .expect("Writing to logs for maximum bag size for fill while should be possible");
-        writer
-            .flush()
-            .expect("Flushing logs for maximum bag size for fill while should be possible");
+    (result_graph, maximum_bag_sizes)
+}
+
+/// Returns the bag of the given vertex of the clique graph. If the clique graph is owned, the bag
+/// is moved out of it, since it isn't needed anymore once the vertex was added to the spanning tree.
+fn take_clique_graph_bag<O: Clone, S: Default + Clone>(
+    clique_graph: &mut Cow<Graph<HashSet<NodeIndex, S>, O, Undirected>>,
+    vertex: NodeIndex,
+) -> HashSet<NodeIndex, S> {
+    match clique_graph {
+        Cow::Borrowed(clique_graph) => clique_graph[vertex].clone(),
+        Cow::Owned(clique_graph) => std::mem::take(&mut clique_graph[vertex]),
+    }
+}
+
+/// Decrements the number of remaining cliques of each vertex of the given clique that was just
+/// added to the spanning tree. Vertices without remaining cliques are removed from the clique graph
+/// map, since it is only used for filling up the bags with the vertices of newly added cliques.
+fn release_vertices_of_added_clique<S: BuildHasher>(
+    clique: &HashSet<NodeIndex, S>,
+    clique_graph_map: &mut HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    remaining_clique_counts: &mut HashMap<NodeIndex, usize, S>,
+) {
+    for vertex in clique {
+        if let Some(remaining_cliques) = remaining_clique_counts.get_mut(vertex) {
+            *remaining_cliques -= 1;
+            if *remaining_cliques == 0 {
+                remaining_clique_counts.remove(vertex);
+                clique_graph_map.remove(vertex);
+            }
+        }
+    }
+}
+
+/// Keeps track of the vertices of the spanning tree whose bags can't grow anymore while the spanning
+/// tree is constructed in [fill_bags_while_generating_mst_with_trace].
+///
+/// A bag is only filled up if it lies on the path in the spanning tree between a newly added vertex
+/// and a vertex whose clique shares a vertex with the new clique. Such paths enter the current
+/// spanning tree at a vertex that is still adjacent to a remaining vertex of the clique graph (the
+/// "frontier", i.e. the first entries of the currently interesting vertices) and only end in such
+/// vertices, since a clique sharing a vertex with a remaining clique is adjacent to it in the clique
+/// graph. Thus a bag is final if it is not contained in the smallest subtree spanning the frontier.
+///
+/// This subtree is maintained incrementally by removing leaves not in the frontier whenever a
+/// vertex is added, leaves the frontier or loses a neighbour in the subtree, so each vertex is only
+/// looked at a constant number of times per change. Since new vertices are only attached to the
+/// frontier, a bag that is final stays final. The edges joining the spanning trees of different
+/// components of the clique graph are not part of the subtree, since their bags are disjoint.
+///
+/// This is conservative: A bag in this subtree might not grow anymore either.
+#[derive(Default)]
+struct FinalizedBagTracker {
+    // The parent of each vertex of the spanning tree, None for the roots of the components
+    parents: Vec<Option<NodeIndex>>,
+    // The number of currently interesting vertices with the vertex as first entry, so the frontier
+    // consists of the vertices with a positive count
+    interesting_counts: Vec<usize>,
+    // The number of neighbours of each vertex that are in the subtree spanning the frontier
+    degrees: Vec<usize>,
+    finalized: Vec<bool>,
+    // Vertices that might have become leaves not in the frontier since the last report
+    candidates: Vec<NodeIndex>,
+}
+
+impl FinalizedBagTracker {
+    /// Adds the vertex that was just added to the spanning tree with the given parent (None for the
+    /// root of a component). The parent should be in the frontier.
+    fn add_vertex(&mut self, vertex: NodeIndex, parent: Option<NodeIndex>) {
+        self.parents.push(parent);
+        self.interesting_counts.push(0);
+        self.degrees.push(usize::from(parent.is_some()));
+        self.finalized.push(false);
+        if let Some(parent) = parent {
+            self.degrees[parent.index()] += 1;
+        }
+        self.candidates.push(vertex);
+    }
+
+    /// Records that a currently interesting vertex with the given vertex as first entry was added
+    fn add_interesting(&mut self, vertex: NodeIndex) {
+        self.interesting_counts[vertex.index()] += 1;
+    }
+
+    /// Records that a currently interesting vertex with the given vertex as first entry was removed
+    fn remove_interesting(&mut self, vertex: NodeIndex) {
+        self.interesting_counts[vertex.index()] -= 1;
+        if self.interesting_counts[vertex.index()] == 0 {
+            self.candidates.push(vertex);
+        }
+    }
+
+    /// Calls on_bag_finalized for each vertex whose bag became final since the last call. If
+    /// discard is true, the reported bags are replaced by empty bags afterwards.
+    fn report_finalized_bags<O, S: Default>(
+        &mut self,
+        result_graph: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+        on_bag_finalized: &mut OnBagFinalized<S>,
+        discard: bool,
+    ) {
+        while let Some(vertex) = self.candidates.pop() {
+            if self.finalized[vertex.index()]
+                || self.interesting_counts[vertex.index()] > 0
+                || self.degrees[vertex.index()] > 1
+            {
+                continue;
+            }
+            self.report(vertex, result_graph, on_bag_finalized, discard);
+
+            for neighbour in result_graph.neighbors(vertex) {
+                let is_tree_edge = self.parents[vertex.index()] == Some(neighbour)
+                    || self.parents[neighbour.index()] == Some(vertex);
+                if is_tree_edge && !self.finalized[neighbour.index()] {
+                    self.degrees[neighbour.index()] -= 1;
+                    self.candidates.push(neighbour);
+                }
+            }
+        }
+    }
+
+    /// Calls on_bag_finalized for each vertex that hasn't been reported yet, which is used once the
+    /// spanning tree is complete.
+    fn report_remaining_bags<O, S: Default>(
+        &mut self,
+        result_graph: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+        on_bag_finalized: &mut OnBagFinalized<S>,
+        discard: bool,
+    ) {
+        for vertex in result_graph.node_indices() {
+            if !self.finalized[vertex.index()] {
+                self.report(vertex, result_graph, on_bag_finalized, discard);
+            }
+        }
+        self.candidates.clear();
+    }
+
+    fn report<O, S: Default>(
+        &mut self,
+        vertex: NodeIndex,
+        result_graph: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+        on_bag_finalized: &mut OnBagFinalized<S>,
+        discard: bool,
+    ) {
+        self.finalized[vertex.index()] = true;
+        on_bag_finalized(vertex, &result_graph[vertex]);
+        if discard {
+            result_graph[vertex] = HashSet::default();
+        }
     }
+}
+
+/// Pushes the size of the biggest bag of the given graph to the maximum bag sizes, unless a bigger
+/// size was pushed before (which happens if bags have been discarded).
+fn push_maximum_bag_size<O, S>(
+    maximum_bag_sizes: &mut Vec<usize>,
+    result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) {
+    let previous_maximum = maximum_bag_sizes.last().copied().unwrap_or(0);
+    maximum_bag_sizes.push(previous_maximum.max(maximum_bag_size(result_graph)));
+}
 
+/// Returns the size of the biggest bag of the given graph or 0 if it has no vertices
+fn maximum_bag_size<O, S>(result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>) -> usize {
     result_graph
+        .node_weights()
+        .map(|bag| bag.len())
+        .max()
+        .unwrap_or(0)
 }
 
 fn fill_bags_from_result_graph<S: BuildHasher + Clone, O>(
@@ -153,6 +571,7 @@ fn fill_bags_from_result_graph<S: BuildHasher + Clone, O>(
     cheapest_old_vertex_res: NodeIndex,
     clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
     node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
+    fill_options: &mut FillOptions,
 ) {
     for vertex_from_starting_graph in result_graph
         .node_weight(new_vertex_res)
@@ -174,6 +593,7 @@ fn fill_bags_from_result_graph<S: BuildHasher + Clone, O>(
                             *vertex_res_graph,
                             result_graph,
                             *vertex_from_starting_graph,
+                            fill_options,
                         );
                     }
                 }
@@ -191,6 +611,7 @@ fn fill_bags<O, S: BuildHasher>(
     end_vertex: NodeIndex,
     graph: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
     vertex_to_be_insert_from_starting_graph: NodeIndex,
+    fill_options: &mut FillOptions,
 ) {
     let mut path: Vec<_> = petgraph::algo::simple_paths::all_simple_paths::<Vec<NodeIndex>, _>(
         &*graph,
@@ -202,15 +623,19 @@ fn fill_bags<O, S: BuildHasher>(
     .next()
     .expect("There should be a path in the tree");
 
+    fill_options.record_path_walk(path.len() - 1);
+
     // Last element is the given end node
     path.pop();
 
     for node_index in path {
-        if node_index != start_vertex {
-            graph
+        if node_index != start_vertex
+            && graph
                 .node_weight_mut(node_index)
                 .expect("Bag for the vertex should exist")
-                .insert(vertex_to_be_insert_from_starting_graph);
+                .insert(vertex_to_be_insert_from_starting_graph)
+        {
+            fill_options.record_insertion(node_index);
         }
     }
 }
@@ -229,6 +654,7 @@ pub fn fill_bags_while_generating_mst_update_edges<
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    fill_options: &mut FillOptions,
 ) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
     let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
     // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
@@ -264,12 +690,25 @@ pub fn fill_bags_while_generating_mst_update_edges<
         // is being attached to
         // The cheapest_new_vertex_clique is the new vertex that is being added to the tree. The NodeIndex corresponds
         // to the vertex in the clique graph and not the result graph and thus still needs to be translated.
-        let (cheapest_old_vertex_res, cheapest_new_vertex_clique) = find_cheapest_vertex(
+        let Some((cheapest_old_vertex_res, cheapest_new_vertex_clique)) = find_cheapest_vertex(
             &clique_graph,
             &result_graph,
             edge_weight_heuristic,
             &currently_interesting_vertices,
-        );
+            fill_options.rng.as_deref_mut(),
+        ) else {
+            let new_root_res = start_spanning_tree_of_next_component(
+                clique_graph,
+                &mut result_graph,
+                &mut clique_graph_remaining_vertices,
+                &mut currently_interesting_vertices,
+                &mut node_index_map,
+            );
+            let edge_weight =
+                edge_weight_heuristic(&result_graph[first_vertex_res], &result_graph[new_root_res]);
+            result_graph.add_edge(first_vertex_res, new_root_res, edge_weight);
+            continue;
+        };
         clique_graph_remaining_vertices.remove(&cheapest_new_vertex_clique);
 
         // Update result graph
@@ -304,6 +743,7 @@ pub fn fill_bags_while_generating_mst_update_edges<
         currently_interesting_vertices
             .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_new_vertex_clique));
 
+        let fill_start = Instant::now();
         fill_bags_from_result_graph_updating_edges(
             &mut result_graph,
             cheapest_new_vertex_res,
@@ -311,7 +751,9 @@ pub fn fill_bags_while_generating_mst_update_edges<
             &clique_graph_map,
             &node_index_map,
             &mut currently_interesting_vertices,
+            fill_options,
         );
+        fill_options.record_fill_time(fill_start);
     }
 
     result_graph
@@ -324,6 +766,7 @@ fn fill_bags_from_result_graph_updating_edges<S: BuildHasher + Clone, O>(
     clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
     node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
     currently_interesting_vertices: &mut HashSet<(NodeIndex, NodeIndex), S>,
+    fill_options: &mut FillOptions,
 ) {
     for vertex_from_starting_graph in result_graph
         .node_weight(new_vertex_res)
@@ -340,7 +783,7 @@ fn fill_bags_from_result_graph_updating_edges<S: BuildHasher + Clone, O>(
             for vertex_in_clique_graph in vertices_in_clique_graph {
                 if let Some(vertex_res_graph) = node_index_map.get(vertex_in_clique_graph) {
                     if vertex_res_graph != &new_vertex_res {
-                        fill_bags_updating_edges(
+                        let (path_length, inserted_into) = fill_bags_updating_edges(
                             new_vertex_res,
                             *vertex_res_graph,
                             result_graph,
@@ -349,6 +792,10 @@ fn fill_bags_from_result_graph_updating_edges<S: BuildHasher + Clone, O>(
                             node_index_map,
                             currently_interesting_vertices,
                         );
+                        fill_options.record_path_walk(path_length);
+                        for vertex_res in inserted_into {
+                            fill_options.record_insertion(vertex_res);
+                        }
                     }
                 }
             }
@@ -357,6 +804,9 @@ fn fill_bags_from_result_graph_updating_edges<S: BuildHasher + Clone, O>(
 }
 
 /// Adapted from [fill_bags]
+///
+/// Returns the number of edges of the path and the vertices on it into whose bags the vertex was
+/// inserted.
 fn fill_bags_updating_edges<O, S: BuildHasher>(
     start_vertex: NodeIndex,
     end_vertex: NodeIndex,
@@ -365,7 +815,7 @@ fn fill_bags_updating_edges<O, S: BuildHasher>(
     clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
     node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
     currently_interesting_vertices: &mut HashSet<(NodeIndex, NodeIndex), S>,
-) {
+) -> (usize, Vec<NodeIndex>) {
     let mut path: Vec<_> = petgraph::algo::simple_paths::all_simple_paths::<Vec<NodeIndex>, _>(
         &*graph,
         start_vertex,
@@ -376,15 +826,21 @@ fn fill_bags_updating_edges<O, S: BuildHasher>(
     .next()
     .expect("There should be a path in the tree");
 
+    let path_length = path.len() - 1;
+    let mut inserted_into = Vec::new();
+
     // Last element is the given end node
     path.pop();
 
     for node_index in path {
         if node_index != start_vertex {
-            graph
+            if graph
                 .node_weight_mut(node_index)
                 .expect("Bag for the vertex should exist")
-                .insert(vertex_to_be_insert_from_starting_graph);
+                .insert(vertex_to_be_insert_from_starting_graph)
+            {
+                inserted_into.push(node_index);
+            }
 
             for vertex_clique_graph in clique_graph_map
                 .get(&vertex_to_be_insert_from_starting_graph)
@@ -396,6 +852,46 @@ fn fill_bags_updating_edges<O, S: BuildHasher>(
             }
         }
     }
+
+    (path_length, inserted_into)
+}
+
+/// Starts a spanning tree of the next component of the clique graph at one of the remaining
+/// vertices. The root is added to the result graph and the node index map, its neighbours are added
+/// to the currently interesting vertices and the NodeIndex of the root in the result graph is returned.
+///
+/// This is called once there are no interesting vertices left, that is, the clique graph is not
+/// connected (e.g. because the original graph has isolated vertices). Joining the new tree to the
+/// already constructed tree is left to the caller, which is valid since bags from different
+/// components are disjoint.
+fn start_spanning_tree_of_next_component<O, S: BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    result_graph: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    clique_graph_remaining_vertices: &mut HashSet<NodeIndex, S>,
+    currently_interesting_vertices: &mut HashSet<(NodeIndex, NodeIndex), S>,
+    node_index_map: &mut HashMap<NodeIndex, NodeIndex, S>,
+) -> NodeIndex {
+    let new_root_clique = *clique_graph_remaining_vertices
+        .iter()
+        .min()
+        .expect("There should be remaining vertices");
+    clique_graph_remaining_vertices.remove(&new_root_clique);
+
+    let new_root_res = result_graph.add_node(
+        clique_graph
+            .node_weight(new_root_clique)
+            .expect("Vertices in clique graph should have bags as weights")
+            .clone(),
+    );
+    node_index_map.insert(new_root_clique, new_root_res);
+
+    for neighbor in clique_graph.neighbors(new_root_clique) {
+        if clique_graph_remaining_vertices.contains(&neighbor) {
+            currently_interesting_vertices.insert((new_root_res, neighbor));
+        }
+    }
+
+    new_root_res
 }
 
 /// Finds the cheapest edge to a vertex not yet in the result graph considering the bags in the result graph
@@ -403,21 +899,52 @@ fn fill_bags_updating_edges<O, S: BuildHasher>(
 /// Returns a tuple with a node index from the result graph in the first and node index from the clique graph
 /// in the second entry. The cheapest edge being the edge between these two nodes only they are different
 /// in different representations (result and clique graph respectively)
-fn find_cheapest_vertex<O: Ord, S>(
+///
+/// Returns None if there are no interesting vertices, that is, if the current spanning tree spans a
+/// component of the clique graph.
+///
+/// If rng is given, ties between equally cheap edges are broken uniformly at random (independently
+/// of the order of the hash set). Otherwise an arbitrary cheapest edge is returned.
+fn find_cheapest_vertex<O: Ord, S, R: RngCore + ?Sized>(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     currently_interesting_vertices: &HashSet<(NodeIndex, NodeIndex), S>,
-) -> (NodeIndex, NodeIndex) {
-    *currently_interesting_vertices
+    rng: Option<&mut R>,
+) -> Option<(NodeIndex, NodeIndex)> {
+    let edge_weight =
+        |(vertex_res_graph, interesting_vertex_clique_graph): &(NodeIndex, NodeIndex)| {
+            edge_weight_heuristic(
+                result_graph
+                    .node_weight(*vertex_res_graph)
+                    .expect(&format!("Vertex {:?} should have weight", vertex_res_graph)),
+                clique_graph
+                    .node_weight(*interesting_vertex_clique_graph)
+                    .expect("Vertices should have weight"),
+            )
+        };
+
+    let Some(rng) = rng else {
+        return currently_interesting_vertices
+            .iter()
+            .min_by_key(|candidate| edge_weight(candidate))
+            .copied();
+    };
+
+    let mut cheapest_candidates: Vec<(NodeIndex, NodeIndex)> = currently_interesting_vertices
         .iter()
-        .min_by_key(|(vertex_res_graph, interesting_vertex_clique_graph)| edge_weight_heuristic(result_graph.node_weight(*vertex_res_graph).expect(&format!("Vertex {:?} should have weight", vertex_res_graph)), clique_graph.node_weight(*interesting_vertex_clique_graph).expect("Vertices should have weight"))).expect("There should be interesting vertices since there are vertices left and the graph is connected")
+        .copied()
+        .min_set_by_key(|candidate| edge_weight(candidate));
+    // Sort the candidates such that the choice only depends on the rng and not on the hasher
+    cheapest_candidates.sort();
+    cheapest_candidates.choose(rng).copied()
 }
 
 pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + BuildHasher + Clone>(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    fill_options: &mut FillOptions,
 ) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
     let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
     // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
@@ -454,12 +981,26 @@ pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + Buil
     node_index_map.insert(first_vertex_clique, first_vertex_res);
 
     while !clique_graph_remaining_vertices.is_empty() {
-        let (cheapest_vertex_res, cheapest_vertex_clique) = find_cheapest_vertex(
+        let Some((cheapest_vertex_res, cheapest_vertex_clique)) = find_cheapest_vertex(
             &clique_graph,
             &result_graph,
             edge_weight_heuristic,
             &currently_interesting_vertices,
-        );
+            fill_options.rng.as_deref_mut(),
+        ) else {
+            let new_root_res = start_spanning_tree_of_next_component(
+                clique_graph,
+                &mut result_graph,
+                &mut clique_graph_remaining_vertices,
+                &mut currently_interesting_vertices,
+                &mut node_index_map,
+            );
+            let edge_weight =
+                edge_weight_heuristic(&result_graph[first_vertex_res], &result_graph[new_root_res]);
+            result_graph.add_edge(first_vertex_res, new_root_res, edge_weight);
+            tree_predecessor_map.insert(new_root_res, (first_vertex_res, 0));
+            continue;
+        };
         clique_graph_remaining_vertices.remove(&cheapest_vertex_clique);
 
         // Update result graph
@@ -503,6 +1044,7 @@ pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + Buil
             .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_vertex_clique));
 
         // Fill bags from result graph
+        let fill_start = Instant::now();
         for vertex_from_starting_graph in result_graph
             .node_weight(new_vertex_res)
             .expect("Vertex should have weight since it was just added")
@@ -518,17 +1060,19 @@ pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + Buil
                                 Default::default();
                             vertices_that_need_path_filled.insert(new_vertex_res);
                             vertices_that_need_path_filled.insert(*vertex_res_graph);
-                            crate::fill_bags_along_paths::fill_bags_until_common_predecessor(
+                            crate::fill_bags_along_paths::fill_bags_until_common_predecessor_with_options(
                                 &mut result_graph,
                                 &tree_predecessor_map,
                                 &vertex_from_starting_graph,
                                 &vertices_that_need_path_filled,
+                                fill_options,
                             )
                         }
                     }
                 }
             }
         }
+        fill_options.record_fill_time(fill_start);
     }
 
     result_graph
@@ -538,6 +1082,9 @@ pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + Buil
 /// using edge weights in prim's algorithm, the weight of an edge (u,v) (v is not yet in the
 /// spanning tree) is the size of the biggest bag in the spanning tree if v was added to the
 /// spanning tree and the bags were filled up/updated accordingly.
+///
+/// Only the filling up of the bags of the resulting tree is recorded in the stats and churn of the
+/// [FillOptions], not the filling up done to compute the weights.
 pub fn fill_bags_while_generating_mst_least_bag_size<
     N,
     E,
@@ -546,6 +1093,7 @@ pub fn fill_bags_while_generating_mst_least_bag_size<
 >(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    fill_options: &mut FillOptions,
 ) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
     let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
     // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
@@ -577,6 +1125,18 @@ pub fn fill_bags_while_generating_mst_least_bag_size<
     node_index_map.insert(first_vertex_clique, first_vertex_res);
 
     while !clique_graph_remaining_vertices.is_empty() {
+        if currently_interesting_vertices.is_empty() {
+            let new_root_res = start_spanning_tree_of_next_component(
+                clique_graph,
+                &mut result_graph,
+                &mut clique_graph_remaining_vertices,
+                &mut currently_interesting_vertices,
+                &mut node_index_map,
+            );
+            result_graph.add_edge(first_vertex_res, new_root_res, O::default());
+            continue;
+        }
+
         let (cheapest_old_vertex_res, cheapest_vertex_clique) = find_vertex_that_minimizes_bag_size(
             &clique_graph,
             &result_graph,
@@ -611,13 +1171,16 @@ pub fn fill_bags_while_generating_mst_least_bag_size<
         currently_interesting_vertices
             .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_vertex_clique));
 
+        let fill_start = Instant::now();
         fill_bags_from_result_graph(
             &mut result_graph,
             cheapest_new_vertex_res,
             cheapest_old_vertex_res,
             &clique_graph_map,
             &node_index_map,
+            fill_options,
         );
+        fill_options.record_fill_time(fill_start);
     }
 
     result_graph
@@ -661,10 +1224,636 @@ fn find_vertex_that_minimizes_bag_size<O: Ord + Default + Clone, S: BuildHasher
                 cheapest_new_vertex_res,
                 *vertex_res_graph,
                 clique_graph_map,
-                node_index_map
+                node_index_map,
+                &mut FillOptions::default(),
             );
 
             // Find treewidth (biggest bag size) of 
             crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition(&result_graph)
         }).expect("There should be interesting vertices since there are vertices left and the graph is connected")
 }
+
+/// Computes a tree decomposition similar to [fill_bags_while_generating_mst] except that the
+/// vertices of the clique graph are attached to the spanning tree in an order consistent with the
+/// given elimination ordering instead of by edge weight.
+///
+/// A clique is destroyed as soon as the first of its vertices is eliminated, so cliques whose first
+/// eliminated vertex comes late in the ordering are close to the root of the tree decomposition
+/// given by the ordering. Thus the spanning tree starts at the clique destroyed last and in each
+/// step the adjacent clique destroyed last is attached, ties being broken by the edge heuristic.
+/// Vertices not contained in the ordering are treated as eliminated after all others.
+pub fn fill_bags_while_generating_mst_ordering_guided<O: Ord, S: Default + BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    ordering: &[NodeIndex],
+    fill_options: &mut FillOptions,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let position_in_ordering: HashMap<NodeIndex, usize, S> = ordering
+        .iter()
+        .enumerate()
+        .map(|(position, vertex)| (*vertex, position))
+        .collect();
+    // The position in the ordering at which the clique is destroyed
+    let destruction_time = |clique_vertex: NodeIndex| {
+        clique_graph[clique_vertex]
+            .iter()
+            .map(|vertex| *position_in_ordering.get(vertex).unwrap_or(&ordering.len()))
+            .min()
+            .unwrap_or(ordering.len())
+    };
+
+    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
+    // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
+    let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+
+    let first_vertex_clique = clique_graph
+        .node_indices()
+        .max_by_key(|vertex| (destruction_time(*vertex), std::cmp::Reverse(*vertex)))
+        .expect("Graph shouldn't be empty");
+
+    // Keeps track of the remaining vertices from the clique graph that still need to be added to
+    // the result_graph
+    let mut clique_graph_remaining_vertices: HashSet<NodeIndex, S> = clique_graph
+        .node_indices()
+        .filter(|vertex| vertex != &first_vertex_clique)
+        .collect();
+
+    // Keeps track of the vertices that could be added to the current sub-tree-graph
+    // First Tuple entry is node_index from the result graph that has an outgoing edge
+    // Second tuple entry is node_index from the clique graph that is the interesting vertex
+    let mut currently_interesting_vertices: HashSet<(NodeIndex, NodeIndex), S> = Default::default();
+
+    let first_vertex_res = result_graph.add_node(
+        clique_graph
+            .node_weight(first_vertex_clique)
+            .expect("Vertices in clique graph should have bags as weights")
+            .clone(),
+    );
+
+    // Add vertices that are reachable from first vertex
+    for neighbor in clique_graph.neighbors(first_vertex_clique) {
+        currently_interesting_vertices.insert((first_vertex_res, neighbor));
+    }
+    node_index_map.insert(first_vertex_clique, first_vertex_res);
+
+    while !clique_graph_remaining_vertices.is_empty() {
+        if currently_interesting_vertices.is_empty() {
+            let new_root_res = start_spanning_tree_of_next_component(
+                clique_graph,
+                &mut result_graph,
+                &mut clique_graph_remaining_vertices,
+                &mut currently_interesting_vertices,
+                &mut node_index_map,
+            );
+            let edge_weight =
+                edge_weight_heuristic(&result_graph[first_vertex_res], &result_graph[new_root_res]);
+            result_graph.add_edge(first_vertex_res, new_root_res, edge_weight);
+            continue;
+        }
+
+        let (cheapest_old_vertex_res, cheapest_new_vertex_clique) = *currently_interesting_vertices
+            .iter()
+            .min_by_key(|(vertex_res_graph, interesting_vertex_clique_graph)| {
+                (
+                    std::cmp::Reverse(destruction_time(*interesting_vertex_clique_graph)),
+                    edge_weight_heuristic(
+                        &result_graph[*vertex_res_graph],
+                        &clique_graph[*interesting_vertex_clique_graph],
+                    ),
+                )
+            })
+            .expect("There should be interesting vertices");
+        clique_graph_remaining_vertices.remove(&cheapest_new_vertex_clique);
+
+        // Update result graph
+        let cheapest_new_vertex_res = result_graph.add_node(
+            clique_graph
+                .node_weight(cheapest_new_vertex_clique)
+                .expect("Vertices in clique graph should have bags as weights")
+                .clone(),
+        );
+
+        node_index_map.insert(cheapest_new_vertex_clique, cheapest_new_vertex_res);
+        let edge_weight = edge_weight_heuristic(
+            &result_graph[cheapest_old_vertex_res],
+            &result_graph[cheapest_new_vertex_res],
+        );
+        result_graph.add_edge(
+            cheapest_old_vertex_res,
+            cheapest_new_vertex_res,
+            edge_weight,
+        );
+
+        // Update currently interesting vertices
+        for neighbor in clique_graph.neighbors(cheapest_new_vertex_clique) {
+            if clique_graph_remaining_vertices.contains(&neighbor) {
+                currently_interesting_vertices.insert((cheapest_new_vertex_res, neighbor));
+            }
+        }
+
+        currently_interesting_vertices
+            .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_new_vertex_clique));
+
+        let fill_start = Instant::now();
+        fill_bags_from_result_graph(
+            &mut result_graph,
+            cheapest_new_vertex_res,
+            cheapest_old_vertex_res,
+            &clique_graph_map,
+            &node_index_map,
+            fill_options,
+        );
+        fill_options.record_fill_time(fill_start);
+    }
+
+    result_graph
+}
+
+/// Computes a tree decomposition similar to [fill_bags_while_generating_mst] except that instead
+/// of prim's algorithm, kruskal's algorithm is used to construct the spanning tree. That is, all bags
+/// start as trees of their own and the globally cheapest edge (according to the edge heuristic
+/// evaluated on the current bags) between two different trees is added in each step. Whenever two
+/// trees are joined, the bags on the path between the occurrences of each vertex in both trees are
+/// filled up.
+///
+/// Since no single seed is needed, a disconnected clique graph naturally yields a spanning forest,
+/// whose trees are joined by edges to the first vertex afterwards (bags from different components
+/// are disjoint, so this results in a valid tree decomposition).
+pub fn fill_bags_while_generating_mst_kruskal<O: Ord, S: Default + BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    fill_options: &mut FillOptions,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    // The vertices of the result graph have the same node indices as the ones in the clique graph
+    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        clique_graph.filter_map(|_, bag| Some(bag.clone()), |_, _| None);
+    let mut trees = UnionFind::<usize>::new(clique_graph.node_count());
+    // The vertices from the starting graph that are contained in the bags of each tree, indexed by
+    // the representative of the tree in the union find structure
+    let mut vertices_of_trees: Vec<HashSet<NodeIndex, S>> =
+        clique_graph.node_weights().cloned().collect();
+
+    loop {
+        let cheapest_edge = clique_graph
+            .edge_indices()
+            .filter_map(|edge| clique_graph.edge_endpoints(edge))
+            .filter(|(first_vertex, second_vertex)| {
+                !trees.equiv(first_vertex.index(), second_vertex.index())
+            })
+            .min_by_key(|(first_vertex, second_vertex)| {
+                edge_weight_heuristic(&result_graph[*first_vertex], &result_graph[*second_vertex])
+            });
+        let Some((first_vertex, second_vertex)) = cheapest_edge else {
+            break;
+        };
+
+        let first_tree = trees.find(first_vertex.index());
+        let second_tree = trees.find(second_vertex.index());
+        result_graph.add_edge(
+            first_vertex,
+            second_vertex,
+            edge_weight_heuristic(&result_graph[first_vertex], &result_graph[second_vertex]),
+        );
+
+        // Connect the occurrences of each vertex contained in both trees
+        let common_vertices: Vec<NodeIndex> = vertices_of_trees[first_tree]
+            .intersection(&vertices_of_trees[second_tree])
+            .copied()
+            .collect();
+        let fill_start = Instant::now();
+        for vertex_from_starting_graph in common_vertices {
+            let vertices_in_clique_graph = clique_graph_map
+                .get(&vertex_from_starting_graph)
+                .expect("Vertex should be contained in a clique");
+            let occurrence_in_tree = |tree: usize| {
+                *vertices_in_clique_graph
+                    .iter()
+                    .find(|vertex_in_clique_graph| {
+                        trees.find(vertex_in_clique_graph.index()) == tree
+                    })
+                    .expect("Vertex of a tree should be contained in a clique of the tree")
+            };
+            let first_occurrence = occurrence_in_tree(first_tree);
+            let second_occurrence = occurrence_in_tree(second_tree);
+            fill_bags(
+                first_occurrence,
+                second_occurrence,
+                &mut result_graph,
+                vertex_from_starting_graph,
+                fill_options,
+            );
+        }
+        fill_options.record_fill_time(fill_start);
+
+        trees.union(first_tree, second_tree);
+        let second_tree_vertices = std::mem::take(&mut vertices_of_trees[second_tree]);
+        vertices_of_trees[first_tree].extend(second_tree_vertices);
+        let joined_tree = trees.find(first_tree);
+        if joined_tree != first_tree {
+            vertices_of_trees.swap(first_tree, joined_tree);
+        }
+    }
+
+    // Join the trees of the spanning forest if the clique graph is not connected
+    if let Some(first_vertex) = result_graph.node_indices().next() {
+        for vertex in result_graph.node_indices().collect::<Vec<_>>() {
+            if trees.union(first_vertex.index(), vertex.index()) {
+                let edge_weight =
+                    edge_weight_heuristic(&result_graph[first_vertex], &result_graph[vertex]);
+                result_graph.add_edge(first_vertex, vertex, edge_weight);
+            }
+        }
+    }
+
+    result_graph
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasherDefault, RandomState};
+
+    use rustc_hash::FxHasher;
+
+    use super::*;
+    use crate::construct_clique_graph::construct_clique_graph_with_bags;
+    use crate::find_maximal_cliques::find_maximal_cliques;
+
+    type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+    #[test]
+    fn test_fill_stats_do_not_change_result() {
+        // The hasher needs to be deterministic for both computations to produce the same result
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let cliques: Vec<Vec<_>> =
+                find_maximal_cliques::<Vec<_>, _, FxBuildHasher>(&test_graph.graph).collect();
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+            let mut fill_stats = FillStats::default();
+            let with_stats = fill_bags_while_generating_mst::<i32, i32, _, FxBuildHasher>(
+                &clique_graph,
+                crate::negative_intersection,
+                clique_graph_map.clone(),
+                false,
+                &mut FillOptions {
+                    stats: Some(&mut fill_stats),
+                    ..Default::default()
+                },
+            );
+            let without_stats = fill_bags_while_generating_mst::<i32, i32, _, FxBuildHasher>(
+                &clique_graph,
+                crate::negative_intersection,
+                clique_graph_map,
+                false,
+                &mut FillOptions::default(),
+            );
+
+            assert_eq!(
+                with_stats.node_weights().collect::<Vec<_>>(),
+                without_stats.node_weights().collect::<Vec<_>>(),
+                "Test graph: {}",
+                i
+            );
+            assert!(fill_stats.insertions <= fill_stats.path_walks * fill_stats.max_path_length);
+        }
+    }
+
+    #[test]
+    fn test_fill_stats_record_insertions() {
+        // Test graph 0 is chordal, so the spanning tree of its clique graph minimizing negative
+        // intersection is a clique tree and nothing needs to be filled up. Test graph 1 isn't.
+        let test_graph = crate::tests::setup_test_graph(1);
+        let cliques: Vec<Vec<_>> =
+            find_maximal_cliques::<Vec<_>, _, RandomState>(&test_graph.graph).collect();
+        let (clique_graph, clique_graph_map) =
+            construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+        let mut fill_stats = FillStats::default();
+        fill_bags_while_generating_mst::<i32, i32, _, RandomState>(
+            &clique_graph,
+            crate::negative_intersection,
+            clique_graph_map.clone(),
+            false,
+            &mut FillOptions {
+                stats: Some(&mut fill_stats),
+                ..Default::default()
+            },
+        );
+        assert!(fill_stats.insertions > 0);
+        assert!(fill_stats.max_path_length >= 1);
+
+        let mut fill_stats = FillStats::default();
+        fill_bags_while_generating_mst_using_tree::<i32, i32, _, RandomState>(
+            &clique_graph,
+            crate::negative_intersection,
+            clique_graph_map,
+            &mut FillOptions {
+                stats: Some(&mut fill_stats),
+                ..Default::default()
+            },
+        );
+        assert!(fill_stats.insertions > 0);
+        assert!(fill_stats.max_path_length >= 1);
+    }
+
+    #[test]
+    fn test_kruskal_gives_valid_decomposition() {
+        // Test graph 0 has isolated vertices and thus a clique graph that is not connected
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let cliques: Vec<Vec<_>> =
+                find_maximal_cliques::<Vec<_>, _, RandomState>(&test_graph.graph).collect();
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+            assert_eq!(
+                petgraph::algo::connected_components(&clique_graph) > 1,
+                i == 0,
+                "Test graph: {}",
+                i
+            );
+
+            let tree_decomposition = fill_bags_while_generating_mst_kruskal::<_, RandomState>(
+                &clique_graph,
+                crate::negative_intersection,
+                clique_graph_map,
+                &mut FillOptions::default(),
+            );
+
+            assert!(
+                crate::check_tree_decomposition(
+                    &test_graph.graph,
+                    &tree_decomposition,
+                    &None,
+                    &None
+                ),
+                "Test graph: {}",
+                i
+            );
+            assert!(
+                crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition(
+                    &tree_decomposition
+                ) >= test_graph.treewidth,
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_recorded_separators_are_subsets_of_joined_bags() {
+        // The maximal cliques of a 3-tree with 20 vertices are its 17 cliques of size 4
+        let graph = crate::generate_k_tree(3, 20).expect("k should be smaller or eq to n");
+        let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, RandomState>(&graph).collect();
+        assert_eq!(cliques.len(), 17);
+        let (clique_graph, clique_graph_map) =
+            construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+        let mut separators = Vec::new();
+        let tree_decomposition = fill_bags_while_generating_mst::<i32, i32, _, RandomState>(
+            &clique_graph,
+            crate::negative_intersection,
+            clique_graph_map,
+            false,
+            &mut FillOptions {
+                separators: Some(&mut separators),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(separators.len(), clique_graph.node_count() - 1);
+        for (vertex, separator) in separators {
+            let attachment_vertex = tree_decomposition
+                .neighbors(vertex)
+                .filter(|neighbour| neighbour < &vertex)
+                .min()
+                .expect("Added vertex should be attached to a vertex added before");
+            // The clique graph is connected, so each clique is attached to an intersecting one
+            assert!(!separator.is_empty());
+            assert!(separator
+                .iter()
+                .all(|v| tree_decomposition[vertex].contains(v)
+                    && tree_decomposition[attachment_vertex].contains(v)));
+        }
+    }
+
+    #[test]
+    fn test_every_bag_is_finalized_exactly_once() {
+        let mut rng = rand::thread_rng();
+        let mut graphs: Vec<_> = (0..3)
+            .map(|i| crate::tests::setup_test_graph(i).graph)
+            .collect();
+        graphs.push(
+            crate::generate_partial_k_tree(4, 40, 30, &mut rng)
+                .expect("k should be smaller or eq to n"),
+        );
+
+        for (i, graph) in graphs.iter().enumerate() {
+            let cliques: Vec<Vec<_>> =
+                find_maximal_cliques::<Vec<_>, _, RandomState>(graph).collect();
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+            let mut finalized_bags = Vec::new();
+            let (tree_decomposition, _) = fill_bags_while_generating_mst_with_trace(
+                Cow::Borrowed(&clique_graph),
+                crate::negative_intersection,
+                clique_graph_map,
+                &mut FillOptions::default(),
+                Some(&mut |vertex, bag: &HashSet<NodeIndex, RandomState>| {
+                    finalized_bags.push((vertex, bag.clone()))
+                }),
+                false,
+            );
+
+            let mut finalized_vertices: Vec<_> =
+                finalized_bags.iter().map(|(vertex, _)| *vertex).collect();
+            finalized_vertices.sort();
+            assert_eq!(
+                finalized_vertices,
+                tree_decomposition.node_indices().collect::<Vec<_>>(),
+                "Graph: {}",
+                i
+            );
+            for (vertex, bag) in finalized_bags {
+                assert_eq!(bag, tree_decomposition[vertex], "Graph: {}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_discarding_finalized_bags_keeps_maximum_bag_sizes() {
+        let mut graphs: Vec<_> = (0..3)
+            .map(|i| crate::tests::setup_test_graph(i).graph)
+            .collect();
+        graphs.push(
+            crate::generate_partial_k_tree(4, 40, 30, &mut rand::thread_rng())
+                .expect("k should be smaller or eq to n"),
+        );
+        // An isolated vertex gives a clique graph with two components
+        graphs[0].add_node(0);
+
+        for (i, graph) in graphs.iter().enumerate() {
+            let cliques: Vec<Vec<_>> =
+                find_maximal_cliques::<Vec<_>, _, FxBuildHasher>(graph).collect();
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+            let (_, maximum_bag_sizes) = fill_bags_while_generating_mst_with_trace(
+                Cow::Borrowed(&clique_graph),
+                crate::negative_intersection,
+                clique_graph_map.clone(),
+                &mut FillOptions::default(),
+                None,
+                false,
+            );
+            let mut maximum_reported_bag_size = 0;
+            let (discarded_tree_decomposition, discarded_maximum_bag_sizes) =
+                fill_bags_while_generating_mst_with_trace(
+                    Cow::Owned(clique_graph),
+                    crate::negative_intersection,
+                    clique_graph_map,
+                    &mut FillOptions::default(),
+                    Some(&mut |_, bag: &HashSet<NodeIndex, FxBuildHasher>| {
+                        maximum_reported_bag_size = maximum_reported_bag_size.max(bag.len())
+                    }),
+                    true,
+                );
+
+            assert_eq!(
+                discarded_maximum_bag_sizes, maximum_bag_sizes,
+                "Graph: {}",
+                i
+            );
+            assert_eq!(
+                Some(&maximum_reported_bag_size),
+                maximum_bag_sizes.last(),
+                "Graph: {}",
+                i
+            );
+            assert!(discarded_tree_decomposition
+                .node_weights()
+                .all(|bag| bag.is_empty()));
+        }
+    }
+
+    #[test]
+    fn test_clique_graph_with_two_components() {
+        let cliques: Vec<Vec<NodeIndex>> = vec![vec![0, 1, 2], vec![4, 5], vec![1, 2, 3]]
+            .into_iter()
+            .map(|clique| clique.into_iter().map(NodeIndex::new).collect())
+            .collect();
+        let (clique_graph, clique_graph_map) =
+            construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+        assert_eq!(
+            find_cheapest_vertex::<_, _, dyn RngCore>(
+                &clique_graph,
+                &clique_graph,
+                crate::negative_intersection,
+                &HashSet::<_, RandomState>::default(),
+                None,
+            ),
+            None
+        );
+
+        let tree_decomposition = fill_bags_while_generating_mst::<i32, i32, _, RandomState>(
+            &clique_graph,
+            crate::negative_intersection,
+            clique_graph_map,
+            false,
+            &mut FillOptions::default(),
+        );
+
+        assert_eq!(tree_decomposition.node_count(), 3);
+        assert_eq!(tree_decomposition.edge_count(), 2);
+        assert_eq!(petgraph::algo::connected_components(&tree_decomposition), 1);
+    }
+
+    #[test]
+    fn test_bag_churn_sums_up_to_insertions() {
+        let graph = crate::generate_partial_k_tree(4, 40, 30, &mut rand::thread_rng())
+            .expect("k should be smaller or eq to n");
+        let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, RandomState>(&graph).collect();
+        let (clique_graph, clique_graph_map) =
+            construct_clique_graph_with_bags(cliques, crate::negative_intersection);
+
+        let mut fill_stats = FillStats::default();
+        let mut bag_churn = HashMap::new();
+        let tree_decomposition = fill_bags_while_generating_mst::<i32, i32, _, RandomState>(
+            &clique_graph,
+            crate::negative_intersection,
+            clique_graph_map,
+            false,
+            &mut FillOptions {
+                stats: Some(&mut fill_stats),
+                churn: Some(&mut bag_churn),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(bag_churn.values().sum::<usize>(), fill_stats.insertions);
+        assert!(bag_churn
+            .iter()
+            .all(|(vertex, churn)| *churn > 0 && *churn <= tree_decomposition[*vertex].len()));
+    }
+
+    #[test]
+    fn test_random_tie_breaking_explores_different_valid_decompositions() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let graph = crate::generate_k_tree(2, 20).expect("k should be smaller or eq to n");
+        let cliques: Vec<Vec<_>> =
+            find_maximal_cliques::<Vec<_>, _, FxBuildHasher>(&graph).collect();
+
+        let mut spanning_trees: HashSet<Vec<(Vec<NodeIndex>, Vec<NodeIndex>)>> = HashSet::new();
+        for seed in 0..10 {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques.clone(), crate::constant);
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            // The constant weight function makes all edges of the clique graph tie
+            let tree_decomposition = fill_bags_while_generating_mst::<i32, i32, _, FxBuildHasher>(
+                &clique_graph,
+                crate::constant,
+                clique_graph_map,
+                false,
+                &mut FillOptions {
+                    rng: Some(&mut rng),
+                    ..Default::default()
+                },
+            );
+            assert!(
+                crate::check_tree_decomposition(&graph, &tree_decomposition, &None, &None),
+                "Seed: {}",
+                seed
+            );
+
+            let sorted_bag = |vertex: NodeIndex| -> Vec<NodeIndex> {
+                let mut bag: Vec<_> = tree_decomposition[vertex].iter().copied().collect();
+                bag.sort();
+                bag
+            };
+            let mut spanning_tree: Vec<_> = tree_decomposition
+                .edge_indices()
+                .map(|edge| {
+                    let (first_vertex, second_vertex) = tree_decomposition
+                        .edge_endpoints(edge)
+                        .expect("Edge should have endpoints");
+                    let (first_bag, second_bag) =
+                        (sorted_bag(first_vertex), sorted_bag(second_vertex));
+                    (
+                        first_bag.clone().min(second_bag.clone()),
+                        first_bag.max(second_bag),
+                    )
+                })
+                .collect();
+            spanning_tree.sort();
+            spanning_trees.insert(spanning_tree);
+        }
+
+        assert!(spanning_trees.len() > 1);
+    }
+}
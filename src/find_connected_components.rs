@@ -1,7 +1,7 @@
 use petgraph::graph::NodeIndex;
 use petgraph::visit::{EdgeCount, IntoNeighbors, IntoNodeIdentifiers};
 use petgraph::{Graph, Undirected};
-use std::hash::BuildHasher;
+use std::hash::{BuildHasher, RandomState};
 use std::iter::from_fn;
 use std::{collections::HashSet, hash::Hash};
 
@@ -30,6 +30,30 @@ where
     })
 }
 
+/// Returns the index of the connected component of each vertex, indexed by the index of its
+/// NodeIndex. The components are numbered from 0 in the order of their smallest vertex, which is the
+/// order in which [find_connected_components] returns them.
+///
+/// Uses the same breadth first search as [find_connected_components].
+pub fn component_labels<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<usize> {
+    let mut labels: Vec<Option<usize>> = vec![None; graph.node_count()];
+    let mut number_of_components = 0;
+
+    for vertex in graph.node_indices() {
+        if labels[vertex.index()].is_none() {
+            for vertex_in_component in breadth_first_search::<_, RandomState>(&graph, vertex) {
+                labels[vertex_in_component.index()] = Some(number_of_components);
+            }
+            number_of_components += 1;
+        }
+    }
+
+    labels
+        .into_iter()
+        .map(|label| label.expect("Every vertex should be in a component"))
+        .collect()
+}
+
 /// Breadth first search implemented iteratively using a stack
 fn breadth_first_search<G, S: Default + BuildHasher>(
     graph: &G,
@@ -74,8 +98,6 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::hash::RandomState;
-
     use super::*;
 
     #[test]
@@ -98,4 +120,19 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_component_labels() {
+        let test_graph = crate::tests::setup_test_graph(0);
+
+        let labels = component_labels(&test_graph.graph);
+
+        assert_eq!(labels.len(), 11);
+        assert!(labels[0..7].iter().all(|label| *label == labels[0]));
+        assert!(labels[7..9].iter().all(|label| *label == labels[7]));
+        assert!(labels[9..11].iter().all(|label| *label == labels[9]));
+        let mut distinct_labels = vec![labels[0], labels[7], labels[9]];
+        distinct_labels.sort();
+        assert_eq!(distinct_labels, vec![0, 1, 2]);
+    }
 }
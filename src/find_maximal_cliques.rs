@@ -1,8 +1,19 @@
 use itertools::Itertools;
+use petgraph::graph::NodeIndex;
 use petgraph::visit::{GraphBase, IntoNeighborsDirected, IntoNodeIdentifiers, NodeCount};
+use petgraph::{Graph, Undirected};
+use rand::seq::IteratorRandom;
 use std::hash::BuildHasher;
+
+use crate::find_width_of_tree_decomposition::find_width_of_tree_decomposition;
+use crate::min_fill_heuristic::{
+    is_chordal, maximum_cardinality_search_ordering, width_of_elimination_ordering,
+};
 use std::iter::from_fn;
-use std::{collections::HashSet, hash::Hash};
+use std::{
+    collections::{BTreeMap, HashSet},
+    hash::Hash,
+};
 
 /// Returns an iterator that produces all [maximal cliques][https://en.wikipedia.org/wiki/Clique_(graph_theory)#Definitions]
 /// in the given graph in arbitrary order.
@@ -11,6 +22,43 @@ use std::{collections::HashSet, hash::Hash};
 pub fn find_maximal_cliques<TargetColl, G, S: Default + BuildHasher + Clone>(
     graph: G,
 ) -> impl Iterator<Item = TargetColl>
+where
+    G: NodeCount,
+    G: IntoNeighborsDirected,
+    G: IntoNodeIdentifiers,
+    G::NodeId: Eq + Hash,
+    TargetColl: FromIterator<G::NodeId>,
+    <G as GraphBase>::NodeId: 'static,
+{
+    find_maximal_cliques_with_pivot::<TargetColl, G, S>(graph, PivotStrategy::MaxAtccDegree)
+}
+
+/// Different strategies for choosing the pivot vertex in the Bron–Kerbosch algorithm used by
+/// [find_maximal_cliques_with_pivot]. The pivot is always chosen among the vertices adjacent to all
+/// vertices of the current clique (atcc), so all strategies enumerate the same cliques and only the
+/// running time differs.
+///
+/// MaxAtccDegree Chooses the vertex with the most neighbours in atcc (used by [find_maximal_cliques])
+///
+/// MaxCandidateDegree Chooses the vertex with the most neighbours among the current candidates,
+/// which minimizes the number of branches in the current step
+///
+/// Random Chooses a vertex uniformly at random
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PivotStrategy {
+    #[default]
+    MaxAtccDegree,
+    MaxCandidateDegree,
+    Random,
+}
+
+/// Returns an iterator that produces all [maximal cliques][https://en.wikipedia.org/wiki/Clique_(graph_theory)#Definitions]
+/// in the given graph in arbitrary order like [find_maximal_cliques] using the given strategy to
+/// choose the pivot vertices, see [PivotStrategy].
+pub fn find_maximal_cliques_with_pivot<TargetColl, G, S: Default + BuildHasher + Clone>(
+    graph: G,
+    pivot_strategy: PivotStrategy,
+) -> impl Iterator<Item = TargetColl>
 where
     G: NodeCount,
     G: IntoNeighborsDirected,
@@ -26,21 +74,15 @@ where
     let mut stack = vec![];
 
     let mut atcc: HashSet<G::NodeId, S> = graph.node_identifiers().collect();
-
-    let u = *atcc
-        .iter()
-        .max_by_key(|v| {
-            let mut tmp = graph.neighbors(**v).collect::<Vec<_>>();
-            tmp.retain(|w| atcc.contains(w));
-            tmp.len()
-        })
-        .expect("Graph shouldn't be empty");
+    let mut candidates: HashSet<G::NodeId, S> = graph.node_identifiers().collect();
 
     let mut promising_candidates: Vec<G::NodeId> = atcc.iter().cloned().collect();
-    let neighbors_u: HashSet<G::NodeId, S> = graph.neighbors(u).collect();
-    promising_candidates.retain(|v| !neighbors_u.contains(v));
-
-    let mut candidates: HashSet<G::NodeId, S> = graph.node_identifiers().collect();
+    // An empty graph has no pivot (and no maximal cliques)
+    if !atcc.is_empty() {
+        let u = choose_pivot(graph, &atcc, &candidates, pivot_strategy);
+        let neighbors_u: HashSet<G::NodeId, S> = graph.neighbors(u).collect();
+        promising_candidates.retain(|v| !neighbors_u.contains(v));
+    }
 
     // current clique - Q                       : Clique that is currently being constructed
     // candidates - cand                        : Current candidates that could be added to Q (current Clique) - special for handling cliques with the given set of nodes
@@ -85,14 +127,7 @@ where
                             atcc = atcc_q.clone();
                             candidates = candidates_q.clone();
 
-                            let u = *atcc
-                                .iter()
-                                .max_by_key(|v| {
-                                    let mut tmp = graph.neighbors(**v).collect::<Vec<_>>();
-                                    tmp.retain(|w| atcc.contains(w));
-                                    tmp.len()
-                                })
-                                .expect("Graph shouldn't be empty");
+                            let u = choose_pivot(graph, &atcc, &candidates, pivot_strategy);
                             promising_candidates = candidates.iter().cloned().collect();
                             let neighbors_u: HashSet<G::NodeId, S> = graph.neighbors(u).collect();
                             promising_candidates.retain(|v| !neighbors_u.contains(v));
@@ -111,6 +146,34 @@ where
     })
 }
 
+/// Chooses a pivot vertex among the vertices adjacent to all vertices of the current clique
+/// according to the given strategy, see [PivotStrategy].
+fn choose_pivot<G, S: BuildHasher>(
+    graph: G,
+    atcc: &HashSet<G::NodeId, S>,
+    candidates: &HashSet<G::NodeId, S>,
+    pivot_strategy: PivotStrategy,
+) -> G::NodeId
+where
+    G: IntoNeighborsDirected,
+    G::NodeId: Eq + Hash,
+{
+    match pivot_strategy {
+        PivotStrategy::MaxAtccDegree => atcc
+            .iter()
+            .max_by_key(|v| graph.neighbors(**v).filter(|w| atcc.contains(w)).count()),
+        PivotStrategy::MaxCandidateDegree => atcc.iter().max_by_key(|v| {
+            graph
+                .neighbors(**v)
+                .filter(|w| candidates.contains(w))
+                .count()
+        }),
+        PivotStrategy::Random => atcc.iter().choose(&mut rand::thread_rng()),
+    }
+    .copied()
+    .expect("Graph shouldn't be empty")
+}
+
 /// Returns an iterator that produces (once each) all cliques that are [maximal][https://en.wikipedia.org/wiki/Clique_(graph_theory)#Definitions]
 /// (and of size less than k) or of size k (and not necessarily maximal) in arbitrary order.
 /// If k is negative, k is set by the function as k = k + omega(G) where omega(G) is the clique number of G
@@ -174,6 +237,160 @@ where
     })
 }
 
+/// Returns an iterator that produces (once each) all cliques of size at least k and all
+/// [maximal cliques][https://en.wikipedia.org/wiki/Clique_(graph_theory)#Definitions] of size less
+/// than k in arbitrary order. The latter are kept so that every vertex is still contained in some
+/// clique. If k is less than 1, k = 1 is used instead.
+///
+/// The cliques are enumerated by taking all combinations of size at least k of each maximal
+/// clique. Since a maximal clique of size n has 2^n subsets, this is only feasible for graphs with
+/// small maximal cliques.
+pub fn find_all_cliques_of_size_at_least<TargetColl, G, S: Default + Clone + BuildHasher>(
+    graph: G,
+    k: usize,
+) -> impl Iterator<Item = TargetColl>
+where
+    G: NodeCount,
+    G: IntoNeighborsDirected,
+    G: IntoNodeIdentifiers,
+    G::NodeId: Eq + Hash + Ord,
+    TargetColl: FromIterator<G::NodeId>,
+    <G as GraphBase>::NodeId: 'static,
+{
+    let k = k.max(1);
+
+    let mut maximal_cliques = find_maximal_cliques::<Vec<_>, G, S>(graph);
+    let mut subsets_of_current_clique: Box<dyn Iterator<Item = Vec<G::NodeId>>> =
+        Box::new(std::iter::empty());
+    let mut seen_cliques = HashSet::<_, S>::default();
+    from_fn(move || loop {
+        if let Some(mut clique) = subsets_of_current_clique.next() {
+            clique.sort();
+            if seen_cliques.insert(clique.clone()) {
+                // Only return cliques that haven't been seen yet (subsets of different maximal
+                // cliques can coincide)
+                return Some(clique.into_iter().collect::<TargetColl>());
+            }
+        } else if let Some(maximal_clique) = maximal_cliques.next() {
+            if maximal_clique.len() <= k {
+                subsets_of_current_clique = Box::new(std::iter::once(maximal_clique));
+            } else {
+                let clique_size = maximal_clique.len();
+                subsets_of_current_clique =
+                    Box::new((k..=clique_size).flat_map(move |size| {
+                        maximal_clique.clone().into_iter().combinations(size)
+                    }));
+            }
+        } else {
+            return None;
+        }
+    })
+}
+
+/// Returns all [maximal cliques][https://en.wikipedia.org/wiki/Clique_(graph_theory)#Definitions]
+/// of the given graph that contain the given vertex in arbitrary order.
+///
+/// Instead of filtering all maximal cliques of the graph, the enumeration is restricted to the
+/// closed neighbourhood of the vertex. Since every vertex in the closed neighbourhood is adjacent to
+/// the vertex, the maximal cliques of the subgraph induced by the closed neighbourhood are exactly
+/// the maximal cliques of the graph containing the vertex.
+///
+/// **Panics**
+/// Panics if the vertex is not contained in the graph.
+pub fn maximal_cliques_through<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    vertex: NodeIndex,
+) -> Vec<Vec<NodeIndex>> {
+    let mut closed_neighbourhood: HashSet<NodeIndex, S> = graph.neighbors(vertex).collect();
+    closed_neighbourhood.insert(vertex);
+
+    // The vertices of the induced subgraph are labeled with their NodeIndex in the original graph
+    let neighbourhood_graph: Graph<NodeIndex, (), Undirected> = graph.filter_map(
+        |v, _| closed_neighbourhood.contains(&v).then_some(v),
+        |_, _| Some(()),
+    );
+
+    find_maximal_cliques::<Vec<_>, _, S>(&neighbourhood_graph)
+        .map(|clique| clique.into_iter().map(|v| neighbourhood_graph[v]).collect())
+        .collect()
+}
+
+/// Returns the lower bound omega(G) - 1 for the treewidth of the graph, where omega(G) is the size
+/// of a biggest clique of the graph. Returns 0 for graphs without edges.
+pub fn clique_number_lower_bound<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> usize {
+    if graph.edge_count() == 0 {
+        return 0;
+    }
+
+    find_maximal_cliques::<Vec<_>, _, S>(graph)
+        .map(|clique| clique.len())
+        .max()
+        .unwrap_or(1)
+        - 1
+}
+
+/// Returns the histogram of the sizes of the maximal cliques of the graph, mapping each size to the
+/// number of maximal cliques of that size. A few huge cliques make the clique graph heuristic
+/// struggle more than many small ones, since each maximal clique is contained in a bag.
+///
+/// Returns an empty map for graphs without vertices.
+pub fn maximal_clique_size_histogram<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for clique in find_maximal_cliques::<Vec<_>, _, S>(graph) {
+        *histogram.entry(clique.len()).or_insert(0) += 1;
+    }
+
+    histogram
+}
+
+/// Returns a maximal clique of the graph whose size minus one is closest to the width of the given
+/// tree decomposition of the graph (preferring bigger cliques), sorted by NodeIndex. Since each
+/// clique is contained in a bag, this is a maximum clique and thus the structural reason why the
+/// width can't be lower than its size minus one (see [clique_number_lower_bound]). If the width is
+/// bigger, the gap is not explained by a clique.
+///
+/// Returns an empty vector for graphs without vertices.
+pub fn dominating_clique<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Vec<NodeIndex> {
+    let width = find_width_of_tree_decomposition(decomposition);
+
+    let mut clique: Vec<NodeIndex> = find_maximal_cliques::<Vec<_>, _, S>(graph)
+        .min_by_key(|clique: &Vec<NodeIndex>| {
+            (
+                width.abs_diff(clique.len().saturating_sub(1)),
+                std::cmp::Reverse(clique.len()),
+            )
+        })
+        .unwrap_or_default();
+    clique.sort();
+
+    clique
+}
+
+/// Returns true if the computed width is provably the treewidth of the graph, that is, if it equals
+/// the lower bound omega(G) - 1. This is always the case for correct results on
+/// [chordal](crate::is_chordal) graphs, where omega(G) - 1 is the treewidth. For chordal graphs the
+/// lower bound is read off a perfect elimination ordering instead of enumerating all maximal
+/// cliques.
+pub fn is_result_optimal<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    computed_width: usize,
+) -> bool {
+    let lower_bound = if is_chordal(graph) {
+        width_of_elimination_ordering(graph, &maximum_cardinality_search_ordering(graph))
+    } else {
+        clique_number_lower_bound::<_, _, S>(graph)
+    };
+
+    computed_width == lower_bound
+}
+
 #[cfg(test)]
 mod tests {
     use std::hash::RandomState;
@@ -201,6 +418,30 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_find_maximum_cliques_of_empty_graph() {
+        let graph: petgraph::Graph<i32, i32, petgraph::Undirected> =
+            petgraph::Graph::new_undirected();
+
+        let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, RandomState>(&graph).collect();
+
+        assert!(cliques.is_empty());
+    }
+
+    #[test]
+    pub fn test_maximal_clique_size_histogram() {
+        let test_graph = crate::tests::setup_test_graph(0);
+
+        let histogram = maximal_clique_size_histogram::<_, _, RandomState>(&test_graph.graph);
+
+        let mut expected_histogram = BTreeMap::new();
+        for clique in test_graph.expected_max_cliques.iter() {
+            *expected_histogram.entry(clique.len()).or_insert(0) += 1;
+        }
+        assert_eq!(histogram, expected_histogram);
+        assert_eq!(histogram, BTreeMap::from([(2, 2), (3, 2), (4, 1)]));
+    }
+
     #[test]
     pub fn test_find_maximum_cliques_bounded() {
         let test_graph = crate::tests::setup_test_graph(0);
@@ -273,4 +514,162 @@ mod tests {
 
         assert_eq!(cliques, expected_bounded_max_cliques);
     }
+
+    #[test]
+    pub fn test_maximal_cliques_through() {
+        let test_graph = crate::tests::setup_test_graph(0);
+
+        let mut cliques =
+            maximal_cliques_through::<_, _, RandomState>(&test_graph.graph, NodeIndex::new(1));
+        for clique in cliques.iter_mut() {
+            clique.sort();
+        }
+        cliques.sort();
+
+        let expected_cliques: Vec<Vec<_>> = vec![vec![0, 1, 2, 5], vec![1, 3, 5]]
+            .into_iter()
+            .map(|v| v.into_iter().map(NodeIndex::new).collect())
+            .collect();
+        assert_eq!(cliques, expected_cliques);
+
+        for vertex in test_graph.graph.node_indices() {
+            let mut cliques =
+                maximal_cliques_through::<_, _, RandomState>(&test_graph.graph, vertex);
+            for clique in cliques.iter_mut() {
+                clique.sort();
+            }
+            cliques.sort();
+
+            let mut expected_cliques = test_graph.expected_max_cliques.clone();
+            expected_cliques.retain(|clique| clique.contains(&vertex));
+
+            assert_eq!(cliques, expected_cliques, "Vertex: {:?}", vertex);
+        }
+    }
+
+    #[test]
+    pub fn test_find_all_cliques_of_size_at_least() {
+        let test_graph = crate::tests::setup_test_graph(2);
+
+        let mut cliques: Vec<Vec<_>> =
+            find_all_cliques_of_size_at_least::<Vec<_>, _, RandomState>(&test_graph.graph, 3)
+                .collect();
+        for clique in cliques.iter_mut() {
+            clique.sort();
+        }
+        cliques.sort();
+
+        // Both maximal cliques of size 4, their 4 subsets of size 3 each, sharing {1, 2, 3}
+        let mut expected_cliques: Vec<Vec<_>> = vec![
+            vec![0, 1, 2, 3],
+            vec![1, 2, 3, 4],
+            vec![0, 1, 2],
+            vec![0, 1, 3],
+            vec![0, 2, 3],
+            vec![1, 2, 3],
+            vec![1, 2, 4],
+            vec![1, 3, 4],
+            vec![2, 3, 4],
+        ]
+        .into_iter()
+        .map(|v| v.into_iter().map(petgraph::graph::node_index).collect())
+        .collect();
+        expected_cliques.sort();
+
+        assert_eq!(cliques, expected_cliques);
+    }
+
+    #[test]
+    fn test_clique_number_lower_bound() {
+        let test_graph = crate::tests::setup_test_graph(2);
+        assert_eq!(
+            clique_number_lower_bound::<_, _, RandomState>(&test_graph.graph),
+            3
+        );
+
+        let mut edgeless_graph: Graph<(), (), Undirected> = Graph::new_undirected();
+        edgeless_graph.add_node(());
+        assert_eq!(
+            clique_number_lower_bound::<_, _, RandomState>(&edgeless_graph),
+            0
+        );
+    }
+
+    #[test]
+    fn test_pivot_strategies_find_identical_cliques() {
+        let test_graph = crate::tests::setup_test_graph(0);
+
+        let [max_atcc_degree, max_candidate_degree, random] = [
+            PivotStrategy::MaxAtccDegree,
+            PivotStrategy::MaxCandidateDegree,
+            PivotStrategy::Random,
+        ]
+        .map(|pivot_strategy| {
+            let mut cliques: Vec<Vec<_>> =
+                find_maximal_cliques_with_pivot::<Vec<_>, _, RandomState>(
+                    &test_graph.graph,
+                    pivot_strategy,
+                )
+                .map(|mut clique: Vec<_>| {
+                    clique.sort();
+                    clique
+                })
+                .collect();
+            cliques.sort();
+            cliques
+        });
+
+        assert_eq!(max_atcc_degree, test_graph.expected_max_cliques);
+        assert_eq!(max_atcc_degree, max_candidate_degree);
+        assert_eq!(max_atcc_degree, random);
+    }
+
+    #[test]
+    fn test_dominating_clique_of_k_tree() {
+        for k in 1..5 {
+            let k_tree = crate::generate_k_tree(k, 30).expect("k should be smaller or eq to n");
+            let result = crate::full_treewidth_result::<_, _, _, RandomState>(
+                &k_tree,
+                crate::SpanningTreeConstructionMethod::FilWh,
+                crate::negative_intersection,
+            );
+
+            let clique = dominating_clique(&k_tree, &result.decomposition);
+
+            assert_eq!(clique.len(), k + 1, "k: {}", k);
+            for (first_vertex, second_vertex) in clique.iter().tuple_combinations() {
+                assert!(k_tree.contains_edge(*first_vertex, *second_vertex));
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_result_optimal_on_k_tree() {
+        for k in 1..5 {
+            let k_tree = crate::generate_k_tree(k, 30).expect("k should be smaller or eq to n");
+            let computed_width = crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                &k_tree,
+                crate::negative_intersection,
+                crate::SpanningTreeConstructionMethod::FilWh,
+                false,
+                crate::CliqueSource::Maximal,
+                None,
+            );
+
+            assert!(is_result_optimal::<_, _, RandomState>(
+                &k_tree,
+                computed_width
+            ));
+            assert!(!is_result_optimal::<_, _, RandomState>(
+                &k_tree,
+                computed_width + 1
+            ));
+        }
+
+        let test_graph = crate::tests::setup_test_graph(1);
+        assert!(is_result_optimal::<_, _, RandomState>(
+            &test_graph.graph,
+            test_graph.treewidth
+        ));
+    }
 }
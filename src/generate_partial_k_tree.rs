@@ -1,8 +1,40 @@
 use petgraph::{graph::NodeIndex, visit::IntoNodeIdentifiers, Graph, Undirected};
 use rand::prelude::SliceRandom;
 use rand::{seq::IteratorRandom, Rng};
+use std::{fmt::Display, hash::BuildHasher};
 
-use crate::maximum_minimum_degree_plus;
+use crate::{
+    compute_treewidth_upper_bound, maximum_minimum_degree_plus, CliqueSource,
+    SpanningTreeConstructionMethod,
+};
+
+/// Errors for invalid parameters passed to the generators of (partial) k-trees.
+///
+/// KGreaterThanN A k-tree needs at least k vertices, so k must be smaller or equal to n
+///
+/// KZero A k-tree with k = 0 has no edges (and no cliques) and is therefore not generated
+///
+/// Callers who don't care about the reason can use [Result::ok] to get an [Option] instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenerateError {
+    KGreaterThanN { k: usize, n: usize },
+    KZero,
+}
+
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenerateError::KGreaterThanN { k, n } => write!(
+                f,
+                "k ({}) should be smaller or equal to the number of vertices n ({})",
+                k, n
+            ),
+            GenerateError::KZero => write!(f, "k should be greater than 0"),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
 
 /// Generates a [k-tree](https://en.wikipedia.org/wiki/K-tree) and then randomly removes p percent
 /// of the edges to get a [partial k-tree](https://en.wikipedia.org/wiki/Partial_k-tree). To
@@ -12,33 +44,66 @@ use crate::maximum_minimum_degree_plus;
 /// **Caution!**: Due to the randomness involved, this function could in theory take
 /// indefinitely to generate a partial k-tree with the desired treewidth.
 ///
-/// If p > 100 all edges will be removed. The Rng is passed in to increase performance when
-/// calling the function multiple times in a row.
+/// p is clamped to 100, that is, if p > 100 all edges will be removed (and the loop will only
+/// terminate if the maximum minimum degree of the graph is k anyways). The Rng is passed in to
+/// increase performance when calling the function multiple times in a row.
 ///
-/// Returns None if k > n.
+/// Returns an error if k > n or k == 0, see [GenerateError].
 pub fn generate_partial_k_tree_with_guaranteed_treewidth(
     k: usize,
     n: usize,
     p: usize,
     rng: &mut impl Rng,
-) -> Option<Graph<i32, i32, Undirected>> {
+) -> Result<Graph<i32, i32, Undirected>, GenerateError> {
     loop {
-        if let Some(graph) = generate_partial_k_tree(k, n, p, rng) {
-            if maximum_minimum_degree_plus(&graph) == k {
-                return Some(graph);
-            }
-        } else {
-            return None;
+        let graph = generate_partial_k_tree(k, n, p, rng)?;
+        if maximum_minimum_degree_plus(&graph) == k {
+            return Ok(graph);
         }
     }
 }
 
+/// Generates the given number of samples of partial k-trees with treewidth k (see
+/// [generate_partial_k_tree_with_guaranteed_treewidth]) and returns the mean of the widths computed
+/// by [compute_treewidth_upper_bound] with the given method, the
+/// [negative_intersection][crate::negative_intersection] edge weights and maximal cliques. Since
+/// the treewidth of each sample is k, the returned average is at least k.
+///
+/// Returns 0.0 if samples is 0 and an error if k > n or k == 0, see [GenerateError].
+pub fn average_width_over_samples<S: Default + BuildHasher + Clone>(
+    k: usize,
+    n: usize,
+    p: usize,
+    samples: usize,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    rng: &mut impl Rng,
+) -> Result<f64, GenerateError> {
+    if samples == 0 {
+        return Ok(0.0);
+    }
+
+    let mut total_width = 0;
+    for _ in 0..samples {
+        let graph = generate_partial_k_tree_with_guaranteed_treewidth(k, n, p, rng)?;
+        total_width += compute_treewidth_upper_bound::<_, _, _, S>(
+            &graph,
+            crate::negative_intersection,
+            treewidth_computation_method,
+            false,
+            CliqueSource::Maximal,
+            None,
+        );
+    }
+
+    Ok(total_width as f64 / samples as f64)
+}
+
 /// Generates a [k-tree](https://en.wikipedia.org/wiki/K-tree) and then randomly removes p percent
 /// of the edges to get a [partial k-tree](https://en.wikipedia.org/wiki/Partial_k-tree).
-/// If p > 100 all edges will be removed. The Rng is passed in to increase performance when calling
-/// the function multiple times in a row.
+/// p is clamped to 100, that is, if p > 100 all edges will be removed. The Rng is passed in to
+/// increase performance when calling the function multiple times in a row.
 ///
-/// Returns None if k > n.
+/// Returns an error if k > n or k == 0, see [GenerateError].
 ///
 /// The number of edges in a k_tree are k * (k - 1) / 2 + k * (n - k). So the number of removed
 /// edges in a partial_k_tree will be (k * (k - 1) / 2 + k * (n - k)) * p / 100 rounded down.
@@ -47,12 +112,16 @@ pub fn generate_partial_k_tree(
     n: usize,
     p: usize,
     rng: &mut impl Rng,
-) -> Option<Graph<i32, i32, Undirected>> {
-    if let Some(mut graph) = generate_k_tree(k, n) {
-        // The number of edges in a k-tree
-        let number_of_edges = k * (k - 1) / 2 + k * (n - k);
-        assert_eq!(number_of_edges, graph.edge_count());
-        let number_of_edges_to_be_removed = ((number_of_edges * p) / 100).min(number_of_edges);
+) -> Result<Graph<i32, i32, Undirected>, GenerateError> {
+    let mut graph = generate_k_tree(k, n)?;
+    // The number of edges in a k-tree
+    let number_of_edges = k * (k - 1) / 2 + k * (n - k);
+    assert_eq!(number_of_edges, graph.edge_count());
+    let number_of_edges_to_be_removed = ((number_of_edges * p) / 100).min(number_of_edges);
+    if number_of_edges_to_be_removed == number_of_edges {
+        // p is at least 100, so all edges are removed
+        graph.clear_edges();
+    } else {
         // Remove p percent of nodes
         for edge_to_be_removed in graph
             .edge_indices()
@@ -60,18 +129,18 @@ pub fn generate_partial_k_tree(
         {
             graph.remove_edge(edge_to_be_removed);
         }
-
-        Some(graph)
-    } else {
-        None
     }
+
+    Ok(graph)
 }
 
 /// Generates a [k-tree](https://en.wikipedia.org/wiki/K-tree) with n vertices and k in the definition.
-/// Returns None if k > n.
-pub fn generate_k_tree(k: usize, n: usize) -> Option<Graph<i32, i32, Undirected>> {
-    if k > n {
-        None
+/// Returns an error if k > n or k == 0, see [GenerateError].
+pub fn generate_k_tree(k: usize, n: usize) -> Result<Graph<i32, i32, Undirected>, GenerateError> {
+    if k == 0 {
+        Err(GenerateError::KZero)
+    } else if k > n {
+        Err(GenerateError::KGreaterThanN { k, n })
     } else {
         let mut graph = generate_complete_graph(k);
         let mut potential_cliques: Vec<Vec<_>> = vec![graph.node_identifiers().collect()];
@@ -92,10 +161,54 @@ pub fn generate_k_tree(k: usize, n: usize) -> Option<Graph<i32, i32, Undirected>
             }
         }
 
-        Some(graph)
+        Ok(graph)
     }
 }
 
+/// The number of pairings tried by [generate_random_regular] before giving up
+const RANDOM_REGULAR_ATTEMPTS: usize = 1000;
+
+/// Generates a random [d-regular graph](https://en.wikipedia.org/wiki/Regular_graph) with n
+/// vertices using the configuration model: Each vertex gets d points, the points are randomly
+/// paired up and each pair becomes an edge. Pairings resulting in self loops or multi-edges are
+/// rejected and a new pairing is tried, which yields a uniformly random d-regular graph. The Rng is
+/// passed in to increase performance when calling the function multiple times in a row.
+///
+/// Returns None if no such graph exists (n * d is odd or d >= n > 0) or if none of
+/// the tried pairings was valid, which gets likely for bigger d.
+pub fn generate_random_regular(
+    n: usize,
+    d: usize,
+    rng: &mut impl Rng,
+) -> Option<Graph<i32, i32, Undirected>> {
+    if (n * d) % 2 == 1 || (n > 0 && d >= n) {
+        return None;
+    }
+
+    let mut points: Vec<usize> = (0..n)
+        .flat_map(|vertex| std::iter::repeat(vertex).take(d))
+        .collect();
+    'attempts: for _ in 0..RANDOM_REGULAR_ATTEMPTS {
+        points.shuffle(rng);
+
+        let mut graph: Graph<i32, i32, Undirected> = Graph::with_capacity(n, n * d / 2);
+        for i in 0..n {
+            graph.add_node(i.try_into().unwrap());
+        }
+        for pair in points.chunks_exact(2) {
+            let (first_vertex, second_vertex) = (NodeIndex::new(pair[0]), NodeIndex::new(pair[1]));
+            if first_vertex == second_vertex || graph.contains_edge(first_vertex, second_vertex) {
+                continue 'attempts;
+            }
+            graph.add_edge(first_vertex, second_vertex, 0);
+        }
+
+        return Some(graph);
+    }
+
+    None
+}
+
 /// Generates a [complete graph](https://en.wikipedia.org/wiki/Complete_graph) with k vertices
 /// and k * (k-1) / 2 edges
 fn generate_complete_graph(k: usize) -> Graph<i32, i32, Undirected> {
@@ -186,4 +299,77 @@ mod tests {
         assert_eq!(max_min_degree_hundred, 20);
         assert_eq!(max_min_degree_twenty_give, 30);
     }
+
+    #[test]
+    fn test_generate_random_regular_degrees() {
+        let mut rng = rand::thread_rng();
+
+        for (n, d) in [(10, 3), (50, 4), (7, 0), (6, 2)] {
+            let graph = generate_random_regular(n, d, &mut rng)
+                .expect("Random regular graphs with small d should be found");
+
+            assert_eq!(graph.node_count(), n);
+            for vertex in graph.node_indices() {
+                assert_eq!(graph.neighbors(vertex).count(), d, "n: {}, d: {}", n, d);
+            }
+        }
+
+        assert!(generate_random_regular(7, 3, &mut rng).is_none());
+        assert!(generate_random_regular(4, 4, &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_generate_k_tree_errors() {
+        let mut rng = rand::thread_rng();
+
+        assert_eq!(
+            generate_k_tree(10, 5).err(),
+            Some(GenerateError::KGreaterThanN { k: 10, n: 5 })
+        );
+        assert_eq!(
+            generate_partial_k_tree(10, 5, 20, &mut rng).err(),
+            Some(GenerateError::KGreaterThanN { k: 10, n: 5 })
+        );
+        assert_eq!(
+            generate_partial_k_tree_with_guaranteed_treewidth(10, 5, 20, &mut rng).err(),
+            Some(GenerateError::KGreaterThanN { k: 10, n: 5 })
+        );
+
+        assert_eq!(generate_k_tree(0, 5).err(), Some(GenerateError::KZero));
+        assert_eq!(
+            generate_partial_k_tree(0, 5, 20, &mut rng).err(),
+            Some(GenerateError::KZero)
+        );
+        assert_eq!(
+            generate_partial_k_tree_with_guaranteed_treewidth(0, 5, 20, &mut rng).err(),
+            Some(GenerateError::KZero)
+        );
+    }
+
+    #[test]
+    fn test_generate_partial_k_tree_clamps_p() {
+        let mut rng = rand::thread_rng();
+        let graph = generate_partial_k_tree(5, 20, 150, &mut rng).expect("k is smaller than n");
+
+        assert_eq!(graph.node_count(), 20);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_average_width_over_samples_is_at_least_k() {
+        let mut rng = rand::thread_rng();
+        for (k, n, p) in [(2, 15, 20), (3, 20, 30)] {
+            let average = average_width_over_samples::<std::hash::RandomState>(
+                k,
+                n,
+                p,
+                5,
+                SpanningTreeConstructionMethod::FilWh,
+                &mut rng,
+            )
+            .expect("k should be smaller or eq to n");
+
+            assert!(average >= k as f64, "k: {}, average: {}", k, average);
+        }
+    }
 }
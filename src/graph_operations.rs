@@ -0,0 +1,788 @@
+use itertools::Itertools;
+use petgraph::{
+    graph::{EdgeIndex, NodeIndex},
+    visit::EdgeRef,
+    Directed,
+    Direction::Incoming,
+    Graph, Undirected,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use crate::{
+    compute_treewidth_upper_bound, full_treewidth_result, min_fill_heuristic::adjacency_sets,
+    CliqueSource, SpanningTreeConstructionMethod,
+};
+
+/// Returns the [square](https://en.wikipedia.org/wiki/Graph_power) of the given graph, that is,
+/// the graph on the same vertices where two vertices are adjacent if their distance in the given
+/// graph is at most 2.
+///
+/// The edges of the given graph are kept with their weights, the added edges between vertices
+/// with a common neighbour get the default weight.
+pub fn graph_square<N: Clone + Default, E: Clone + Default>(
+    graph: &Graph<N, E, Undirected>,
+) -> Graph<N, E, Undirected> {
+    let adjacency = adjacency_sets(graph);
+    let mut square = graph.clone();
+
+    for vertex in 0..adjacency.len() {
+        let mut neighbours_at_distance_two: Vec<usize> = adjacency[vertex]
+            .iter()
+            .flat_map(|neighbour| adjacency[*neighbour].iter().copied())
+            // Only add each edge once (from the endpoint with the smaller index)
+            .filter(|w| *w > vertex && !adjacency[vertex].contains(w))
+            .collect();
+        neighbours_at_distance_two.sort_unstable();
+        neighbours_at_distance_two.dedup();
+
+        for other_vertex in neighbours_at_distance_two {
+            square.add_edge(
+                NodeIndex::new(vertex),
+                NodeIndex::new(other_vertex),
+                E::default(),
+            );
+        }
+    }
+
+    square
+}
+
+/// Returns the [complement](https://en.wikipedia.org/wiki/Complement_graph) of the given graph,
+/// that is, the graph on the same vertices where two distinct vertices are adjacent if and only if
+/// they are not adjacent in the given graph. The edges get the default weight.
+pub fn complement_graph<N: Clone + Default, E: Clone + Default>(
+    graph: &Graph<N, E, Undirected>,
+) -> Graph<N, E, Undirected> {
+    let adjacency = adjacency_sets(graph);
+    let mut complement = graph.clone();
+    complement.clear_edges();
+
+    for vertex in 0..adjacency.len() {
+        for other_vertex in vertex + 1..adjacency.len() {
+            if !adjacency[vertex].contains(&other_vertex) {
+                complement.add_edge(
+                    NodeIndex::new(vertex),
+                    NodeIndex::new(other_vertex),
+                    E::default(),
+                );
+            }
+        }
+    }
+
+    complement
+}
+
+/// Returns the [moral graph](https://en.wikipedia.org/wiki/Moral_graph) of the given directed
+/// acyclic graph (e.g. a Bayesian network), that is, the undirected graph on the same vertices where
+/// two vertices are adjacent if they are adjacent in the given graph or have a common child.
+///
+/// Each edge occurs only once and gets the default weight.
+pub fn moralize<N: Clone + Default, E: Default>(
+    dag: &Graph<N, E, Directed>,
+) -> Graph<N, E, Undirected> {
+    let mut moral_graph: Graph<N, E, Undirected> =
+        Graph::with_capacity(dag.node_count(), dag.edge_count());
+    for vertex in dag.node_indices() {
+        moral_graph.add_node(dag[vertex].clone());
+    }
+
+    let mut edges: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
+    let mut add_edge = |first_vertex: NodeIndex, second_vertex: NodeIndex| {
+        let edge = (
+            first_vertex.min(second_vertex),
+            first_vertex.max(second_vertex),
+        );
+        if first_vertex != second_vertex && edges.insert(edge) {
+            moral_graph.add_edge(edge.0, edge.1, E::default());
+        }
+    };
+
+    for vertex in dag.node_indices() {
+        let parents: Vec<NodeIndex> = dag.neighbors_directed(vertex, Incoming).collect();
+        for parent in parents.iter() {
+            add_edge(*parent, vertex);
+        }
+        for (first_parent, second_parent) in parents.into_iter().tuple_combinations() {
+            add_edge(first_parent, second_parent);
+        }
+    }
+
+    moral_graph
+}
+
+/// Returns the [tensor product](https://en.wikipedia.org/wiki/Tensor_product_of_graphs) of a and
+/// b, that is, the graph on the pairs of vertices of a and b where (u, x) and (v, y) are adjacent if
+/// u and v are adjacent in a and x and y are adjacent in b.
+///
+/// The vertex (u, x) has the NodeIndex u * |V(b)| + x and the pair as its weight. The edges get the
+/// default weight.
+pub fn tensor_product<N: Clone, E: Clone + Default>(
+    a: &Graph<N, E, Undirected>,
+    b: &Graph<N, E, Undirected>,
+) -> Graph<(NodeIndex, NodeIndex), E, Undirected> {
+    let mut product = product_vertices(a, b);
+    let product_vertex =
+        |u: NodeIndex, x: NodeIndex| NodeIndex::new(u.index() * b.node_count() + x.index());
+
+    for first_edge in a.edge_references() {
+        for second_edge in b.edge_references() {
+            let (u, v) = (first_edge.source(), first_edge.target());
+            let (x, y) = (second_edge.source(), second_edge.target());
+            product.update_edge(product_vertex(u, x), product_vertex(v, y), E::default());
+            product.update_edge(product_vertex(u, y), product_vertex(v, x), E::default());
+        }
+    }
+
+    product
+}
+
+/// Returns the [Cartesian product](https://en.wikipedia.org/wiki/Cartesian_product_of_graphs) of a
+/// and b, that is, the graph on the pairs of vertices of a and b where (u, x) and (v, y) are
+/// adjacent if u = v and x and y are adjacent in b or if x = y and u and v are adjacent in a.
+///
+/// The vertex (u, x) has the NodeIndex u * |V(b)| + x and the pair as its weight. The edges keep
+/// the weights of the edges of a respectively b they come from.
+pub fn cartesian_product<N: Clone, E: Clone + Default>(
+    a: &Graph<N, E, Undirected>,
+    b: &Graph<N, E, Undirected>,
+) -> Graph<(NodeIndex, NodeIndex), E, Undirected> {
+    let mut product = product_vertices(a, b);
+    let product_vertex =
+        |u: NodeIndex, x: NodeIndex| NodeIndex::new(u.index() * b.node_count() + x.index());
+
+    for edge in a.edge_references() {
+        for x in b.node_indices() {
+            product.update_edge(
+                product_vertex(edge.source(), x),
+                product_vertex(edge.target(), x),
+                edge.weight().clone(),
+            );
+        }
+    }
+    for edge in b.edge_references() {
+        for u in a.node_indices() {
+            product.update_edge(
+                product_vertex(u, edge.source()),
+                product_vertex(u, edge.target()),
+                edge.weight().clone(),
+            );
+        }
+    }
+
+    product
+}
+
+/// Returns the graph without edges whose vertices are the pairs of vertices of a and b, where the
+/// pair (u, x) has the NodeIndex u * |V(b)| + x.
+fn product_vertices<N, E>(
+    a: &Graph<N, E, Undirected>,
+    b: &Graph<N, E, Undirected>,
+) -> Graph<(NodeIndex, NodeIndex), E, Undirected> {
+    let mut product: Graph<(NodeIndex, NodeIndex), E, Undirected> =
+        Graph::with_capacity(a.node_count() * b.node_count(), 0);
+    for u in a.node_indices() {
+        for x in b.node_indices() {
+            product.add_node((u, x));
+        }
+    }
+
+    product
+}
+
+/// Returns the graph on the vertices of a containing the edges that are present in both a and b.
+/// The graphs are assumed to share the same node index space, edges of a with an endpoint that is
+/// not a vertex of b are dropped. The edges keep their weights from a.
+pub fn edge_intersection<N: Clone, E: Clone>(
+    a: &Graph<N, E, Undirected>,
+    b: &Graph<N, E, Undirected>,
+) -> Graph<N, E, Undirected> {
+    let mut intersection = a.clone();
+    intersection.clear_edges();
+
+    for edge in a.edge_references() {
+        let (source, target) = (edge.source(), edge.target());
+        if source.index() < b.node_count()
+            && target.index() < b.node_count()
+            && b.contains_edge(source, target)
+            && !intersection.contains_edge(source, target)
+        {
+            intersection.add_edge(source, target, edge.weight().clone());
+        }
+    }
+
+    intersection
+}
+
+/// Returns the primal graph of the hypergraph given by the hyperedges, that is, the graph whose
+/// vertices are the variables of the hyperedges where two variables are adjacent if they occur in a
+/// common hyperedge. Thus each hyperedge becomes a clique.
+///
+/// The variable i is the vertex with NodeIndex i and weight i, so the graph has the biggest variable
+/// plus one vertices. Edges get the weight 0.
+pub fn primal_graph_from_hyperedges<I: IntoIterator<Item = Vec<u32>>>(
+    hyperedges: I,
+) -> Graph<i32, i32, Undirected> {
+    let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+
+    for hyperedge in hyperedges {
+        if let Some(biggest_variable) = hyperedge.iter().max() {
+            for i in graph.node_count()..=*biggest_variable as usize {
+                graph.add_node(i as i32);
+            }
+        }
+        for (first_variable, second_variable) in hyperedge.iter().tuple_combinations() {
+            let (first_vertex, second_vertex) = (
+                NodeIndex::new(*first_variable as usize),
+                NodeIndex::new(*second_variable as usize),
+            );
+            if first_vertex != second_vertex && !graph.contains_edge(first_vertex, second_vertex) {
+                graph.add_edge(first_vertex, second_vertex, 0);
+            }
+        }
+    }
+
+    graph
+}
+
+/// Computes an upper bound for the treewidth of the [complement][complement_graph] of the given
+/// graph with [compute_treewidth_upper_bound] (see there for the remaining parameters).
+pub fn treewidth_of_complement<
+    N: Clone + Default,
+    E: Clone + Default,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+) -> usize {
+    compute_treewidth_upper_bound(
+        &complement_graph(graph),
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_source,
+        None,
+    )
+}
+
+/// Computes an upper bound for the treewidth of the graph on n vertices that contains all edges
+/// except the given non-edges with [compute_treewidth_upper_bound] (see there for the remaining
+/// parameters). This is convenient for dense graphs, which are specified more compactly by their
+/// non-edges.
+///
+/// The vertex i is the vertex with NodeIndex i. Non-edges that are self loops or given multiple
+/// times are ignored.
+///
+/// **Panics**
+/// Panics if a non-edge has an endpoint that is not smaller than n.
+pub fn treewidth_from_complement_edges<
+    I: IntoIterator<Item = (u32, u32)>,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    n: usize,
+    non_edges: I,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+) -> usize {
+    let mut non_edge_graph: Graph<i32, i32, Undirected> = Graph::with_capacity(n, 0);
+    for i in 0..n {
+        non_edge_graph.add_node(i as i32);
+    }
+    for (first_vertex, second_vertex) in non_edges {
+        assert!(
+            (first_vertex as usize) < n && (second_vertex as usize) < n,
+            "Non-edge ({}, {}) should only have endpoints smaller than n = {}",
+            first_vertex,
+            second_vertex,
+            n
+        );
+        let (first_vertex, second_vertex) = (
+            NodeIndex::new(first_vertex as usize),
+            NodeIndex::new(second_vertex as usize),
+        );
+        if first_vertex != second_vertex
+            && !non_edge_graph.contains_edge(first_vertex, second_vertex)
+        {
+            non_edge_graph.add_edge(first_vertex, second_vertex, 0);
+        }
+    }
+
+    treewidth_of_complement(
+        &non_edge_graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_source,
+    )
+}
+
+/// Computes an upper bound for the treewidth of the graph of each time window on n vertices with
+/// [compute_treewidth_upper_bound] (see there for the remaining parameters), which allows following
+/// how the structure of a temporal graph changes over time.
+///
+/// The timestamped edges are triples (u, v, t) of an edge between the vertices with NodeIndex u and
+/// v and the time t at which it is active. The time is split into consecutive windows of the given
+/// length starting at 0, so the window starting at s contains the edges with s <= t < s + window.
+/// Returns the start of each window containing at least one edge together with the upper bound for
+/// its graph, sorted by start. Windows without edges are left out, their graphs have treewidth 0.
+///
+/// Self loops and edges given multiple times in a window are ignored.
+///
+/// **Panics**
+/// Panics if window is 0 or an edge has an endpoint that is not smaller than n.
+pub fn treewidth_over_windows<
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    n: usize,
+    timestamped_edges: &[(u32, u32, u64)],
+    window: u64,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+) -> Vec<(u64, usize)> {
+    assert!(window > 0, "Window should have a positive length");
+
+    let mut edges_of_windows: BTreeMap<u64, Vec<(u32, u32)>> = BTreeMap::new();
+    for (first_vertex, second_vertex, time) in timestamped_edges.iter().copied() {
+        assert!(
+            (first_vertex as usize) < n && (second_vertex as usize) < n,
+            "Edge ({}, {}) should only have endpoints smaller than n = {}",
+            first_vertex,
+            second_vertex,
+            n
+        );
+        edges_of_windows
+            .entry(time - time % window)
+            .or_default()
+            .push((first_vertex, second_vertex));
+    }
+
+    edges_of_windows
+        .into_iter()
+        .map(|(window_start, edges)| {
+            let mut window_graph: Graph<i32, i32, Undirected> =
+                Graph::with_capacity(n, edges.len());
+            for i in 0..n {
+                window_graph.add_node(i as i32);
+            }
+            for (first_vertex, second_vertex) in edges {
+                let (first_vertex, second_vertex) = (
+                    NodeIndex::new(first_vertex as usize),
+                    NodeIndex::new(second_vertex as usize),
+                );
+                if first_vertex != second_vertex
+                    && !window_graph.contains_edge(first_vertex, second_vertex)
+                {
+                    window_graph.add_edge(first_vertex, second_vertex, 0);
+                }
+            }
+
+            let width = compute_treewidth_upper_bound(
+                &window_graph,
+                edge_weight_function,
+                treewidth_computation_method,
+                check_tree_decomposition_bool,
+                clique_source,
+                None,
+            );
+            (window_start, width)
+        })
+        .collect()
+}
+
+/// Computes an upper bound for the treewidth of the subgraph of the given graph that only keeps the
+/// edges whose weight satisfies keep_edge with [compute_treewidth_upper_bound] (see there for the
+/// remaining parameters). All vertices are kept, vertices that become isolated don't change the
+/// treewidth.
+pub fn treewidth_of_edge_filtered<
+    N: Clone + Default,
+    E: Clone + Default,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    keep_edge: impl Fn(&E) -> bool,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+) -> usize {
+    let filtered_graph = graph.filter_map(
+        |_, vertex_weight| Some(vertex_weight.clone()),
+        |_, edge_weight| keep_edge(edge_weight).then(|| edge_weight.clone()),
+    );
+
+    compute_treewidth_upper_bound(
+        &filtered_graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_source,
+        None,
+    )
+}
+
+/// Computes an approximation of the treewidth of the given graph after deleting at most budget of
+/// its edges with a greedy heuristic: In each of at most budget rounds, the edge inside a biggest bag
+/// of the [full_treewidth_result] of the current graph whose removal yields the smallest upper
+/// bound with [compute_treewidth_upper_bound] is removed. The smallest upper bound encountered is
+/// returned.
+///
+/// Each round computes one upper bound per edge inside the biggest bag, so this is only feasible
+/// for small graphs or budgets.
+pub fn treewidth_with_edge_budget<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    budget: usize,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+) -> usize {
+    let mut current_graph = graph.clone();
+    let mut result = full_treewidth_result(
+        &current_graph,
+        treewidth_computation_method,
+        edge_weight_function,
+    );
+    let mut smallest_width = result.width;
+
+    for _ in 0..budget {
+        if smallest_width == 0 {
+            break;
+        }
+        let biggest_bag = result
+            .decomposition
+            .node_weights()
+            .max_by_key(|bag| bag.len())
+            .expect("Tree decomposition should have a bag if the width is positive");
+        let mut candidate_edges: Vec<EdgeIndex> = current_graph
+            .edge_references()
+            .filter(|edge| {
+                biggest_bag.contains(&edge.source()) && biggest_bag.contains(&edge.target())
+            })
+            .map(|edge| edge.id())
+            .collect();
+        // A biggest bag that was filled up during the construction might not contain any edge
+        if candidate_edges.is_empty() {
+            candidate_edges = current_graph.edge_indices().collect();
+        }
+
+        let Some((_, graph_after_removal)) = candidate_edges
+            .into_iter()
+            .map(|edge| {
+                let mut graph_after_removal = current_graph.clone();
+                graph_after_removal.remove_edge(edge);
+                let width = compute_treewidth_upper_bound(
+                    &graph_after_removal,
+                    edge_weight_function,
+                    treewidth_computation_method,
+                    false,
+                    CliqueSource::Maximal,
+                    None,
+                );
+                (width, graph_after_removal)
+            })
+            .min_by_key(|(width, _)| *width)
+        else {
+            break;
+        };
+
+        current_graph = graph_after_removal;
+        result = full_treewidth_result(
+            &current_graph,
+            treewidth_computation_method,
+            edge_weight_function,
+        );
+        smallest_width = smallest_width.min(result.width);
+    }
+
+    smallest_width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_square_of_path() {
+        let path = petgraph::graph::UnGraph::<i32, i32>::from_edges([(0, 1), (1, 2), (2, 3)]);
+
+        let square = graph_square(&path);
+
+        let mut edges: Vec<(usize, usize)> = square
+            .edge_indices()
+            .map(|e| {
+                let (v, w) = square
+                    .edge_endpoints(e)
+                    .expect("Edge should have endpoints");
+                (v.index().min(w.index()), v.index().max(w.index()))
+            })
+            .collect();
+        edges.sort();
+
+        assert_eq!(square.node_count(), 4);
+        assert_eq!(edges, vec![(0, 1), (0, 2), (1, 2), (1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn test_treewidth_of_complement_of_edgeless_graph() {
+        let mut edgeless_graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        for _ in 0..4 {
+            edgeless_graph.add_node(0);
+        }
+
+        assert_eq!(complement_graph(&edgeless_graph).edge_count(), 6);
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let treewidth = treewidth_of_complement::<_, _, _, std::hash::RandomState>(
+                &edgeless_graph,
+                crate::negative_intersection,
+                computation_method,
+                true,
+                CliqueSource::Maximal,
+            );
+
+            assert_eq!(treewidth, 3);
+        }
+    }
+
+    #[test]
+    fn test_treewidth_from_complement_edges_of_edgeless_and_complete_graph() {
+        let all_pairs: Vec<(u32, u32)> = (0..5).tuple_combinations().collect();
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let edgeless_treewidth = treewidth_from_complement_edges::<_, _, std::hash::RandomState>(
+                5,
+                all_pairs.clone(),
+                crate::negative_intersection,
+                computation_method,
+                true,
+                CliqueSource::Maximal,
+            );
+            let complete_treewidth = treewidth_from_complement_edges::<_, _, std::hash::RandomState>(
+                5,
+                [],
+                crate::negative_intersection,
+                computation_method,
+                true,
+                CliqueSource::Maximal,
+            );
+
+            assert_eq!(edgeless_treewidth, 0);
+            assert_eq!(complete_treewidth, 4);
+        }
+    }
+
+    #[test]
+    fn test_treewidth_over_two_windows() {
+        // A triangle in the first window and a path in the second one, the edge at time 10 belongs
+        // to the second window
+        let timestamped_edges = [
+            (0, 1, 1),
+            (1, 2, 3),
+            (2, 0, 9),
+            (0, 1, 10),
+            (1, 2, 12),
+            (2, 3, 15),
+        ];
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            assert_eq!(
+                treewidth_over_windows::<_, std::hash::RandomState>(
+                    4,
+                    &timestamped_edges,
+                    10,
+                    crate::negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                ),
+                vec![(0, 2), (10, 1)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_of_edge_filtered_to_path() {
+        // A 5-clique whose path edges have weight 1 and all other edges weight 0
+        let mut clique: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<_> = (0..5).map(|_| clique.add_node(0)).collect();
+        for (first_index, second_index) in (0..5).tuple_combinations() {
+            let weight = if second_index == first_index + 1 {
+                1
+            } else {
+                0
+            };
+            clique.add_edge(vertices[first_index], vertices[second_index], weight);
+        }
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let unfiltered_treewidth = treewidth_of_edge_filtered::<_, _, _, std::hash::RandomState>(
+                &clique,
+                |_| true,
+                crate::negative_intersection,
+                computation_method,
+                true,
+                CliqueSource::Maximal,
+            );
+            let filtered_treewidth = treewidth_of_edge_filtered::<_, _, _, std::hash::RandomState>(
+                &clique,
+                |weight| *weight == 1,
+                crate::negative_intersection,
+                computation_method,
+                true,
+                CliqueSource::Maximal,
+            );
+
+            assert_eq!(unfiltered_treewidth, 4);
+            assert_eq!(filtered_treewidth, 1);
+        }
+    }
+
+    #[test]
+    fn test_treewidth_with_edge_budget_of_complete_graph() {
+        // The complete graph on 5 vertices is a 5-cycle with 5 chords
+        let mut complete_graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<_> = (0..5).map(|_| complete_graph.add_node(0)).collect();
+        for (first_index, second_index) in (0..5).tuple_combinations() {
+            complete_graph.add_edge(vertices[first_index], vertices[second_index], 0);
+        }
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let width_without_deletions =
+                treewidth_with_edge_budget::<_, _, _, std::hash::RandomState>(
+                    &complete_graph,
+                    0,
+                    crate::negative_intersection,
+                    computation_method,
+                );
+            let width_with_deletions = treewidth_with_edge_budget::<_, _, _, std::hash::RandomState>(
+                &complete_graph,
+                5,
+                crate::negative_intersection,
+                computation_method,
+            );
+
+            assert_eq!(width_without_deletions, 4);
+            assert!(
+                width_with_deletions <= 2,
+                "computation method: {:?}",
+                computation_method
+            );
+        }
+    }
+
+    #[test]
+    fn test_moralize_v_structure() {
+        let mut v_structure: Graph<i32, i32, Directed> = Graph::new();
+        let first_parent = v_structure.add_node(0);
+        let second_parent = v_structure.add_node(1);
+        let child = v_structure.add_node(2);
+        v_structure.add_edge(first_parent, child, 1);
+        v_structure.add_edge(second_parent, child, 1);
+
+        let moral_graph = moralize(&v_structure);
+
+        assert_eq!(moral_graph.node_count(), 3);
+        assert_eq!(moral_graph.edge_count(), 3);
+        assert!(moral_graph.contains_edge(first_parent, second_parent));
+        assert!(moral_graph.contains_edge(first_parent, child));
+        assert!(moral_graph.contains_edge(second_parent, child));
+        assert_eq!(
+            crate::compute_treewidth_upper_bound::<_, _, _, std::hash::RandomState>(
+                &moral_graph,
+                crate::negative_intersection,
+                SpanningTreeConstructionMethod::FilWh,
+                true,
+                CliqueSource::Maximal,
+                None,
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn test_cartesian_product_of_two_edges_is_four_cycle() {
+        let edge = petgraph::graph::UnGraph::<i32, i32>::from_edges([(0, 1)]);
+
+        let product = cartesian_product(&edge, &edge);
+
+        assert_eq!(product.node_count(), 4);
+        assert_eq!(product.edge_count(), 4);
+        assert!(product
+            .node_indices()
+            .all(|vertex| product.neighbors(vertex).count() == 2));
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            assert_eq!(
+                compute_treewidth_upper_bound::<_, _, _, std::hash::RandomState>(
+                    &product,
+                    crate::negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                    None,
+                ),
+                2
+            );
+        }
+    }
+
+    #[test]
+    fn test_tensor_product_of_two_edges_is_two_disjoint_edges() {
+        let edge = petgraph::graph::UnGraph::<i32, i32>::from_edges([(0, 1)]);
+
+        let product = tensor_product(&edge, &edge);
+
+        assert_eq!(product.node_count(), 4);
+        assert_eq!(product.edge_count(), 2);
+        assert!(product.contains_edge(NodeIndex::new(0), NodeIndex::new(3)));
+        assert!(product.contains_edge(NodeIndex::new(1), NodeIndex::new(2)));
+        assert_eq!(
+            product[NodeIndex::new(2)],
+            (NodeIndex::new(1), NodeIndex::new(0))
+        );
+    }
+
+    #[test]
+    fn test_edge_intersection_of_triangle_and_path() {
+        let mut triangle: Graph<(), (), Undirected> = Graph::new_undirected();
+        let vertices: Vec<_> = (0..3).map(|_| triangle.add_node(())).collect();
+        let mut path = triangle.clone();
+        triangle.extend_with_edges([(0, 1), (1, 2), (2, 0)]);
+        path.extend_with_edges([(2, 1), (0, 2)]);
+
+        let intersection = edge_intersection(&triangle, &path);
+
+        assert_eq!(intersection.node_count(), 3);
+        assert_eq!(intersection.edge_count(), 2);
+        assert!(intersection.contains_edge(vertices[1], vertices[2]));
+        assert!(intersection.contains_edge(vertices[0], vertices[2]));
+        assert!(!intersection.contains_edge(vertices[0], vertices[1]));
+    }
+
+    #[test]
+    fn test_primal_graph_of_single_hyperedge_is_triangle() {
+        let primal_graph = primal_graph_from_hyperedges([vec![0, 1, 2]]);
+
+        assert_eq!(primal_graph.node_count(), 3);
+        assert_eq!(primal_graph.edge_count(), 3);
+        for (first_vertex, second_vertex) in [(0, 1), (1, 2), (0, 2)] {
+            assert!(primal_graph
+                .contains_edge(NodeIndex::new(first_vertex), NodeIndex::new(second_vertex)));
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    hash::Hash,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use petgraph::{graph::NodeIndex, graphmap::UnGraphMap, Graph, Undirected};
+
+/// Reads a graph in the [DIMACS](https://mat.tepper.cmu.edu/COLOR/instances.html) format (as used
+/// by the .col files of the graph coloring instances). Lines starting with c are comments, the line
+/// "p edge n m" gives the number of vertices n and each line "e u v" is an edge between the
+/// vertices u and v, which are numbered from 1 to n.
+///
+/// The vertex with number i gets the NodeIndex i - 1 and the weight i - 1, edges get the weight 0.
+/// Duplicate edges (e.g. edges listed in both directions) and self loops are ignored.
+///
+/// Returns an error with kind [InvalidData][io::ErrorKind::InvalidData] if the input is malformed.
+pub fn read_dimacs<R: BufRead>(reader: R) -> io::Result<Graph<i32, i32, Undirected>> {
+    let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+    let mut found_problem_line = false;
+
+    for line in reader.lines() {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("p") => {
+                let number_of_vertices = tokens
+                    .nth(1)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| invalid_data(&line))?;
+                for i in 0..number_of_vertices {
+                    graph.add_node(i as i32);
+                }
+                found_problem_line = true;
+            }
+            Some("e") if found_problem_line => {
+                let mut endpoint = || {
+                    tokens
+                        .next()
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .filter(|v| (1..=graph.node_count()).contains(v))
+                        .map(|v| NodeIndex::new(v - 1))
+                        .ok_or_else(|| invalid_data(&line))
+                };
+                let (first_vertex, second_vertex) = (endpoint()?, endpoint()?);
+                add_edge_if_new(&mut graph, first_vertex, second_vertex);
+            }
+            Some("c") | None => {}
+            _ => return Err(invalid_data(&line)),
+        }
+    }
+
+    if found_problem_line {
+        Ok(graph)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Missing problem line \"p edge n m\"",
+        ))
+    }
+}
+
+/// Reads the graph given by the nonzero pattern of a sparse matrix in the
+/// [Matrix Market](https://math.nist.gov/MatrixMarket/formats.html) coordinate format (.mtx files).
+/// The file starts with the banner "%%MatrixMarket matrix coordinate field symmetry", followed by
+/// comment lines starting with %, the line "rows columns nonzeros" and one line "i j [value]" per
+/// nonzero entry, where the indices are numbered from 1.
+///
+/// Each nonzero entry (i, j) is an edge between the vertices i and j, so the matrix is symmetrized
+/// and the diagonal is ignored. The values are ignored as well. The graph has max(rows, columns)
+/// vertices and like in [read_dimacs], the vertex with number i gets the NodeIndex i - 1 and the
+/// weight i - 1, edges get the weight 0.
+///
+/// Returns an error with kind [InvalidData][io::ErrorKind::InvalidData] if the input is malformed
+/// or not in the coordinate format.
+pub fn read_matrix_market<R: BufRead>(reader: R) -> io::Result<Graph<i32, i32, Undirected>> {
+    let mut lines = reader.lines();
+
+    let banner = lines.next().transpose()?.unwrap_or_default();
+    let banner_tokens: Vec<String> = banner
+        .split_whitespace()
+        .map(|token| token.to_lowercase())
+        .collect();
+    if banner_tokens.len() < 3
+        || banner_tokens[0] != "%%matrixmarket"
+        || banner_tokens[1] != "matrix"
+        || banner_tokens[2] != "coordinate"
+    {
+        return Err(invalid_data(&banner));
+    }
+
+    let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+    let mut found_size_line = false;
+
+    for line in lines {
+        let line = line?;
+        if line.starts_with('%') || line.trim().is_empty() {
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+
+        if !found_size_line {
+            let mut dimension = || {
+                tokens
+                    .next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| invalid_data(&line))
+            };
+            let (rows, columns) = (dimension()?, dimension()?);
+            for i in 0..rows.max(columns) {
+                graph.add_node(i as i32);
+            }
+            found_size_line = true;
+            continue;
+        }
+
+        let mut index = || {
+            tokens
+                .next()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|v| (1..=graph.node_count()).contains(v))
+                .map(|v| NodeIndex::new(v - 1))
+                .ok_or_else(|| invalid_data(&line))
+        };
+        let (first_vertex, second_vertex) = (index()?, index()?);
+        add_edge_if_new(&mut graph, first_vertex, second_vertex);
+    }
+
+    if found_size_line {
+        Ok(graph)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Missing size line \"rows columns nonzeros\"",
+        ))
+    }
+}
+
+/// Reads a graph in the [PACE](https://pacechallenge.org/2017/treewidth/) format (.gr files). Lines
+/// starting with c are comments, the line "p tw n m" gives the number of vertices n and each other
+/// line "u v" is an edge between the vertices u and v, which are numbered from 1 to n.
+///
+/// Like in [read_dimacs], the vertex with number i gets the NodeIndex i - 1 and the weight i - 1,
+/// edges get the weight 0. Duplicate edges and self loops are ignored.
+///
+/// Returns an error with kind [InvalidData][io::ErrorKind::InvalidData] if the input is malformed.
+pub fn read_pace<R: BufRead>(reader: R) -> io::Result<Graph<i32, i32, Undirected>> {
+    let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+    let mut found_problem_line = false;
+
+    for line in reader.lines() {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("p") => {
+                let number_of_vertices = tokens
+                    .nth(1)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| invalid_data(&line))?;
+                for i in 0..number_of_vertices {
+                    graph.add_node(i as i32);
+                }
+                found_problem_line = true;
+            }
+            Some("c") | None => {}
+            Some(first_token) if found_problem_line => {
+                let endpoint = |token: Option<&str>| {
+                    token
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .filter(|v| (1..=graph.node_count()).contains(v))
+                        .map(|v| NodeIndex::new(v - 1))
+                        .ok_or_else(|| invalid_data(&line))
+                };
+                let (first_vertex, second_vertex) =
+                    (endpoint(Some(first_token))?, endpoint(tokens.next())?);
+                add_edge_if_new(&mut graph, first_vertex, second_vertex);
+            }
+            _ => return Err(invalid_data(&line)),
+        }
+    }
+
+    if found_problem_line {
+        Ok(graph)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Missing problem line \"p tw n m\"",
+        ))
+    }
+}
+
+/// Reads a graph given as a list of edges, one edge "u v" per line, where the vertices are
+/// numbered from 0 (as e.g. in the SNAP datasets). Further tokens of a line (e.g. edge weights) are
+/// ignored, as are empty lines and comment lines starting with # or %.
+///
+/// The graph has one vertex more than the biggest number of a vertex, the vertex with number i gets
+/// the NodeIndex i and the weight i, edges get the weight 0. Duplicate edges and self loops are
+/// ignored.
+///
+/// Returns an error with kind [InvalidData][io::ErrorKind::InvalidData] if the input is malformed.
+pub fn read_edge_list<R: BufRead>(reader: R) -> io::Result<Graph<i32, i32, Undirected>> {
+    let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with('#') || line.starts_with('%') || line.trim().is_empty() {
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+
+        let mut endpoint = || {
+            tokens
+                .next()
+                .and_then(|v| v.parse::<usize>().ok())
+                .ok_or_else(|| invalid_data(&line))
+        };
+        let (first_vertex, second_vertex) = (endpoint()?, endpoint()?);
+        for i in graph.node_count()..=first_vertex.max(second_vertex) {
+            graph.add_node(i as i32);
+        }
+        add_edge_if_new(
+            &mut graph,
+            NodeIndex::new(first_vertex),
+            NodeIndex::new(second_vertex),
+        );
+    }
+
+    Ok(graph)
+}
+
+/// Reads the graph from the file at the given path choosing the reader by the extension of the file:
+/// - .col: [read_dimacs]
+/// - .gr: [read_pace]
+/// - .mtx: [read_matrix_market]
+/// - .el: [read_edge_list]
+///
+/// Returns an error with kind [InvalidInput][io::ErrorKind::InvalidInput] if the extension is
+/// missing or not supported, and the errors of opening the file and of the reader otherwise.
+pub fn read_graph_auto<P: AsRef<Path>>(path: P) -> io::Result<Graph<i32, i32, Undirected>> {
+    let path = path.as_ref();
+    let open = || File::open(path).map(BufReader::new);
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("col") => read_dimacs(open()?),
+        Some("gr") => read_pace(open()?),
+        Some("mtx") => read_matrix_market(open()?),
+        Some("el") => read_edge_list(open()?),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unsupported graph file format: {}", path.display()),
+        )),
+    }
+}
+
+/// Adds an edge with weight 0 between the two vertices unless they are equal or already adjacent.
+fn add_edge_if_new(
+    graph: &mut Graph<i32, i32, Undirected>,
+    first_vertex: NodeIndex,
+    second_vertex: NodeIndex,
+) {
+    if first_vertex != second_vertex && !graph.contains_edge(first_vertex, second_vertex) {
+        graph.add_edge(first_vertex, second_vertex, 0);
+    }
+}
+
+/// Returns an error of kind [InvalidData][io::ErrorKind::InvalidData] for the given line.
+fn invalid_data(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Malformed line: {}", line),
+    )
+}
+
+/// Converts the given [UnGraphMap] into a [Graph] as used by this crate together with the map from
+/// the nodes of the graph map to their NodeIndex in the graph, which allows mapping results (e.g.
+/// the bags of a tree decomposition) back to the nodes of the graph map.
+///
+/// The nodes get the NodeIndices in the order in which they were added to the graph map and keep
+/// themselves as weights, edges keep their weights. Self loops are ignored.
+pub fn from_graphmap<N: Copy + Ord + Hash, E: Clone>(
+    graph: &UnGraphMap<N, E>,
+) -> (Graph<N, E, Undirected>, HashMap<N, NodeIndex>) {
+    let mut converted_graph: Graph<N, E, Undirected> =
+        Graph::with_capacity(graph.node_count(), graph.edge_count());
+    let mut node_index: HashMap<N, NodeIndex> = HashMap::new();
+
+    for node in graph.nodes() {
+        node_index.insert(node, converted_graph.add_node(node));
+    }
+    for (first_node, second_node, weight) in graph.all_edges() {
+        if first_node != second_node {
+            converted_graph.add_edge(
+                node_index[&first_node],
+                node_index[&second_node],
+                weight.clone(),
+            );
+        }
+    }
+
+    (converted_graph, node_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_dimacs() {
+        let input =
+            "c A triangle with a pendant vertex\np edge 4 4\ne 1 2\ne 2 3\ne 3 1\ne 1 3\ne 3 4\n";
+
+        let graph = read_dimacs(input.as_bytes()).expect("Input should be valid DIMACS");
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        assert!(graph.contains_edge(NodeIndex::new(2), NodeIndex::new(3)));
+
+        assert!(read_dimacs("e 1 2\n".as_bytes()).is_err());
+        assert!(read_dimacs("p edge 2 1\ne 1 3\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read_matrix_market() {
+        // Lower triangle of a symmetric pattern matrix of a path 1 - 2 - 3 - 4 with a full diagonal
+        let input = "%%MatrixMarket matrix coordinate pattern symmetric\n\
+            % A comment\n\
+            4 4 7\n\
+            1 1\n2 1\n2 2\n3 2\n3 3\n4 3\n4 4\n";
+
+        let graph =
+            read_matrix_market(input.as_bytes()).expect("Input should be valid Matrix Market");
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+        for (first_vertex, second_vertex) in [(0, 1), (1, 2), (2, 3)] {
+            assert!(
+                graph.contains_edge(NodeIndex::new(first_vertex), NodeIndex::new(second_vertex))
+            );
+        }
+
+        assert!(read_matrix_market("4 4 1\n1 2\n".as_bytes()).is_err());
+        assert!(read_matrix_market(
+            "%%MatrixMarket matrix array real general\n2 2\n1.0\n".as_bytes()
+        )
+        .is_err());
+        assert!(read_matrix_market(
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 3 1.0\n".as_bytes()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_read_pace_and_edge_list() {
+        let graph = read_pace("c A path\np tw 4 3\n1 2\n2 3\n3 4\n2 1\n".as_bytes())
+            .expect("Input should be valid PACE");
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.contains_edge(NodeIndex::new(2), NodeIndex::new(3)));
+        assert!(read_pace("1 2\n".as_bytes()).is_err());
+        assert!(read_pace("p tw 2 1\n1 3\n".as_bytes()).is_err());
+
+        let graph = read_edge_list("# A path\n0 1\n1 2 0.5\n\n2 3\n1 0\n".as_bytes())
+            .expect("Input should be a valid edge list");
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.contains_edge(NodeIndex::new(2), NodeIndex::new(3)));
+        assert!(read_edge_list("0 a\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read_graph_auto() {
+        // Each file contains the triangle 1, 2, 3 with the pendant vertex 4 (numbered from 0 in
+        // the edge list)
+        let files = [
+            ("triangle.col", "p edge 4 4\ne 1 2\ne 2 3\ne 3 1\ne 3 4\n"),
+            ("triangle.gr", "p tw 4 4\n1 2\n2 3\n3 1\n3 4\n"),
+            (
+                "triangle.mtx",
+                "%%MatrixMarket matrix coordinate pattern symmetric\n4 4 4\n2 1\n3 2\n3 1\n4 3\n",
+            ),
+            ("triangle.el", "0 1\n1 2\n2 0\n2 3\n"),
+        ];
+        let directory =
+            std::env::temp_dir().join(format!("read_graph_auto_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).expect("Temporary directory should be creatable");
+
+        for (file_name, content) in files {
+            let path = directory.join(file_name);
+            std::fs::write(&path, content).expect("Temporary file should be writable");
+
+            let graph = read_graph_auto(&path).expect("File should be readable");
+
+            assert_eq!(graph.node_count(), 4, "File: {}", file_name);
+            assert_eq!(graph.edge_count(), 4, "File: {}", file_name);
+            assert!(
+                graph.contains_edge(NodeIndex::new(2), NodeIndex::new(3)),
+                "File: {}",
+                file_name
+            );
+        }
+
+        let unsupported_path = directory.join("triangle.txt");
+        std::fs::write(&unsupported_path, "0 1\n").expect("Temporary file should be writable");
+        assert_eq!(
+            read_graph_auto(&unsupported_path)
+                .expect_err("Extension should be unsupported")
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        std::fs::remove_dir_all(&directory).expect("Temporary directory should be removable");
+    }
+
+    #[test]
+    fn test_from_graphmap_triangle() {
+        let mut graph_map: UnGraphMap<&str, ()> = UnGraphMap::new();
+        graph_map.add_edge("a", "b", ());
+        graph_map.add_edge("b", "c", ());
+        graph_map.add_edge("c", "a", ());
+
+        let (graph, node_index) = from_graphmap(&graph_map);
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        for node in ["a", "b", "c"] {
+            assert_eq!(graph[node_index[node]], node);
+        }
+        assert_eq!(
+            crate::compute_treewidth_upper_bound::<_, _, _, std::hash::RandomState>(
+                &graph,
+                crate::negative_intersection,
+                crate::SpanningTreeConstructionMethod::FilWh,
+                true,
+                crate::CliqueSource::Maximal,
+                None,
+            ),
+            2
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use std::{collections::HashSet, hash::BuildHasher};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    compute_treewidth_upper_bound::compute_tree_decomposition,
+    find_width_of_tree_decomposition::find_width_of_tree_decomposition,
+    min_fill_heuristic::adjacency_sets, negative_intersection, CliqueOrder, CliqueSource,
+    FillOptions, SpanningTreeConstructionMethod,
+};
+
+/// Computes a tree decomposition by running each of the given methods on each biconnected
+/// component (block) of the graph and taking the decomposition of smallest width per block. The
+/// decompositions of the blocks are joined at the cut vertices (see [join_decompositions]), which
+/// gives a tree decomposition of the whole graph whose width is the maximum of the widths of the
+/// blocks. Thus the width is at most the width of each single method run on the blocks.
+///
+/// The clique graphs are weighted with [negative_intersection]. Ties between methods are broken in
+/// favour of the method coming first.
+///
+/// Returns the width together with the tree decomposition. For a graph without vertices the width is
+/// 0 and the tree decomposition is empty.
+///
+/// **Panics**
+/// Panics if methods is empty and the graph has a block with at least two vertices.
+pub fn hybrid_best_decomposition<N: Clone, E: Clone, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    methods: &[SpanningTreeConstructionMethod],
+) -> (usize, Graph<HashSet<NodeIndex, S>, i32, Undirected>) {
+    let mut tree_decomposition: Graph<HashSet<NodeIndex, S>, i32, Undirected> =
+        Graph::new_undirected();
+    // The bag of the tree decomposition each vertex was first added to
+    let mut bag_of_vertex: Vec<Option<NodeIndex>> = vec![None; graph.node_count()];
+    let mut remaining_blocks = biconnected_components(graph);
+
+    // The blocks are added along the block-cut tree, so that each block shares at most one vertex
+    // (a cut vertex) with the blocks that have been added before
+    while !remaining_blocks.is_empty() {
+        let position = remaining_blocks
+            .iter()
+            .position(|block| block.iter().any(|v| bag_of_vertex[v.index()].is_some()))
+            .unwrap_or(0);
+        let block = remaining_blocks.swap_remove(position);
+        let block_decomposition = best_block_decomposition::<N, E, S>(graph, &block, methods);
+
+        let (target_bag, other_bag) = match block
+            .iter()
+            .find_map(|v| bag_of_vertex[v.index()].map(|bag| (*v, bag)))
+        {
+            Some((cut_vertex, bag)) => (
+                Some(bag),
+                block_decomposition
+                    .node_indices()
+                    .find(|other_bag| block_decomposition[*other_bag].contains(&cut_vertex))
+                    .expect("Cut vertex should be contained in a bag of the block"),
+            ),
+            // The block is the first one of its connected component
+            None => (tree_decomposition.node_indices().next(), NodeIndex::new(0)),
+        };
+
+        let offset = tree_decomposition.node_count();
+        join_decompositions(
+            &mut tree_decomposition,
+            &block_decomposition,
+            target_bag,
+            other_bag,
+        );
+        for bag in block_decomposition.node_indices() {
+            for vertex in block_decomposition[bag].iter() {
+                bag_of_vertex[vertex.index()].get_or_insert(NodeIndex::new(offset + bag.index()));
+            }
+        }
+    }
+
+    (
+        find_width_of_tree_decomposition(&tree_decomposition),
+        tree_decomposition,
+    )
+}
+
+/// Adds the vertices and edges of the other tree decomposition to the target tree decomposition
+/// and connects the other bag (a NodeIndex of the other decomposition) with the target bag by an
+/// edge with the default weight. The NodeIndex i of the other decomposition becomes the NodeIndex
+/// offset + i in the target decomposition, where offset is the number of vertices of the target
+/// decomposition before joining. If target_bag is None, no edge is added.
+///
+/// The result is a tree decomposition of the union of the two graphs if every vertex contained in
+/// both decompositions is contained in both the target bag and the other bag, for example if the
+/// decompositions only share a cut vertex or nothing at all.
+pub fn join_decompositions<O: Clone + Default, S: Clone>(
+    target: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    other: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    target_bag: Option<NodeIndex>,
+    other_bag: NodeIndex,
+) {
+    let offset = target.node_count();
+    for bag in other.node_weights() {
+        target.add_node(bag.clone());
+    }
+    for edge in other.edge_indices() {
+        let (first_bag, second_bag) = other
+            .edge_endpoints(edge)
+            .expect("Edge should have endpoints");
+        target.add_edge(
+            NodeIndex::new(offset + first_bag.index()),
+            NodeIndex::new(offset + second_bag.index()),
+            other[edge].clone(),
+        );
+    }
+
+    if let Some(target_bag) = target_bag {
+        target.add_edge(
+            target_bag,
+            NodeIndex::new(offset + other_bag.index()),
+            O::default(),
+        );
+    }
+}
+
+/// Returns the tree decomposition of smallest width of the subgraph induced by the block among the
+/// ones computed by the given methods. The bags contain the NodeIndices of the original graph.
+fn best_block_decomposition<N: Clone, E: Clone, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    block: &[NodeIndex],
+    methods: &[SpanningTreeConstructionMethod],
+) -> Graph<HashSet<NodeIndex, S>, i32, Undirected> {
+    if block.len() == 1 {
+        let mut tree_decomposition = Graph::new_undirected();
+        tree_decomposition.add_node(HashSet::from_iter(block.iter().copied()));
+        return tree_decomposition;
+    }
+
+    // The vertices of the induced subgraph are labeled with their NodeIndex in the original graph
+    let block_vertices: HashSet<NodeIndex> = block.iter().copied().collect();
+    let subgraph: Graph<NodeIndex, (), Undirected> = graph.filter_map(
+        |v, _| block_vertices.contains(&v).then_some(v),
+        |_, _| Some(()),
+    );
+
+    methods
+        .iter()
+        .map(|method| {
+            let (tree_decomposition, _, _) = compute_tree_decomposition::<_, _, _, S>(
+                &subgraph,
+                negative_intersection,
+                *method,
+                CliqueSource::Maximal,
+                None,
+                CliqueOrder::AsGiven,
+                &mut FillOptions::default(),
+            );
+            tree_decomposition.map(
+                |_, bag| bag.iter().map(|v| subgraph[*v]).collect(),
+                |_, weight| *weight,
+            )
+        })
+        .min_by_key(find_width_of_tree_decomposition)
+        .expect("There should be at least one method")
+}
+
+/// A vertex of the [block-cut tree][block_cut_tree], which is either a block (given by its
+/// vertices) or a cut vertex of the graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockCutNode {
+    Block(Vec<NodeIndex>),
+    Cut(NodeIndex),
+}
+
+/// Returns the [block-cut tree](https://en.wikipedia.org/wiki/Biconnected_component#Block-cut_tree)
+/// of the graph, that is, the bipartite graph whose vertices are the blocks and the cut vertices of
+/// the graph where a block is adjacent to the cut vertices it contains. The block-cut tree is a
+/// forest with one tree per connected component of the graph.
+///
+/// The blocks come first (see [biconnected_components] for their order), followed by the cut
+/// vertices in ascending order.
+pub fn block_cut_tree<N, E>(
+    graph: &Graph<N, E, Undirected>,
+) -> Graph<BlockCutNode, (), Undirected> {
+    let blocks = biconnected_components(graph);
+    let mut blocks_of_vertex: Vec<Vec<usize>> = vec![Vec::new(); graph.node_count()];
+    for (block_index, block) in blocks.iter().enumerate() {
+        for vertex in block {
+            blocks_of_vertex[vertex.index()].push(block_index);
+        }
+    }
+
+    let mut block_cut_tree: Graph<BlockCutNode, (), Undirected> = Graph::new_undirected();
+    for block in blocks {
+        block_cut_tree.add_node(BlockCutNode::Block(block));
+    }
+    for (vertex, blocks_containing_vertex) in blocks_of_vertex.into_iter().enumerate() {
+        // Exactly the vertices contained in more than one block are cut vertices
+        if blocks_containing_vertex.len() > 1 {
+            let cut_node = block_cut_tree.add_node(BlockCutNode::Cut(NodeIndex::new(vertex)));
+            for block_index in blocks_containing_vertex {
+                block_cut_tree.add_edge(NodeIndex::new(block_index), cut_node, ());
+            }
+        }
+    }
+
+    block_cut_tree
+}
+
+/// Returns the vertex sets of the [biconnected components](https://en.wikipedia.org/wiki/Biconnected_component)
+/// (blocks) of the graph using Tarjan's algorithm. Isolated vertices form blocks on their own,
+/// bridges form blocks of two vertices.
+pub(crate) fn biconnected_components<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<Vec<NodeIndex>> {
+    let adjacency: Vec<Vec<usize>> = adjacency_sets(graph)
+        .into_iter()
+        .map(|neighbours| {
+            let mut neighbours: Vec<usize> = neighbours.into_iter().collect();
+            neighbours.sort_unstable();
+            neighbours
+        })
+        .collect();
+    let mut discovery_time: Vec<Option<usize>> = vec![None; adjacency.len()];
+    let mut low = vec![0; adjacency.len()];
+    let mut time = 0;
+    let mut edge_stack: Vec<(usize, usize)> = Vec::new();
+    let mut blocks = Vec::new();
+
+    for root in 0..adjacency.len() {
+        if discovery_time[root].is_some() {
+            continue;
+        }
+        discovery_time[root] = Some(time);
+        low[root] = time;
+        time += 1;
+        if adjacency[root].is_empty() {
+            blocks.push(vec![NodeIndex::new(root)]);
+            continue;
+        }
+
+        // Each entry consists of a vertex, its parent in the dfs tree and the position of the next
+        // neighbour to be visited
+        let mut stack: Vec<(usize, Option<usize>, usize)> = vec![(root, None, 0)];
+        while let Some((vertex, parent, next_neighbour)) = stack.last_mut() {
+            let (vertex, parent) = (*vertex, *parent);
+            let vertex_discovery_time =
+                discovery_time[vertex].expect("Vertex should be discovered");
+
+            if let Some(neighbour) = adjacency[vertex].get(*next_neighbour).copied() {
+                *next_neighbour += 1;
+                match discovery_time[neighbour] {
+                    None => {
+                        edge_stack.push((vertex, neighbour));
+                        discovery_time[neighbour] = Some(time);
+                        low[neighbour] = time;
+                        time += 1;
+                        stack.push((neighbour, Some(vertex), 0));
+                    }
+                    Some(neighbour_discovery_time)
+                        if Some(neighbour) != parent
+                            && neighbour_discovery_time < vertex_discovery_time =>
+                    {
+                        // Back edge
+                        edge_stack.push((vertex, neighbour));
+                        low[vertex] = low[vertex].min(neighbour_discovery_time);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            stack.pop();
+            if let Some(parent) = parent {
+                low[parent] = low[parent].min(low[vertex]);
+                if low[vertex] >= discovery_time[parent].expect("Parent should be discovered") {
+                    // The parent is a cut vertex (or the root) separating the block below it
+                    let mut block: HashSet<usize> = HashSet::new();
+                    while let Some((first_vertex, second_vertex)) = edge_stack.pop() {
+                        block.insert(first_vertex);
+                        block.insert(second_vertex);
+                        if (first_vertex, second_vertex) == (parent, vertex) {
+                            break;
+                        }
+                    }
+                    let mut block: Vec<NodeIndex> = block.into_iter().map(NodeIndex::new).collect();
+                    block.sort();
+                    blocks.push(block);
+                }
+            }
+        }
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    /// Returns three k-trees for k = 2, 3, 4 glued together at single vertices, so the graph has
+    /// (at least) three blocks
+    fn glued_k_trees() -> Graph<i32, i32, Undirected> {
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let mut previous_vertex: Option<NodeIndex> = None;
+
+        for k in 2..5 {
+            let k_tree = crate::generate_k_tree(k, 15).expect("k should be smaller or eq to n");
+            // The first vertex of the k-tree is identified with the last vertex of the previous one
+            let vertex_map: Vec<NodeIndex> = k_tree
+                .node_indices()
+                .map(|vertex| match previous_vertex {
+                    Some(previous_vertex) if vertex.index() == 0 => previous_vertex,
+                    _ => graph.add_node(k_tree[vertex]),
+                })
+                .collect();
+            for edge in k_tree.edge_indices() {
+                let (first_vertex, second_vertex) = k_tree
+                    .edge_endpoints(edge)
+                    .expect("Edge should have endpoints");
+                graph.add_edge(
+                    vertex_map[first_vertex.index()],
+                    vertex_map[second_vertex.index()],
+                    0,
+                );
+            }
+            previous_vertex = vertex_map.last().copied();
+        }
+
+        graph
+    }
+
+    #[test]
+    fn test_biconnected_components() {
+        let mut graph: Graph<(), (), Undirected> = Graph::new_undirected();
+        for _ in 0..7 {
+            graph.add_node(());
+        }
+        // A triangle and a square connected by the bridge 2 - 3 and the isolated vertex 6
+        graph.extend_with_edges([(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)]);
+        graph.add_edge(NodeIndex::new(4), NodeIndex::new(5), ());
+
+        let mut blocks = biconnected_components(&graph);
+        blocks.sort();
+
+        let expected_blocks: Vec<Vec<NodeIndex>> =
+            vec![vec![0, 1, 2], vec![2, 3], vec![3, 4, 5], vec![6]]
+                .into_iter()
+                .map(|block| block.into_iter().map(NodeIndex::new).collect())
+                .collect();
+        assert_eq!(blocks, expected_blocks);
+    }
+
+    #[test]
+    fn test_hybrid_width_is_at_most_each_method_width() {
+        let methods = crate::tests::COMPUTATION_METHODS;
+        let mut graphs: Vec<Graph<i32, i32, Undirected>> = [0, 2]
+            .into_iter()
+            .map(|i| crate::tests::setup_test_graph(i).graph)
+            .collect();
+        graphs.push(glued_k_trees());
+
+        for (i, graph) in graphs.iter().enumerate() {
+            let (hybrid_width, tree_decomposition) =
+                hybrid_best_decomposition::<_, _, RandomState>(graph, &methods);
+
+            assert_eq!(
+                find_width_of_tree_decomposition(&tree_decomposition),
+                hybrid_width
+            );
+            for method in methods {
+                let width = crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    graph,
+                    negative_intersection,
+                    method,
+                    false,
+                    CliqueSource::Maximal,
+                    None,
+                );
+                assert!(
+                    hybrid_width <= width,
+                    "Graph: {}, method: {:?}, hybrid width: {}, width: {}",
+                    i,
+                    method,
+                    hybrid_width,
+                    width
+                );
+            }
+        }
+
+        let glued_k_trees = graphs.last().expect("Graphs should not be empty");
+        let (hybrid_width, tree_decomposition) =
+            hybrid_best_decomposition::<_, _, RandomState>(glued_k_trees, &methods);
+        assert_eq!(hybrid_width, 4);
+        assert!(crate::check_tree_decomposition(
+            glued_k_trees,
+            &tree_decomposition,
+            &None,
+            &None
+        ));
+    }
+
+    #[test]
+    fn test_block_cut_tree_of_two_triangles_sharing_a_vertex() {
+        let graph: Graph<i32, i32, Undirected> =
+            Graph::from_edges([(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+
+        let block_cut_tree = block_cut_tree(&graph);
+
+        assert_eq!(block_cut_tree.node_count(), 3);
+        assert_eq!(block_cut_tree.edge_count(), 2);
+        let cut_node = block_cut_tree
+            .node_indices()
+            .find(|node| matches!(block_cut_tree[*node], BlockCutNode::Cut(_)))
+            .expect("Block-cut tree should contain a cut vertex");
+        assert_eq!(
+            block_cut_tree[cut_node],
+            BlockCutNode::Cut(NodeIndex::new(2))
+        );
+
+        let mut blocks: Vec<Vec<NodeIndex>> = block_cut_tree
+            .neighbors(cut_node)
+            .map(|node| match &block_cut_tree[node] {
+                BlockCutNode::Block(block) => block.clone(),
+                BlockCutNode::Cut(_) => panic!("Cut vertices should only be adjacent to blocks"),
+            })
+            .collect();
+        blocks.sort();
+        assert_eq!(
+            blocks,
+            vec![
+                vec![NodeIndex::new(0), NodeIndex::new(1), NodeIndex::new(2)],
+                vec![NodeIndex::new(2), NodeIndex::new(3), NodeIndex::new(4)]
+            ]
+        );
+    }
+}
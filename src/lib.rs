@@ -1,49 +1,140 @@
+mod benchmark;
+mod bitset_bag;
 mod check_tree_decomposition;
 mod clique_graph_edge_weight_functions;
 mod compute_treewidth_upper_bound;
 pub mod construct_clique_graph;
+mod decomposition_statistics;
+mod feedback_vertex_set;
 pub mod fill_bags_along_paths;
 mod fill_bags_while_generating_mst;
 pub mod find_connected_components;
 pub mod find_maximal_cliques;
 pub mod find_width_of_tree_decomposition;
 mod generate_partial_k_tree;
+mod graph_operations;
+mod graph_readers;
+mod hybrid_decomposition;
 mod maximum_minimum_degree_heuristic;
+mod min_fill_heuristic;
+mod planar_separator;
+mod reduction_rules;
+mod refine_decomposition;
+mod separator_approximation;
+mod treedepth;
+mod trivial_decomposition;
 
 // Imports for using the library
+pub use benchmark::{
+    run_benchmarks, run_benchmarks_over_dir, treewidth_batch, write_benchmark_csv,
+    BenchmarkHeuristic, BenchmarkResult,
+};
+pub use bitset_bag::BitsetBag;
 pub(crate) use check_tree_decomposition::check_tree_decomposition;
+pub use check_tree_decomposition::{
+    assign_edges_to_bags, bags_reference_valid_vertices, edges_covered,
+    validate_tree_decomposition, TreeDecompositionFault,
+};
 pub use clique_graph_edge_weight_functions::*;
 pub use compute_treewidth_upper_bound::{
-    compute_treewidth_upper_bound, compute_treewidth_upper_bound_not_connected,
-    SpanningTreeConstructionMethod,
+    compute_treewidth_streaming_bags, compute_treewidth_timed, compute_treewidth_upper_bound,
+    compute_treewidth_upper_bound_checked, compute_treewidth_upper_bound_not_connected,
+    compute_treewidth_upper_bound_verified, compute_treewidth_upper_bound_with_clique_order,
+    compute_treewidth_upper_bound_with_fill_options, compute_treewidth_with_trace,
+    decomposition_if_width_at_most, estimate_width_only, full_treewidth_result, tree_likeness,
+    treewidth_best_seed, treewidth_bounds, treewidth_with_fallback, width_vs_clique_bound,
+    CliqueSource, PhaseTimings, SpanningTreeConstructionMethod, TreewidthError, TreewidthResult,
+};
+pub use construct_clique_graph::{
+    add_clique_to_clique_graph, clique_graph_with_map, order_cliques, CliqueGraphMap, CliqueOrder,
 };
+pub use decomposition_statistics::{
+    adjacency_overlap_stats, compare_decompositions, decomposition_tree_shape,
+    decompositions_equivalent, edge_separators, separator_width, tree_decomposition_stats,
+    DecompositionComparison, Dominance, TreeDecompositionStats, TreeShape,
+};
+pub use feedback_vertex_set::feedback_vertex_set_greedy;
 pub(crate) use fill_bags_while_generating_mst::{
-    fill_bags_while_generating_mst, fill_bags_while_generating_mst_least_bag_size,
+    fill_bags_while_generating_mst, fill_bags_while_generating_mst_kruskal,
+    fill_bags_while_generating_mst_least_bag_size, fill_bags_while_generating_mst_ordering_guided,
     fill_bags_while_generating_mst_update_edges, fill_bags_while_generating_mst_using_tree,
+    fill_bags_while_generating_mst_with_trace,
 };
+pub use fill_bags_while_generating_mst::{FillOptions, FillStats};
+pub use find_connected_components::component_labels;
 pub(crate) use find_connected_components::find_connected_components;
+pub use find_maximal_cliques::{
+    clique_number_lower_bound, dominating_clique, find_maximal_cliques_with_pivot,
+    is_result_optimal, maximal_clique_size_histogram, PivotStrategy,
+};
 pub use generate_partial_k_tree::{
-    generate_k_tree, generate_partial_k_tree, generate_partial_k_tree_with_guaranteed_treewidth,
+    average_width_over_samples, generate_k_tree, generate_partial_k_tree,
+    generate_partial_k_tree_with_guaranteed_treewidth, generate_random_regular, GenerateError,
+};
+pub use graph_operations::{
+    cartesian_product, complement_graph, edge_intersection, graph_square, moralize,
+    primal_graph_from_hyperedges, tensor_product, treewidth_from_complement_edges,
+    treewidth_of_complement, treewidth_of_edge_filtered, treewidth_over_windows,
+    treewidth_with_edge_budget,
+};
+pub use graph_readers::{
+    from_graphmap, read_dimacs, read_edge_list, read_graph_auto, read_matrix_market, read_pace,
+};
+pub use hybrid_decomposition::{
+    block_cut_tree, hybrid_best_decomposition, join_decompositions, BlockCutNode,
 };
 pub(crate) use maximum_minimum_degree_heuristic::maximum_minimum_degree_plus;
+pub use maximum_minimum_degree_heuristic::{delta2d_lower_bound, minor_min_width_lbn};
+pub use min_fill_heuristic::{
+    elimination_game_dot_frames, elimination_ordering_from_decomposition,
+    fill_in_of_elimination_ordering, fill_reducing_permutation, is_chordal, min_fill_ordering,
+    min_fill_ordering_lazy, width_of_elimination_ordering,
+};
+pub use planar_separator::{treewidth_planar_hint, treewidth_upper_bound_from_bfs_layers};
+pub use reduction_rules::{
+    improved_graph, is_series_parallel, peel, quotient_by_modules, reduce_dominated_vertices,
+    reduce_twins, treewidth_via_two_core, two_core,
+};
+pub use refine_decomposition::{
+    recompute_after_edge_removal, refine_decomposition, relabel_decomposition, repair_connectivity,
+    split_decomposition_at,
+};
+pub use separator_approximation::treewidth_approx_separator;
+pub use treedepth::treedepth_upper_bound;
+pub use trivial_decomposition::trivial_decomposition_from_spanning_tree;
+
+/// The hasher used for the HashSets created inside the crate (see the hashset macro), which is also
+/// a sensible choice for the hasher type parameter S of the public functions. In debug builds or
+/// with the feature `deterministic` it is the deterministic [FxHasher][rustc_hash::FxHasher], so
+/// that the computed widths are reproducible.
+#[cfg(any(debug_assertions, feature = "deterministic"))]
+pub type DefaultHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+/// The hasher used for the HashSets created inside the crate (see the hashset macro). In release
+/// builds without the feature `deterministic` it is the randomly seeded
+/// [RandomState][std::hash::RandomState], so the computed widths may differ between runs.
+#[cfg(not(any(debug_assertions, feature = "deterministic")))]
+pub type DefaultHasher = std::hash::RandomState;
+
+/// Returns true if HashSets with the hasher S iterate in the same order whenever the same entries
+/// are inserted in the same order, in which case the computed widths are reproducible (see
+/// [DefaultHasher]). This is true for hashers like [FxHasher][rustc_hash::FxHasher] and false for
+/// randomly seeded hashers like [RandomState][std::hash::RandomState].
+///
+/// This is checked by comparing the hashes of two independently created default hashers, so a
+/// randomly seeded hasher could be misclassified if it happened to produce identical hashes.
+pub fn treewidth_is_deterministic<S: Default + std::hash::BuildHasher>() -> bool {
+    let (first_hasher, second_hasher) = (S::default(), S::default());
+
+    (0..8u64).all(|value| first_hasher.hash_one(value) == second_hasher.hash_one(value))
+}
 
-// Debug version
-#[cfg(debug_assertions)]
 macro_rules! hashset {
     () => {{
-        let tmp: std::collections::HashSet<_, std::hash::BuildHasherDefault<rustc_hash::FxHasher>> =
-            Default::default();
+        let tmp: std::collections::HashSet<_, crate::DefaultHasher> = Default::default();
         tmp
     }};
 }
-
-// Non-debug version
-#[cfg(not(debug_assertions))]
-macro_rules! hashset {
-    () => {
-        std::collections::HashSet::new()
-    };
-}
 pub(crate) use hashset;
 
 #[cfg(test)]
@@ -282,6 +373,34 @@ pub(crate) mod tests {
         }
     }
 
+    /// Returns the side x side grid graph with vertex x * side + y at position (x, y). It has
+    /// treewidth side.
+    pub fn grid_graph(side: usize) -> Graph<(), (), petgraph::prelude::Undirected> {
+        let mut grid: Graph<(), (), petgraph::prelude::Undirected> = Graph::new_undirected();
+        for _ in 0..side * side {
+            grid.add_node(());
+        }
+        for x in 0..side {
+            for y in 0..side {
+                if x + 1 < side {
+                    grid.add_edge(
+                        NodeIndex::new(x * side + y),
+                        NodeIndex::new((x + 1) * side + y),
+                        (),
+                    );
+                }
+                if y + 1 < side {
+                    grid.add_edge(
+                        NodeIndex::new(x * side + y),
+                        NodeIndex::new(x * side + y + 1),
+                        (),
+                    );
+                }
+            }
+        }
+        grid
+    }
+
     #[test]
     fn hash_test() {
         let mut test = true;
@@ -304,6 +423,47 @@ pub(crate) mod tests {
         debug_assert!(test);
     }
 
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn test_deterministic_hasher_gives_identical_runs() {
+        let k_tree: Graph<i32, i32, petgraph::prelude::Undirected> =
+            crate::generate_partial_k_tree(5, 60, 30, &mut rand::thread_rng())
+                .expect("k should be smaller or eq to n");
+
+        for computation_method in COMPUTATION_METHODS {
+            let first_run = compute_treewidth_upper_bound::<_, _, _, DefaultHasher>(
+                &k_tree,
+                negative_intersection,
+                computation_method,
+                false,
+                CliqueSource::Maximal,
+                None,
+            );
+            let second_run = compute_treewidth_upper_bound::<_, _, _, DefaultHasher>(
+                &k_tree,
+                negative_intersection,
+                computation_method,
+                false,
+                CliqueSource::Maximal,
+                None,
+            );
+
+            assert_eq!(
+                first_run, second_run,
+                "computation method: {:?}",
+                computation_method
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_is_deterministic() {
+        assert!(crate::treewidth_is_deterministic::<
+            std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+        >());
+        assert!(!crate::treewidth_is_deterministic::<std::hash::RandomState>());
+    }
+
     fn test_graph_on_all_heuristics<N: Clone + Debug, E: Clone + Debug>(
         graph: Graph<N, E, petgraph::prelude::Undirected>,
         expected_treewidth: usize,
@@ -315,7 +475,7 @@ pub(crate) mod tests {
                 negative_intersection::<std::hash::RandomState>,
                 computation_method,
                 true,
-                None,
+                CliqueSource::Maximal,
             );
             assert_eq!(treewidth, expected_treewidth, "{}", msg);
 
@@ -324,7 +484,7 @@ pub(crate) mod tests {
                 least_difference::<std::hash::RandomState>,
                 computation_method,
                 true,
-                None,
+                CliqueSource::Maximal,
             );
             assert_eq!(
                 treewidth, expected_treewidth,
@@ -342,7 +502,8 @@ pub(crate) mod tests {
         for _ in 0..25 {
             let mut rng = rand::thread_rng();
 
-            let k: usize = (rng.gen::<f32>() * 50.0) as usize;
+            // k should be at least 1, since 0-trees can't be generated
+            let k: usize = (rng.gen::<f32>() * 49.0) as usize + 1;
             // n should be strictly greater than k otherwise k_tree has not guaranteed treewidth k
             let n: usize = (rng.gen::<f32>() * 100.0) as usize + k + 1;
 
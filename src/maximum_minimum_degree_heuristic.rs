@@ -3,6 +3,8 @@ use std::collections::HashSet;
 use itertools::Itertools;
 use petgraph::{graph::NodeIndex, visit::IntoNodeIdentifiers, Graph, Undirected};
 
+use crate::min_fill_heuristic::adjacency_sets;
+
 /// Computes the contraction degeneracy of the given graph according to https://link.springer.com/chapter/10.1007/978-3-540-30140-0_56 (see MMD+: least-c)
 pub fn maximum_minimum_degree_plus<N: Clone + Default, E: Clone + Default>(
     graph: &Graph<N, E, Undirected>,
@@ -54,6 +56,156 @@ pub fn maximum_minimum_degree_plus<N: Clone + Default, E: Clone + Default>(
     max_min
 }
 
+/// Computes a lower bound for the treewidth of the given graph using the LBN+ improvement of
+/// [maximum_minimum_degree_plus] according to https://doi.org/10.1016/j.dam.2005.10.018 (see
+/// LBN+(MMD+)).
+///
+/// It relies on the fact that if the treewidth of the graph is at most low, adding edges between
+/// non-adjacent vertices with at least low + 1 common neighbours doesn't change the treewidth. Thus
+/// the graph is alternately improved by adding these edges and contracted (like in
+/// [maximum_minimum_degree_plus]). As soon as [maximum_minimum_degree_plus] of the current graph
+/// exceeds low, the treewidth is bigger than low, so low is increased and the process restarts.
+///
+/// The result is at least [maximum_minimum_degree_plus] of the graph. Adding the edges takes
+/// O(n²·d) time per step, so this is meant for small to medium sized graphs.
+pub fn minor_min_width_lbn<N: Clone + Default, E: Clone + Default>(
+    graph: &Graph<N, E, Undirected>,
+) -> usize {
+    let mut low = maximum_minimum_degree_plus(graph);
+    let mut improved = true;
+
+    while improved {
+        improved = false;
+        let mut adjacency = adjacency_sets(graph);
+        let mut alive = vec![true; adjacency.len()];
+        let mut remaining_vertices = adjacency.len();
+
+        // A graph with at most low + 1 vertices has treewidth at most low
+        while remaining_vertices > low + 1 {
+            add_common_neighbour_edges(&mut adjacency, &alive, low + 1);
+            if maximum_minimum_degree_plus(&graph_from_adjacency(&adjacency, &alive)) > low {
+                low += 1;
+                improved = true;
+                break;
+            }
+
+            let min_degree_vertex = (0..adjacency.len())
+                .filter(|v| alive[*v])
+                .min_by_key(|v| adjacency[*v].len())
+                .expect("Graph should have remaining vertices");
+            let least_common_neighbours_neighbour = adjacency[min_degree_vertex]
+                .iter()
+                .copied()
+                .min_by_key(|w| {
+                    adjacency[*w]
+                        .intersection(&adjacency[min_degree_vertex])
+                        .count()
+                });
+
+            // Contract the vertex into the neighbour (or delete it if it is isolated)
+            let neighbours = std::mem::take(&mut adjacency[min_degree_vertex]);
+            for neighbour in neighbours.iter() {
+                adjacency[*neighbour].remove(&min_degree_vertex);
+            }
+            if let Some(contracted_neighbour) = least_common_neighbours_neighbour {
+                for neighbour in neighbours {
+                    if neighbour != contracted_neighbour {
+                        adjacency[neighbour].insert(contracted_neighbour);
+                        adjacency[contracted_neighbour].insert(neighbour);
+                    }
+                }
+            }
+            alive[min_degree_vertex] = false;
+            remaining_vertices -= 1;
+        }
+    }
+
+    low
+}
+
+/// Computes the δ2D lower bound for the treewidth of the given graph according to
+/// https://doi.org/10.1007/11561071_57, that is, the maximum over all subgraphs of the second
+/// smallest degree. This is at least the maximum over all subgraphs of the minimum degree (MMD).
+///
+/// It relies on the fact that a graph of treewidth k with at least two vertices has two vertices of
+/// degree at most k. Thus for each vertex v and each subgraph containing v, the minimum degree of
+/// the other vertices is a lower bound. For each vertex v, the vertex of minimum degree other than
+/// v is repeatedly deleted and the maximum of these minimum degrees is taken, which is the maximum
+/// of this bound over all subgraphs containing v. Choosing v as a vertex of minimum degree of a
+/// subgraph shows that the result is at least the second smallest degree of each subgraph. This
+/// takes O(n³) time, so it is meant for small to medium sized graphs.
+pub fn delta2d_lower_bound<N, E>(graph: &Graph<N, E, Undirected>) -> usize {
+    let initial_adjacency = adjacency_sets(graph);
+    let mut lower_bound = 0;
+
+    for fixed_vertex in 0..initial_adjacency.len() {
+        let mut adjacency = initial_adjacency.clone();
+        let mut alive = vec![true; adjacency.len()];
+
+        while let Some(min_degree_vertex) = (0..adjacency.len())
+            .filter(|v| alive[*v] && *v != fixed_vertex)
+            .min_by_key(|v| adjacency[*v].len())
+        {
+            lower_bound = lower_bound.max(adjacency[min_degree_vertex].len());
+            for neighbour in std::mem::take(&mut adjacency[min_degree_vertex]) {
+                adjacency[neighbour].remove(&min_degree_vertex);
+            }
+            alive[min_degree_vertex] = false;
+        }
+    }
+
+    lower_bound
+}
+
+/// Adds edges between all pairs of alive non-adjacent vertices with at least the given number of
+/// common neighbours until there are no such pairs anymore.
+fn add_common_neighbour_edges(
+    adjacency: &mut [HashSet<usize>],
+    alive: &[bool],
+    common_neighbours: usize,
+) {
+    let mut added_edge = true;
+
+    while added_edge {
+        added_edge = false;
+        for (first_vertex, second_vertex) in (0..adjacency.len())
+            .filter(|v| alive[*v])
+            .tuple_combinations()
+        {
+            if !adjacency[first_vertex].contains(&second_vertex)
+                && adjacency[first_vertex]
+                    .intersection(&adjacency[second_vertex])
+                    .count()
+                    >= common_neighbours
+            {
+                adjacency[first_vertex].insert(second_vertex);
+                adjacency[second_vertex].insert(first_vertex);
+                added_edge = true;
+            }
+        }
+    }
+}
+
+/// Returns the graph on the alive vertices given by the adjacency sets.
+fn graph_from_adjacency(adjacency: &[HashSet<usize>], alive: &[bool]) -> Graph<(), (), Undirected> {
+    let mut graph: Graph<(), (), Undirected> = Graph::new_undirected();
+    let mut graph_index: Vec<Option<NodeIndex>> = vec![None; adjacency.len()];
+    for vertex in (0..adjacency.len()).filter(|v| alive[*v]) {
+        graph_index[vertex] = Some(graph.add_node(()));
+    }
+    for (vertex, neighbours) in adjacency.iter().enumerate() {
+        for neighbour in neighbours.iter().filter(|neighbour| vertex < **neighbour) {
+            if let (Some(first_vertex), Some(second_vertex)) =
+                (graph_index[vertex], graph_index[*neighbour])
+            {
+                graph.add_edge(first_vertex, second_vertex, ());
+            }
+        }
+    }
+
+    graph
+}
+
 /// Contracts the edge between vertex one and vertex two. If no edge exists, nothing happens
 fn contract_edge<N: Clone + Default, E: Clone + Default>(
     graph: &mut Graph<N, E, Undirected>,
@@ -79,3 +231,90 @@ fn contract_edge<N: Clone + Default, E: Clone + Default>(
         graph.remove_node(vertex_two);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the maximum over all steps of the minimum degree when repeatedly deleting a vertex of
+    /// minimum degree (MMD)
+    fn maximum_minimum_degree<N, E>(graph: &Graph<N, E, Undirected>) -> usize {
+        let mut adjacency = adjacency_sets(graph);
+        let mut alive = vec![true; adjacency.len()];
+        let mut max_min = 0;
+
+        while let Some(min_degree_vertex) = (0..adjacency.len())
+            .filter(|v| alive[*v])
+            .min_by_key(|v| adjacency[*v].len())
+        {
+            max_min = max_min.max(adjacency[min_degree_vertex].len());
+            for neighbour in std::mem::take(&mut adjacency[min_degree_vertex]) {
+                adjacency[neighbour].remove(&min_degree_vertex);
+            }
+            alive[min_degree_vertex] = false;
+        }
+
+        max_min
+    }
+
+    #[test]
+    fn test_lower_bounds_are_ordered() {
+        // Test graph 0 is left out, since maximum_minimum_degree_plus stops at the first isolated
+        // vertex and thus gives weak bounds for graphs that are not connected
+        let mut graphs: Vec<(Graph<i32, i32, Undirected>, usize)> = (1..3)
+            .map(|i| {
+                let test_graph = crate::tests::setup_test_graph(i);
+                (test_graph.graph, test_graph.treewidth)
+            })
+            .collect();
+        for k in 2..5 {
+            graphs.push((
+                crate::generate_k_tree(k, 20).expect("k should be smaller or eq to n"),
+                k,
+            ));
+        }
+
+        for (i, (graph, treewidth)) in graphs.iter().enumerate() {
+            let mmd = maximum_minimum_degree(graph);
+            let mmd_plus = maximum_minimum_degree_plus(graph);
+            let lbn_plus = minor_min_width_lbn(graph);
+
+            assert!(mmd <= mmd_plus, "Graph: {}", i);
+            assert!(mmd_plus <= lbn_plus, "Graph: {}", i);
+            assert!(lbn_plus <= *treewidth, "Graph: {}", i);
+        }
+    }
+
+    #[test]
+    fn test_delta2d_lower_bound() {
+        for k in 2..6 {
+            let k_tree = crate::generate_k_tree(k, 30).expect("k should be smaller or eq to n");
+
+            let delta2d = delta2d_lower_bound(&k_tree);
+
+            assert!(maximum_minimum_degree(&k_tree) <= delta2d, "k: {}", k);
+            assert_eq!(delta2d, k, "k: {}", k);
+        }
+
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            assert!(
+                delta2d_lower_bound(&test_graph.graph) <= test_graph.treewidth,
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_lbn_plus_on_grid() {
+        // The 6 x 6 grid has treewidth 6
+        let grid = crate::tests::grid_graph(6);
+
+        let lbn_plus = minor_min_width_lbn(&grid);
+
+        assert!(maximum_minimum_degree_plus(&grid) <= lbn_plus);
+        assert!(lbn_plus <= 6);
+    }
+}
@@ -0,0 +1,525 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+/// Computes an [elimination ordering](https://en.wikipedia.org/wiki/Chordal_graph#Perfect_elimination_and_efficient_recognition)
+/// of the given graph using the min-fill heuristic. In each step the vertex whose elimination adds
+/// the fewest fill edges (edges between its not yet adjacent neighbours) is eliminated. Ties are
+/// broken by choosing the vertex with the smallest NodeIndex.
+///
+/// This is the naive version recomputing the fill count of every remaining vertex in each step,
+/// which takes O(V²·d²) time. See [min_fill_ordering_lazy] for a faster version producing the
+/// same ordering.
+pub fn min_fill_ordering<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<NodeIndex> {
+    let mut adjacency = adjacency_sets(graph);
+    let mut remaining_vertices: HashSet<usize> = graph.node_indices().map(|v| v.index()).collect();
+    let mut ordering = Vec::with_capacity(graph.node_count());
+
+    while let Some(vertex) = remaining_vertices
+        .iter()
+        .min_by_key(|v| (fill_count(&adjacency, **v), **v))
+        .copied()
+    {
+        eliminate_vertex(&mut adjacency, vertex);
+        remaining_vertices.remove(&vertex);
+        ordering.push(NodeIndex::new(vertex));
+    }
+
+    ordering
+}
+
+/// Computes the same elimination ordering as [min_fill_ordering] using a priority queue.
+///
+/// The current fill count of each vertex is kept in a [BinaryHeap] with lazy invalidation, that is,
+/// entries are not removed once the fill count of a vertex changes but skipped once they are
+/// popped and found to be outdated. Instead of recomputing fill counts, they are updated
+/// incrementally whenever a fill edge is added or the eliminated vertex is removed, which only
+/// affects the neighbours of the eliminated vertex and the common neighbours of the endpoints of
+/// the added fill edges.
+pub fn min_fill_ordering_lazy<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<NodeIndex> {
+    let mut adjacency = adjacency_sets(graph);
+    let mut current_fill_counts: Vec<usize> = (0..adjacency.len())
+        .map(|v| fill_count(&adjacency, v))
+        .collect();
+    let mut eliminated = vec![false; adjacency.len()];
+    let mut ordering = Vec::with_capacity(graph.node_count());
+
+    // Reverse turns the max-heap into a min-heap. Equal fill counts are ordered by vertex index
+    // which gives the same tie-breaking as the naive version
+    let mut queue: BinaryHeap<Reverse<(usize, usize)>> = current_fill_counts
+        .iter()
+        .enumerate()
+        .map(|(vertex, fill)| Reverse((*fill, vertex)))
+        .collect();
+
+    while let Some(Reverse((fill, vertex))) = queue.pop() {
+        // Skip entries that have been invalidated since they were pushed
+        if eliminated[vertex] || fill != current_fill_counts[vertex] {
+            continue;
+        }
+        eliminated[vertex] = true;
+        ordering.push(NodeIndex::new(vertex));
+
+        let neighbours: Vec<usize> = adjacency[vertex].iter().copied().collect();
+        let mut changed_vertices: HashSet<usize> = HashSet::new();
+
+        // Turn the neighbourhood into a clique
+        for (i, neighbour) in neighbours.iter().enumerate() {
+            for other_neighbour in &neighbours[i + 1..] {
+                if !adjacency[*neighbour].contains(other_neighbour) {
+                    add_edge_updating_fill_counts(
+                        &mut adjacency,
+                        &mut current_fill_counts,
+                        &mut changed_vertices,
+                        *neighbour,
+                        *other_neighbour,
+                    );
+                }
+            }
+        }
+
+        // Remove the vertex. Since its neighbourhood is a clique now, each neighbour loses exactly
+        // the pairs of the vertex with the neighbour's neighbours outside of the closed
+        // neighbourhood of the vertex
+        for neighbour in neighbours {
+            adjacency[neighbour].remove(&vertex);
+            let non_adjacent_pairs_with_vertex = adjacency[neighbour]
+                .iter()
+                .filter(|w| !adjacency[vertex].contains(w))
+                .count();
+            current_fill_counts[neighbour] -= non_adjacent_pairs_with_vertex;
+            changed_vertices.insert(neighbour);
+        }
+        adjacency[vertex].clear();
+
+        for changed_vertex in changed_vertices {
+            queue.push(Reverse((
+                current_fill_counts[changed_vertex],
+                changed_vertex,
+            )));
+        }
+    }
+
+    ordering
+}
+
+/// Adds the edge between the two vertices and updates the fill counts of the vertices whose
+/// neighbourhoods are affected. The vertices whose fill count changed are added to changed_vertices.
+fn add_edge_updating_fill_counts(
+    adjacency: &mut [HashSet<usize>],
+    fill_counts: &mut [usize],
+    changed_vertices: &mut HashSet<usize>,
+    vertex_one: usize,
+    vertex_two: usize,
+) {
+    let common_neighbours: Vec<usize> = adjacency[vertex_one]
+        .intersection(&adjacency[vertex_two])
+        .copied()
+        .collect();
+
+    // The pair (vertex_one, vertex_two) is no longer missing in the common neighbours' neighbourhoods
+    for common_neighbour in &common_neighbours {
+        fill_counts[*common_neighbour] -= 1;
+        changed_vertices.insert(*common_neighbour);
+    }
+    // Each endpoint gains a new neighbour that is not adjacent to its non-common neighbours
+    fill_counts[vertex_one] += adjacency[vertex_one].len() - common_neighbours.len();
+    fill_counts[vertex_two] += adjacency[vertex_two].len() - common_neighbours.len();
+    changed_vertices.insert(vertex_one);
+    changed_vertices.insert(vertex_two);
+
+    adjacency[vertex_one].insert(vertex_two);
+    adjacency[vertex_two].insert(vertex_one);
+}
+
+/// Returns the width of the tree decomposition induced by the given elimination ordering, that is,
+/// the maximum number of neighbours a vertex has at the time of its elimination.
+///
+/// Returns 0 if the graph is empty.
+///
+/// **Panics**
+/// Panics if the ordering contains a vertex that is not in the graph.
+pub fn width_of_elimination_ordering<N, E>(
+    graph: &Graph<N, E, Undirected>,
+    ordering: &[NodeIndex],
+) -> usize {
+    let mut adjacency = adjacency_sets(graph);
+    let mut width = 0;
+
+    for vertex in ordering {
+        width = width.max(adjacency[vertex.index()].len());
+        eliminate_vertex(&mut adjacency, vertex.index());
+    }
+
+    width
+}
+
+/// Returns a fill-reducing permutation of the vertices of the graph, e.g. for reordering the rows and
+/// columns of a sparse symmetric matrix (whose nonzero pattern is the graph) before a Cholesky
+/// factorization. The permutation is given by the min fill elimination ordering, that is, the k-th
+/// entry is the index of the vertex that is eliminated k-th (and thus the old index of the row or
+/// column at new position k).
+///
+/// The predicted fill of the factorization is [fill_in_of_elimination_ordering] of the ordering and
+/// the width of the ordering is an upper bound for the treewidth.
+pub fn fill_reducing_permutation<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<usize> {
+    min_fill_ordering_lazy(graph)
+        .into_iter()
+        .map(|vertex| vertex.index())
+        .collect()
+}
+
+/// Returns the number of fill edges added when eliminating the vertices of the graph in the given
+/// order, which is the number of additional nonzeros (in one triangle) of the Cholesky factor of a
+/// sparse symmetric matrix with this nonzero pattern permuted according to the ordering.
+///
+/// **Panics**
+/// Panics if the ordering contains a vertex that is not in the graph.
+pub fn fill_in_of_elimination_ordering<N, E>(
+    graph: &Graph<N, E, Undirected>,
+    ordering: &[NodeIndex],
+) -> usize {
+    let mut adjacency = adjacency_sets(graph);
+
+    ordering
+        .iter()
+        .map(|vertex| eliminate_vertex(&mut adjacency, vertex.index()).len())
+        .sum()
+}
+
+/// Returns an elimination ordering of the vertices contained in the bags of the given tree
+/// decomposition whose width (see [width_of_elimination_ordering]) is at most the width of the
+/// decomposition.
+///
+/// The ordering is derived by repeatedly removing a leaf bag of the tree and eliminating the
+/// vertices of the leaf that are not contained in its neighbouring bag (in ascending order). Since
+/// the remaining neighbours of such a vertex are contained in the leaf, eliminating it only creates
+/// fill edges inside the leaf. The decomposition is assumed to be a valid tree decomposition.
+pub fn elimination_ordering_from_decomposition<E, S: BuildHasher>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> Vec<NodeIndex> {
+    let mut degrees: Vec<usize> = decomposition
+        .node_indices()
+        .map(|bag| decomposition.neighbors(bag).count())
+        .collect();
+    let mut removed = vec![false; decomposition.node_count()];
+    let mut eliminated: HashSet<NodeIndex> = HashSet::new();
+    let mut ordering = Vec::new();
+    let mut leaves: Vec<NodeIndex> = decomposition
+        .node_indices()
+        .filter(|bag| degrees[bag.index()] <= 1)
+        .collect();
+
+    while let Some(leaf) = leaves.pop() {
+        if removed[leaf.index()] {
+            continue;
+        }
+        removed[leaf.index()] = true;
+        let neighbour = decomposition
+            .neighbors(leaf)
+            .find(|neighbour| !removed[neighbour.index()]);
+
+        let mut vertices_to_eliminate: Vec<NodeIndex> = decomposition[leaf]
+            .iter()
+            .filter(|vertex| {
+                neighbour.map_or(true, |neighbour| !decomposition[neighbour].contains(vertex))
+            })
+            .copied()
+            .filter(|vertex| !eliminated.contains(vertex))
+            .collect();
+        vertices_to_eliminate.sort();
+        eliminated.extend(vertices_to_eliminate.iter().copied());
+        ordering.extend(vertices_to_eliminate);
+
+        if let Some(neighbour) = neighbour {
+            degrees[neighbour.index()] -= 1;
+            if degrees[neighbour.index()] <= 1 {
+                leaves.push(neighbour);
+            }
+        }
+    }
+
+    ordering
+}
+
+/// Returns one [Graphviz](https://graphviz.org/) dot string per step of eliminating the vertices
+/// of the graph in the given order, which allows watching the elimination game.
+///
+/// The frame of step i shows the graph after eliminating the first i vertices of the ordering. The
+/// vertices are labeled by their indices, the vertex eliminated in step i is filled red and the fill
+/// edges added by the previous eliminations are dashed.
+///
+/// **Panics**
+/// Panics if the ordering contains a vertex that is not in the graph.
+pub fn elimination_game_dot_frames<N, E>(
+    graph: &Graph<N, E, Undirected>,
+    ordering: &[NodeIndex],
+) -> Vec<String> {
+    let mut adjacency = adjacency_sets(graph);
+    let mut eliminated = vec![false; adjacency.len()];
+    let mut fill_edges: HashSet<(usize, usize)> = HashSet::new();
+    let mut frames = Vec::with_capacity(ordering.len());
+
+    for vertex in ordering {
+        let mut frame = String::from("graph {\n");
+        for other_vertex in (0..adjacency.len()).filter(|v| !eliminated[*v]) {
+            if other_vertex == vertex.index() {
+                frame.push_str(&format!(
+                    "    {} [style=filled, fillcolor=red];\n",
+                    other_vertex
+                ));
+            } else {
+                frame.push_str(&format!("    {};\n", other_vertex));
+            }
+        }
+        for (first_vertex, neighbours) in adjacency.iter().enumerate() {
+            let mut neighbours: Vec<usize> = neighbours
+                .iter()
+                .copied()
+                .filter(|second_vertex| first_vertex < *second_vertex)
+                .collect();
+            neighbours.sort_unstable();
+            for second_vertex in neighbours {
+                if fill_edges.contains(&(first_vertex, second_vertex)) {
+                    frame.push_str(&format!(
+                        "    {} -- {} [style=dashed];\n",
+                        first_vertex, second_vertex
+                    ));
+                } else {
+                    frame.push_str(&format!("    {} -- {};\n", first_vertex, second_vertex));
+                }
+            }
+        }
+        frame.push_str("}\n");
+        frames.push(frame);
+
+        eliminated[vertex.index()] = true;
+        fill_edges.extend(
+            eliminate_vertex(&mut adjacency, vertex.index())
+                .into_iter()
+                .map(|(first_vertex, second_vertex)| {
+                    (
+                        first_vertex.min(second_vertex),
+                        first_vertex.max(second_vertex),
+                    )
+                }),
+        );
+    }
+
+    frames
+}
+
+/// Returns true if the graph is [chordal](https://en.wikipedia.org/wiki/Chordal_graph), that is,
+/// if every cycle of length at least 4 has a chord.
+///
+/// Uses the fact that the reverse of a maximum cardinality search ordering is a perfect elimination
+/// ordering if and only if the graph is chordal, so it suffices to check whether eliminating the
+/// vertices in this ordering adds any fill edges.
+pub fn is_chordal<N, E>(graph: &Graph<N, E, Undirected>) -> bool {
+    let mut adjacency = adjacency_sets(graph);
+
+    maximum_cardinality_search_ordering(graph)
+        .into_iter()
+        .all(|vertex| eliminate_vertex(&mut adjacency, vertex.index()).is_empty())
+}
+
+/// Computes an elimination ordering by [maximum cardinality search](https://doi.org/10.1137/0213035):
+/// Vertices are visited one after another, always choosing the unvisited vertex with the most
+/// visited neighbours, and the ordering is the reverse of the visiting order. Ties are broken by
+/// choosing the vertex with the smallest NodeIndex.
+///
+/// The ordering is a perfect elimination ordering if and only if the graph is chordal.
+pub(crate) fn maximum_cardinality_search_ordering<N, E>(
+    graph: &Graph<N, E, Undirected>,
+) -> Vec<NodeIndex> {
+    let adjacency = adjacency_sets(graph);
+    let mut visited_neighbours = vec![0; adjacency.len()];
+    let mut visited = vec![false; adjacency.len()];
+    let mut ordering = Vec::with_capacity(adjacency.len());
+
+    while let Some(vertex) = (0..adjacency.len())
+        .filter(|v| !visited[*v])
+        .min_by_key(|v| (Reverse(visited_neighbours[*v]), *v))
+    {
+        visited[vertex] = true;
+        for neighbour in &adjacency[vertex] {
+            visited_neighbours[*neighbour] += 1;
+        }
+        ordering.push(NodeIndex::new(vertex));
+    }
+
+    ordering.reverse();
+    ordering
+}
+
+/// Returns the neighbourhoods of the vertices of the graph indexed by the index of their NodeIndex.
+/// Self loops are ignored.
+pub(crate) fn adjacency_sets<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<HashSet<usize>> {
+    graph
+        .node_indices()
+        .map(|v| {
+            graph
+                .neighbors(v)
+                .filter(|w| w != &v)
+                .map(|w| w.index())
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the number of edges that would need to be added to turn the neighbourhood of the
+/// vertex into a clique.
+pub(crate) fn fill_count(adjacency: &[HashSet<usize>], vertex: usize) -> usize {
+    let neighbours: Vec<_> = adjacency[vertex].iter().collect();
+    let mut fill = 0;
+
+    for (i, neighbour) in neighbours.iter().enumerate() {
+        for other_neighbour in &neighbours[i + 1..] {
+            if !adjacency[**neighbour].contains(other_neighbour) {
+                fill += 1;
+            }
+        }
+    }
+
+    fill
+}
+
+/// Eliminates the vertex by turning its neighbourhood into a clique and removing it from the
+/// graph. Returns the fill edges that were added.
+pub(crate) fn eliminate_vertex(
+    adjacency: &mut [HashSet<usize>],
+    vertex: usize,
+) -> Vec<(usize, usize)> {
+    let neighbours: Vec<usize> = std::mem::take(&mut adjacency[vertex]).into_iter().collect();
+    let mut fill_edges = Vec::new();
+
+    for (i, neighbour) in neighbours.iter().enumerate() {
+        adjacency[*neighbour].remove(&vertex);
+        for other_neighbour in &neighbours[i + 1..] {
+            if adjacency[*neighbour].insert(*other_neighbour) {
+                adjacency[*other_neighbour].insert(*neighbour);
+                fill_edges.push((*neighbour, *other_neighbour));
+            }
+        }
+    }
+
+    fill_edges
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[test]
+    fn test_min_fill_lazy_matches_naive() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            let naive_ordering = min_fill_ordering(&test_graph.graph);
+            let lazy_ordering = min_fill_ordering_lazy(&test_graph.graph);
+
+            assert_eq!(naive_ordering.len(), test_graph.graph.node_count());
+            assert_eq!(
+                width_of_elimination_ordering(&test_graph.graph, &naive_ordering),
+                width_of_elimination_ordering(&test_graph.graph, &lazy_ordering),
+                "Test graph: {}",
+                i
+            );
+            assert_eq!(naive_ordering, lazy_ordering, "Test graph: {}", i);
+        }
+
+        let mut rng = rand::thread_rng();
+        let graph = crate::generate_partial_k_tree(5, 60, 30, &mut rng)
+            .expect("k should be smaller or eq to n");
+        assert_eq!(min_fill_ordering(&graph), min_fill_ordering_lazy(&graph));
+    }
+
+    #[test]
+    fn test_fill_reducing_permutation_on_k_tree() {
+        for k in 2..5 {
+            let k_tree = crate::generate_k_tree(k, 40).expect("k should be smaller or eq to n");
+
+            let permutation = fill_reducing_permutation(&k_tree);
+            let mut sorted_permutation = permutation.clone();
+            sorted_permutation.sort();
+            assert_eq!(sorted_permutation, (0..40).collect::<Vec<_>>());
+
+            let permuted_ordering: Vec<_> = permutation.into_iter().map(NodeIndex::new).collect();
+            let identity_ordering: Vec<_> = k_tree.node_indices().collect();
+            let permuted_fill = fill_in_of_elimination_ordering(&k_tree, &permuted_ordering);
+            let identity_fill = fill_in_of_elimination_ordering(&k_tree, &identity_ordering);
+
+            // k-trees are chordal, so min fill finds a perfect elimination ordering
+            assert_eq!(permuted_fill, 0, "k: {}", k);
+            assert!(
+                permuted_fill < identity_fill,
+                "k: {}, identity fill: {}",
+                k,
+                identity_fill
+            );
+        }
+    }
+
+    #[test]
+    fn test_elimination_game_dot_frames_of_cycle() {
+        let cycle =
+            petgraph::graph::UnGraph::<i32, i32>::from_edges([(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let ordering: Vec<NodeIndex> = cycle.node_indices().collect();
+
+        let frames = elimination_game_dot_frames(&cycle, &ordering);
+
+        assert_eq!(frames.len(), ordering.len());
+        for frame in frames.iter() {
+            assert!(frame.starts_with("graph {"));
+        }
+        assert!(frames[0].contains("0 [style=filled, fillcolor=red];"));
+        assert!(frames[0].contains("0 -- 1;"));
+        // Eliminating 0 adds the fill edge between 1 and 3
+        assert!(frames[1].contains("1 [style=filled, fillcolor=red];"));
+        assert!(frames[1].contains("1 -- 3 [style=dashed];"));
+        assert!(!frames[1].contains("0 -- 1"));
+        assert!(!frames[3].contains("--"));
+    }
+
+    #[test]
+    fn test_is_chordal() {
+        assert!(is_chordal(&crate::tests::setup_test_graph(0).graph));
+        assert!(!is_chordal(&crate::tests::setup_test_graph(1).graph));
+
+        let k_tree = crate::generate_k_tree(4, 30).expect("k should be smaller or eq to n");
+        assert!(is_chordal(&k_tree));
+
+        let mut cycle: Graph<(), (), Undirected> = Graph::new_undirected();
+        for _ in 0..4 {
+            cycle.add_node(());
+        }
+        cycle.extend_with_edges([(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert!(!is_chordal(&cycle));
+        cycle.add_edge(NodeIndex::new(0), NodeIndex::new(2), ());
+        assert!(is_chordal(&cycle));
+    }
+
+    #[test]
+    fn test_min_fill_lazy_on_large_partial_k_tree() {
+        let mut rng = rand::thread_rng();
+        let graph = crate::generate_partial_k_tree(10, 300, 30, &mut rng)
+            .expect("k should be smaller or eq to n");
+
+        let start = Instant::now();
+        let ordering = min_fill_ordering_lazy(&graph);
+        let elapsed = start.elapsed();
+
+        assert_eq!(ordering.len(), 300);
+        assert!(
+            elapsed < Duration::from_secs(2),
+            "Lazy min fill took {:?}",
+            elapsed
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::BuildHasher,
+};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    compute_treewidth_upper_bound,
+    find_width_of_tree_decomposition::find_width_of_tree_decomposition, negative_intersection,
+    separator_approximation::components_of_subgraph, CliqueSource, SpanningTreeConstructionMethod,
+};
+
+/// Computes an upper bound for the treewidth assuming that the graph is
+/// [planar](https://en.wikipedia.org/wiki/Planar_graph). The bound is only good (O(√n) up to the
+/// boundaries accumulating in the recursion) if the graph actually is planar, for other graphs it
+/// is still a valid upper bound but usually a bad one.
+///
+/// The graph is decomposed like in [treewidth_approx_separator][crate::treewidth_approx_separator]
+/// except that the separators are BFS layers in the style of Lipton and Tarjan: For a component W
+/// with boundary B a breadth first search is started in W and among the layers L such that both the
+/// vertices before and after L make up at most 2/3 of W, the smallest one is chosen as separator S.
+/// The median layer always satisfies this. B ∪ S is a bag and the components of W - S are handled
+/// recursively.
+///
+/// Once a component together with its boundary has at most leaf_size vertices, the boundary is
+/// turned into a clique and the upper bound of the induced subgraph is computed with the clique
+/// graph heuristic using [FilWh][SpanningTreeConstructionMethod::FilWh]. Since the boundary is
+/// contained in a bag of this decomposition, it can be attached to the bag containing the boundary.
+/// The returned upper bound is the maximum of the widths of all bags and pieces.
+pub fn treewidth_planar_hint<N: Clone, E: Clone, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    leaf_size: usize,
+) -> usize {
+    let all_vertices: HashSet<NodeIndex> = graph.node_indices().collect();
+
+    // Each entry consists of the component to be decomposed and its boundary
+    let mut stack: Vec<(HashSet<NodeIndex>, HashSet<NodeIndex>)> =
+        components_of_subgraph(graph, &all_vertices)
+            .into_iter()
+            .map(|component| (component, HashSet::new()))
+            .collect();
+    let mut upper_bound = 0;
+
+    while let Some((component, boundary)) = stack.pop() {
+        if component.len() + boundary.len() <= leaf_size {
+            upper_bound = upper_bound.max(width_of_piece::<N, E, S>(graph, &component, &boundary));
+            continue;
+        }
+
+        let separator = find_layer_separator(graph, &component);
+
+        let mut bag: HashSet<NodeIndex> = boundary;
+        bag.extend(separator.iter().copied());
+        upper_bound = upper_bound.max(bag.len() - 1);
+
+        let remaining_vertices: HashSet<NodeIndex> =
+            component.difference(&separator).copied().collect();
+        for remaining_component in components_of_subgraph(graph, &remaining_vertices) {
+            let remaining_component_boundary: HashSet<NodeIndex> = bag
+                .iter()
+                .filter(|v| {
+                    graph
+                        .neighbors(**v)
+                        .any(|w| remaining_component.contains(&w))
+                })
+                .copied()
+                .collect();
+            stack.push((remaining_component, remaining_component_boundary));
+        }
+    }
+
+    upper_bound
+}
+
+/// Computes an upper bound for the treewidth from the BFS layers of the graph started at root. Since
+/// edges only join vertices of the same or of consecutive layers, the bags consisting of two
+/// consecutive layers form a path decomposition, whose width is returned. This is fast and good for
+/// graphs with thin layers, e.g. a k-outerplanar graph with few vertices per layer.
+///
+/// Vertices that are not reachable from the root are layered by further breadth first searches
+/// started at the smallest unreached vertex and get their own path decompositions, which are joined
+/// to the first one.
+///
+/// **Panics**
+/// Panics if the root is not a vertex of the graph.
+pub fn treewidth_upper_bound_from_bfs_layers<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    root: NodeIndex,
+) -> usize {
+    find_width_of_tree_decomposition(&bfs_layer_decomposition::<N, E, S>(graph, root))
+}
+
+/// Returns the path decomposition described in [treewidth_upper_bound_from_bfs_layers].
+fn bfs_layer_decomposition<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    root: NodeIndex,
+) -> Graph<HashSet<NodeIndex, S>, (), Undirected> {
+    assert!(
+        root.index() < graph.node_count(),
+        "Root should be a vertex of the graph"
+    );
+    let mut decomposition: Graph<HashSet<NodeIndex, S>, (), Undirected> = Graph::new_undirected();
+    let mut seen: HashSet<NodeIndex> = HashSet::new();
+
+    for component_root in std::iter::once(root).chain(graph.node_indices()) {
+        if !seen.insert(component_root) {
+            continue;
+        }
+
+        let mut layers: Vec<Vec<NodeIndex>> = Vec::new();
+        let mut queue: VecDeque<(NodeIndex, usize)> = VecDeque::from([(component_root, 0)]);
+        while let Some((vertex, depth)) = queue.pop_front() {
+            if layers.len() == depth {
+                layers.push(Vec::new());
+            }
+            layers[depth].push(vertex);
+            for neighbour in graph.neighbors(vertex) {
+                if seen.insert(neighbour) {
+                    queue.push_back((neighbour, depth + 1));
+                }
+            }
+        }
+
+        let bags: Vec<HashSet<NodeIndex, S>> = if layers.len() == 1 {
+            vec![layers[0].iter().copied().collect()]
+        } else {
+            layers
+                .iter()
+                .tuple_windows()
+                .map(|(layer, next_layer)| layer.iter().chain(next_layer).copied().collect())
+                .collect()
+        };
+
+        let mut previous_bag: Option<NodeIndex> = None;
+        for bag in bags {
+            let bag_index = decomposition.add_node(bag);
+            if let Some(previous_bag) = previous_bag {
+                decomposition.add_edge(previous_bag, bag_index, ());
+            } else if bag_index.index() > 0 {
+                // Bags of different components are disjoint
+                decomposition.add_edge(NodeIndex::new(0), bag_index, ());
+            }
+            previous_bag = Some(bag_index);
+        }
+    }
+
+    decomposition
+}
+
+/// Returns the smallest BFS layer of the (connected) subgraph induced by the component such that
+/// the layers before and after it contain at most 2/3 of the vertices of the component each. The
+/// breadth first search starts at the smallest vertex of the component.
+///
+/// The returned separator is non-empty if the component is non-empty.
+fn find_layer_separator<N, E>(
+    graph: &Graph<N, E, Undirected>,
+    component: &HashSet<NodeIndex>,
+) -> HashSet<NodeIndex> {
+    let Some(root) = component.iter().min().copied() else {
+        return HashSet::new();
+    };
+
+    let mut layers: Vec<Vec<NodeIndex>> = Vec::new();
+    let mut seen: HashSet<NodeIndex> = HashSet::from([root]);
+    let mut queue: VecDeque<(NodeIndex, usize)> = VecDeque::from([(root, 0)]);
+    while let Some((vertex, depth)) = queue.pop_front() {
+        if layers.len() == depth {
+            layers.push(Vec::new());
+        }
+        layers[depth].push(vertex);
+        for neighbour in graph.neighbors(vertex) {
+            if component.contains(&neighbour) && seen.insert(neighbour) {
+                queue.push_back((neighbour, depth + 1));
+            }
+        }
+    }
+
+    let maximum_side_size = 2 * component.len() / 3;
+    let mut vertices_before = 0;
+    let mut best_layer: Option<&Vec<NodeIndex>> = None;
+    for layer in layers.iter() {
+        let vertices_after = component.len() - vertices_before - layer.len();
+        if vertices_before <= maximum_side_size
+            && vertices_after <= maximum_side_size
+            && best_layer.map_or(true, |best_layer| layer.len() < best_layer.len())
+        {
+            best_layer = Some(layer);
+        }
+        vertices_before += layer.len();
+    }
+
+    best_layer
+        .expect("The median layer should satisfy the balance")
+        .iter()
+        .copied()
+        .collect()
+}
+
+/// Returns the upper bound computed by the clique graph heuristic for the subgraph induced by the
+/// component and its boundary, where the boundary is turned into a clique.
+fn width_of_piece<N: Clone, E: Clone, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    component: &HashSet<NodeIndex>,
+    boundary: &HashSet<NodeIndex>,
+) -> usize {
+    let mut piece: Graph<NodeIndex, (), Undirected> = graph.filter_map(
+        |v, _| (component.contains(&v) || boundary.contains(&v)).then_some(v),
+        |_, _| Some(()),
+    );
+
+    let boundary_in_piece: Vec<NodeIndex> = piece
+        .node_indices()
+        .filter(|v| boundary.contains(&piece[*v]))
+        .collect();
+    for (first_vertex, second_vertex) in boundary_in_piece.into_iter().tuple_combinations() {
+        piece.update_edge(first_vertex, second_vertex, ());
+    }
+
+    compute_treewidth_upper_bound::<_, _, _, S>(
+        &piece,
+        negative_intersection,
+        SpanningTreeConstructionMethod::FilWh,
+        false,
+        CliqueSource::Maximal,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    #[test]
+    fn test_treewidth_planar_hint_on_grid_graphs() {
+        for side in [6, 12, 24] {
+            let upper_bound =
+                treewidth_planar_hint::<_, _, RandomState>(&crate::tests::grid_graph(side), 16);
+
+            // The side x side grid has treewidth side and the bound should be linear in side
+            assert!(
+                side <= upper_bound && upper_bound <= 2 * side,
+                "Side: {}, upper bound: {}",
+                side,
+                upper_bound
+            );
+        }
+    }
+
+    #[test]
+    fn test_bfs_layers_on_grid_graphs() {
+        for side in [3, 6, 12] {
+            let grid = crate::tests::grid_graph(side);
+            let upper_bound = treewidth_upper_bound_from_bfs_layers::<_, _, RandomState>(
+                &grid,
+                NodeIndex::new(0),
+            );
+
+            // The layers from a corner are the anti-diagonals, the two longest ones have side and
+            // side - 1 vertices. The side x side grid has treewidth side.
+            assert_eq!(upper_bound, 2 * side - 2, "Side: {}", side);
+            assert!(side <= upper_bound, "Side: {}", side);
+        }
+    }
+
+    #[test]
+    fn test_bfs_layer_decomposition_is_valid() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            for root in test_graph.graph.node_indices() {
+                let decomposition =
+                    bfs_layer_decomposition::<_, _, RandomState>(&test_graph.graph, root);
+
+                assert_eq!(
+                    crate::check_tree_decomposition::validate_tree_decomposition(
+                        &test_graph.graph,
+                        &decomposition
+                    ),
+                    Ok(()),
+                    "Test graph: {}, root: {:?}",
+                    i,
+                    root
+                );
+                assert!(
+                    find_width_of_tree_decomposition(&decomposition) >= test_graph.treewidth,
+                    "Test graph: {}, root: {:?}",
+                    i,
+                    root
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,694 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    compute_treewidth_upper_bound, min_fill_heuristic::adjacency_sets, CliqueSource,
+    SpanningTreeConstructionMethod,
+};
+
+/// Applies the safe reduction rules for vertices of degree at most 2 until none of them apply and
+/// returns the remaining "core" of the graph together with the accumulated lower bound low, such
+/// that the treewidth of the graph is the maximum of the treewidth of the core and low. Thus the
+/// clique graph heuristic only needs to be run on the core and the final upper bound is
+/// max(core_width, low).
+///
+/// The rules are (following Bodlaender, Koster and van den Eijkhof):
+/// - Islet: A vertex of degree 0 is removed.
+/// - Twig: A vertex of degree 1 is removed and low is set to at least 1.
+/// - Series: If low is at least 2, a vertex of degree 2 is removed and an edge between its two
+///   neighbours is added.
+///
+/// If the islet and twig rules don't apply anymore, the remaining graph has minimum degree at least
+/// 2 and thus treewidth at least 2, so low is raised to 2 to enable the series rule.
+///
+/// The vertices of the core keep their relative order and weights. Edges added by the series rule
+/// get the weight of one of the edges they replace.
+pub fn peel<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> (Graph<N, E, Undirected>, usize) {
+    let mut adjacency = adjacency_sets(graph);
+    let mut removed = vec![false; adjacency.len()];
+    let mut remaining_vertices = adjacency.len();
+    // Weights of the edges added by the series rule with the smaller endpoint first
+    let mut added_edge_weights: HashMap<(usize, usize), E> = HashMap::new();
+    let mut low = 0;
+
+    loop {
+        let mut stack: Vec<usize> = (0..adjacency.len()).filter(|v| !removed[*v]).collect();
+
+        while let Some(vertex) = stack.pop() {
+            if removed[vertex] {
+                continue;
+            }
+            let neighbours: Vec<usize> = adjacency[vertex].iter().copied().collect();
+
+            match neighbours.len() {
+                // Islet and twig rule
+                degree @ (0 | 1) => {
+                    low = low.max(degree);
+                }
+                // Series rule
+                2 if low >= 2 => {
+                    let (first_neighbour, second_neighbour) = (neighbours[0], neighbours[1]);
+                    if adjacency[first_neighbour].insert(second_neighbour) {
+                        adjacency[second_neighbour].insert(first_neighbour);
+                        let weight =
+                            edge_weight(graph, &added_edge_weights, vertex, first_neighbour);
+                        added_edge_weights.insert(
+                            (
+                                first_neighbour.min(second_neighbour),
+                                first_neighbour.max(second_neighbour),
+                            ),
+                            weight,
+                        );
+                    }
+                }
+                _ => continue,
+            }
+
+            for neighbour in neighbours {
+                adjacency[neighbour].remove(&vertex);
+                stack.push(neighbour);
+            }
+            adjacency[vertex].clear();
+            removed[vertex] = true;
+            remaining_vertices -= 1;
+        }
+
+        if remaining_vertices > 0 && low < 2 {
+            // The remaining graph has minimum degree at least 2 and thus contains a cycle
+            low = 2;
+        } else {
+            break;
+        }
+    }
+
+    // Construct the core from the remaining vertices and edges
+    let mut core: Graph<N, E, Undirected> = Graph::new_undirected();
+    let mut core_index: HashMap<usize, NodeIndex> = HashMap::new();
+    for vertex in (0..adjacency.len()).filter(|v| !removed[*v]) {
+        core_index.insert(vertex, core.add_node(graph[NodeIndex::new(vertex)].clone()));
+    }
+    for vertex in (0..adjacency.len()).filter(|v| !removed[*v]) {
+        let mut neighbours: Vec<usize> = adjacency[vertex]
+            .iter()
+            .copied()
+            .filter(|neighbour| *neighbour > vertex)
+            .collect();
+        neighbours.sort_unstable();
+        for neighbour in neighbours {
+            core.add_edge(
+                core_index[&vertex],
+                core_index[&neighbour],
+                edge_weight(graph, &added_edge_weights, vertex, neighbour),
+            );
+        }
+    }
+
+    (core, low)
+}
+
+/// Merges [twins](https://en.wikipedia.org/wiki/Twin_(graph_theory)) of the graph, that is,
+/// vertices with the same neighbourhood (false twins) or the same closed neighbourhood (true twins).
+/// Of each class of twins only the vertex with the smallest NodeIndex is kept as representative.
+///
+/// Returns the reduced graph together with a map from each vertex of the reduced graph to the
+/// vertices of the given graph it stands for, the representative first followed by its twins. A
+/// tree decomposition of the reduced graph is turned into one of the given graph by replacing each
+/// vertex in each bag by the vertices it stands for.
+///
+/// **Caution**: In contrast to [peel], this is not safe, since the width grows by the number of twins
+/// merged into the vertices of a bag. For example, K_{3,3} is reduced to a single edge.
+///
+/// Twins are only merged once, merging may create new twins in the reduced graph.
+pub fn reduce_twins<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> (Graph<N, E, Undirected>, HashMap<NodeIndex, Vec<NodeIndex>>) {
+    let adjacency = adjacency_sets(graph);
+    let mut representative: Vec<usize> = (0..adjacency.len()).collect();
+
+    // A vertex can't have both false and true twins, so both kinds can be grouped one after another
+    for closed_neighbourhood in [false, true] {
+        let mut class_representatives: HashMap<Vec<usize>, usize> = HashMap::new();
+        let remaining_vertices: Vec<usize> = (0..adjacency.len())
+            .filter(|v| representative[*v] == *v)
+            .collect();
+        for vertex in remaining_vertices {
+            let mut neighbourhood: Vec<usize> = adjacency[vertex].iter().copied().collect();
+            if closed_neighbourhood {
+                neighbourhood.push(vertex);
+            }
+            neighbourhood.sort_unstable();
+            representative[vertex] = *class_representatives.entry(neighbourhood).or_insert(vertex);
+        }
+    }
+
+    let reduced_graph = graph.filter_map(
+        |vertex, weight| (representative[vertex.index()] == vertex.index()).then(|| weight.clone()),
+        |_, weight| Some(weight.clone()),
+    );
+
+    let mut reduced_index: HashMap<usize, NodeIndex> = HashMap::new();
+    for (index, vertex) in (0..adjacency.len())
+        .filter(|v| representative[*v] == *v)
+        .enumerate()
+    {
+        reduced_index.insert(vertex, NodeIndex::new(index));
+    }
+    let mut twins: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+    for vertex in 0..adjacency.len() {
+        twins
+            .entry(reduced_index[&representative[vertex]])
+            .or_default()
+            .push(NodeIndex::new(vertex));
+    }
+
+    (reduced_graph, twins)
+}
+
+/// Removes vertices dominated by a neighbour, that is, vertices u with a neighbour v such that the
+/// closed neighbourhood of u is a subset of the closed neighbourhood of v. The vertices are
+/// considered in the order of their NodeIndex and a vertex that dominates a removed vertex is never
+/// removed itself, so each removed vertex is dominated by a vertex of the reduced graph. If a vertex
+/// is dominated by multiple neighbours that haven't been removed, the one with the smallest
+/// NodeIndex is chosen.
+///
+/// Returns the reduced graph, whose vertices keep their relative order and weights, together with a
+/// map from each removed vertex to the vertex dominating it (both as NodeIndices of the given
+/// graph). A tree decomposition of the reduced graph is turned into one of the given graph by adding
+/// each removed vertex to all bags containing its dominator.
+///
+/// **Caution**: Like [reduce_twins], this is not safe, since the width grows by the number of
+/// vertices added to a bag. For example, a complete graph is reduced to a single vertex. Only
+/// dominated vertices of degree 1 can always be removed without changing the treewidth (as long as
+/// an edge remains), see [peel].
+pub fn reduce_dominated_vertices<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> (Graph<N, E, Undirected>, HashMap<NodeIndex, NodeIndex>) {
+    let adjacency = adjacency_sets(graph);
+    let mut removed = vec![false; adjacency.len()];
+    let mut is_dominator = vec![false; adjacency.len()];
+    let mut dominators: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+
+    for vertex in 0..adjacency.len() {
+        if is_dominator[vertex] {
+            continue;
+        }
+        let dominator = adjacency[vertex]
+            .iter()
+            .copied()
+            .filter(|neighbour| {
+                !removed[*neighbour]
+                    && adjacency[vertex]
+                        .iter()
+                        .all(|w| w == neighbour || adjacency[*neighbour].contains(w))
+            })
+            .min();
+        if let Some(dominator) = dominator {
+            removed[vertex] = true;
+            is_dominator[dominator] = true;
+            dominators.insert(NodeIndex::new(vertex), NodeIndex::new(dominator));
+        }
+    }
+
+    let reduced_graph = graph.filter_map(
+        |vertex, weight| (!removed[vertex.index()]).then(|| weight.clone()),
+        |_, weight| Some(weight.clone()),
+    );
+
+    (reduced_graph, dominators)
+}
+
+/// Contracts [modules](https://en.wikipedia.org/wiki/Modular_decomposition) of the graph, that is,
+/// sets of vertices M such that each vertex outside of M is adjacent to either all or none of the
+/// vertices of M. The modules are the maximal modules not containing the vertex v with the smallest
+/// NodeIndex, which are found by partition refinement: Starting with the neighbours and the
+/// non-neighbours of v, each part is split by the neighbourhoods of the vertices outside of it
+/// until no part can be split anymore.
+///
+/// Returns the quotient graph, whose vertices are v and the modules, together with the vertices of
+/// the given graph making up each vertex of the quotient graph (sorted, the first one is the
+/// representative whose weight the vertex gets). Two vertices of the quotient graph are adjacent if
+/// their representatives are, the edge gets the weight of the edge between them.
+///
+/// Since the quotient graph is the subgraph induced by the representatives, its treewidth is a
+/// lower bound for the treewidth of the graph. Replacing each vertex in each bag of a tree
+/// decomposition of the quotient graph by the vertices of its module yields a tree decomposition of
+/// the graph, whose bags grow by the sizes of the contained modules minus one. Modules containing v
+/// are not found.
+pub fn quotient_by_modules<N: Clone + Default, E: Clone + Default>(
+    graph: &Graph<N, E, Undirected>,
+) -> (Graph<N, E, Undirected>, Vec<Vec<NodeIndex>>) {
+    let adjacency = adjacency_sets(graph);
+    if adjacency.is_empty() {
+        return (Graph::new_undirected(), Vec::new());
+    }
+
+    let (neighbours, non_neighbours): (Vec<usize>, Vec<usize>) =
+        (1..adjacency.len()).partition(|w| adjacency[0].contains(w));
+    let mut parts: Vec<Vec<usize>> = vec![vec![0], neighbours, non_neighbours];
+    parts.retain(|part| !part.is_empty());
+
+    let mut refined = true;
+    while refined {
+        refined = false;
+        for part_index in 1..parts.len() {
+            let part = &parts[part_index];
+            let splitting_vertex = (1..adjacency.len()).find(|y| {
+                !part.contains(y)
+                    && part.iter().any(|w| adjacency[*y].contains(w))
+                    && part.iter().any(|w| !adjacency[*y].contains(w))
+            });
+            if let Some(splitting_vertex) = splitting_vertex {
+                let (adjacent, non_adjacent): (Vec<usize>, Vec<usize>) = parts[part_index]
+                    .iter()
+                    .partition(|w| adjacency[splitting_vertex].contains(w));
+                parts[part_index] = adjacent;
+                parts.push(non_adjacent);
+                refined = true;
+                break;
+            }
+        }
+    }
+    for part in parts.iter_mut() {
+        part.sort_unstable();
+    }
+    parts.sort_unstable();
+
+    let mut quotient_graph: Graph<N, E, Undirected> =
+        Graph::with_capacity(parts.len(), graph.edge_count());
+    for part in parts.iter() {
+        quotient_graph.add_node(graph[NodeIndex::new(part[0])].clone());
+    }
+    for ((first_index, first_part), (second_index, second_part)) in
+        parts.iter().enumerate().tuple_combinations()
+    {
+        if let Some(edge) = graph.find_edge(
+            NodeIndex::new(first_part[0]),
+            NodeIndex::new(second_part[0]),
+        ) {
+            quotient_graph.add_edge(
+                NodeIndex::new(first_index),
+                NodeIndex::new(second_index),
+                graph[edge].clone(),
+            );
+        }
+    }
+
+    let modules = parts
+        .into_iter()
+        .map(|part| part.into_iter().map(NodeIndex::new).collect())
+        .collect();
+
+    (quotient_graph, modules)
+}
+
+/// Returns true if the graph is series-parallel in the sense that each of its biconnected components
+/// is a [series-parallel graph](https://en.wikipedia.org/wiki/Series%E2%80%93parallel_graph), which
+/// is the case if and only if the treewidth of the graph is at most 2.
+///
+/// The graph is series-parallel if and only if it is reduced to the empty graph by removing
+/// vertices of degree at most 1 and contracting vertices of degree 2 (merging parallel edges), which
+/// are exactly the rules applied by [peel]. Thus this takes time linear in the size of the graph
+/// (up to hashing).
+pub fn is_series_parallel<N: Clone, E: Clone>(graph: &Graph<N, E, Undirected>) -> bool {
+    let (core, _) = peel(graph);
+
+    core.node_count() == 0
+}
+
+/// Returns the [2-core](https://en.wikipedia.org/wiki/Degeneracy_(graph_theory)#k-Cores) of the
+/// graph, that is, the subgraph remaining after iteratively removing vertices of degree at most 1.
+/// The vertices and edges of the 2-core keep their relative order and weights.
+///
+/// This only applies the islet and twig rules of [peel].
+pub fn two_core<N: Clone, E: Clone>(graph: &Graph<N, E, Undirected>) -> Graph<N, E, Undirected> {
+    let mut adjacency = adjacency_sets(graph);
+    let mut removed = vec![false; adjacency.len()];
+    let mut stack: Vec<usize> = (0..adjacency.len()).collect();
+
+    while let Some(vertex) = stack.pop() {
+        if removed[vertex] || adjacency[vertex].len() > 1 {
+            continue;
+        }
+        for neighbour in std::mem::take(&mut adjacency[vertex]) {
+            adjacency[neighbour].remove(&vertex);
+            stack.push(neighbour);
+        }
+        removed[vertex] = true;
+    }
+
+    graph.filter_map(
+        |vertex, weight| (!removed[vertex.index()]).then(|| weight.clone()),
+        |_, weight| Some(weight.clone()),
+    )
+}
+
+/// Computes an upper bound for the treewidth with [compute_treewidth_upper_bound] on the
+/// [2-core][two_core] of the graph only (see there for the remaining parameters). Since the removed
+/// vertices form trees attached to the 2-core, the bound is the maximum of the bound of the 2-core
+/// and 1 if an edge was removed.
+///
+/// This is a cheap speedup for graphs with big sparse fringes. See [peel] for more reduction rules.
+pub fn treewidth_via_two_core<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_source: CliqueSource,
+) -> usize {
+    let core = two_core(graph);
+    let core_width = compute_treewidth_upper_bound(
+        &core,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_source,
+        None,
+    );
+
+    if core.edge_count() < graph.edge_count() {
+        core_width.max(1)
+    } else {
+        core_width
+    }
+}
+
+/// Returns the improved graph (following Bodlaender and Koster) of the graph with respect to the
+/// given bound low, that is, the graph with an additional edge between any two non-adjacent vertices
+/// that have at least low + 1 common neighbours. The common neighbours are counted in the given
+/// graph, the added edges get the default weight.
+///
+/// Two vertices with at least low + 1 common neighbours lie in a common bag of every tree
+/// decomposition of width at most low, so the graph has treewidth at most low if and only if the
+/// improved graph has. In particular, the improved graph has the same treewidth as the graph if low
+/// is at least the treewidth, and a lower bound greater than low for the improved graph proves that
+/// the treewidth of the graph is greater than low as well.
+pub fn improved_graph<N: Clone, E: Clone + Default>(
+    graph: &Graph<N, E, Undirected>,
+    low: usize,
+) -> Graph<N, E, Undirected> {
+    let adjacency = adjacency_sets(graph);
+    let mut improved_graph = graph.clone();
+
+    for (first_vertex, second_vertex) in (0..adjacency.len()).tuple_combinations() {
+        if adjacency[first_vertex].contains(&second_vertex) {
+            continue;
+        }
+        let number_of_common_neighbours = adjacency[first_vertex]
+            .intersection(&adjacency[second_vertex])
+            .count();
+        if number_of_common_neighbours > low {
+            improved_graph.add_edge(
+                NodeIndex::new(first_vertex),
+                NodeIndex::new(second_vertex),
+                E::default(),
+            );
+        }
+    }
+
+    improved_graph
+}
+
+/// Returns the weight of the edge between the two vertices, which is either an edge of the graph
+/// or an edge added by a reduction rule.
+fn edge_weight<N, E: Clone>(
+    graph: &Graph<N, E, Undirected>,
+    added_edge_weights: &HashMap<(usize, usize), E>,
+    first_vertex: usize,
+    second_vertex: usize,
+) -> E {
+    match graph.find_edge(NodeIndex::new(first_vertex), NodeIndex::new(second_vertex)) {
+        Some(edge) => graph[edge].clone(),
+        None => added_edge_weights
+            .get(&(
+                first_vertex.min(second_vertex),
+                first_vertex.max(second_vertex),
+            ))
+            .expect("Edge should either be in the graph or have been added")
+            .clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    #[test]
+    fn test_peel_tree() {
+        let mut tree: Graph<(), (), Undirected> = Graph::new_undirected();
+        for _ in 0..8 {
+            tree.add_node(());
+        }
+        tree.extend_with_edges([(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (5, 6), (5, 7)]);
+
+        let (core, low) = peel(&tree);
+
+        assert_eq!(core.node_count(), 0);
+        assert_eq!(low, 1);
+    }
+
+    #[test]
+    fn test_reduce_twins_of_complete_bipartite_graph() {
+        let complete_bipartite_graph = petgraph::graph::UnGraph::<(), ()>::from_edges(
+            (0..3).flat_map(|i| (3..6).map(move |j| (i, j))),
+        );
+
+        let (reduced_graph, twins) = reduce_twins(&complete_bipartite_graph);
+
+        assert_eq!(reduced_graph.node_count(), 2);
+        assert_eq!(reduced_graph.edge_count(), 1);
+        assert_eq!(
+            twins[&NodeIndex::new(0)],
+            [0, 1, 2].map(NodeIndex::new).to_vec()
+        );
+        assert_eq!(
+            twins[&NodeIndex::new(1)],
+            [3, 4, 5].map(NodeIndex::new).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_reduce_twins_of_test_graph() {
+        // The vertices 1, 2 and 3 of test graph 2 are true twins, 0 and 4 are false twins
+        let test_graph = crate::tests::setup_test_graph(2);
+
+        let (reduced_graph, twins) = reduce_twins(&test_graph.graph);
+
+        assert_eq!(reduced_graph.node_count(), 2);
+        assert_eq!(reduced_graph.edge_count(), 1);
+        assert_eq!(
+            twins[&NodeIndex::new(0)],
+            [0, 4].map(NodeIndex::new).to_vec()
+        );
+        assert_eq!(
+            twins[&NodeIndex::new(1)],
+            [1, 2, 3].map(NodeIndex::new).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_reduce_dominated_pendant_vertex() {
+        // A triangle 1, 2, 3 where 1 has the pendant vertex 0
+        let graph =
+            petgraph::graph::UnGraph::<(), ()>::from_edges([(0, 1), (1, 2), (2, 3), (3, 1)]);
+
+        let (reduced_graph, dominators) = reduce_dominated_vertices(&graph);
+
+        // 0 is dominated by 1, then 2 and 3 are dominated by 1 as well (2 is also dominated by 3)
+        assert_eq!(reduced_graph.node_count(), 1);
+        assert_eq!(
+            dominators,
+            HashMap::from([
+                (NodeIndex::new(0), NodeIndex::new(1)),
+                (NodeIndex::new(2), NodeIndex::new(1)),
+                (NodeIndex::new(3), NodeIndex::new(1))
+            ])
+        );
+
+        // In a path, the two endpoints are dominated by their neighbours
+        let path = petgraph::graph::UnGraph::<(), ()>::from_edges([(0, 1), (1, 2), (2, 3)]);
+
+        let (reduced_path, dominators) = reduce_dominated_vertices(&path);
+
+        assert_eq!(reduced_path.node_count(), 2);
+        assert_eq!(reduced_path.edge_count(), 1);
+        assert_eq!(
+            dominators,
+            HashMap::from([
+                (NodeIndex::new(0), NodeIndex::new(1)),
+                (NodeIndex::new(3), NodeIndex::new(2))
+            ])
+        );
+    }
+
+    #[test]
+    fn test_quotient_by_modules_contracts_module() {
+        // The vertices 1, 2 and 3 are all adjacent to 0 and 4 and form a module, 5 is a pendant
+        // vertex of 0
+        let graph = petgraph::graph::UnGraph::<i32, i32>::from_edges([
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 4),
+            (2, 4),
+            (3, 4),
+            (0, 5),
+        ]);
+
+        let (quotient_graph, modules) = quotient_by_modules(&graph);
+
+        assert_eq!(
+            modules,
+            vec![vec![0], vec![1, 2, 3], vec![4], vec![5]]
+                .into_iter()
+                .map(|module| module.into_iter().map(NodeIndex::new).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(quotient_graph.node_count(), 4);
+        assert_eq!(quotient_graph.edge_count(), 3);
+        assert!(quotient_graph.contains_edge(NodeIndex::new(0), NodeIndex::new(1)));
+        assert!(quotient_graph.contains_edge(NodeIndex::new(1), NodeIndex::new(2)));
+        assert!(quotient_graph.contains_edge(NodeIndex::new(0), NodeIndex::new(3)));
+    }
+
+    #[test]
+    fn test_is_series_parallel() {
+        let complete_graph = petgraph::graph::UnGraph::<(), ()>::from_edges([
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+        ]);
+        // Three internally disjoint paths between 0 and 1
+        let theta_graph = petgraph::graph::UnGraph::<(), ()>::from_edges([
+            (0, 2),
+            (2, 1),
+            (0, 3),
+            (3, 4),
+            (4, 1),
+            (0, 5),
+            (5, 6),
+            (6, 7),
+            (7, 1),
+        ]);
+
+        assert!(!is_series_parallel(&complete_graph));
+        assert!(is_series_parallel(&theta_graph));
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            assert!(!is_series_parallel(&test_graph.graph), "Test graph: {}", i);
+        }
+    }
+
+    #[test]
+    fn test_peel_keeps_treewidth_of_test_graphs() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            let (core, low) = peel(&test_graph.graph);
+            let core_width = if core.node_count() == 0 {
+                0
+            } else {
+                crate::compute_treewidth_upper_bound_not_connected::<_, _, _, RandomState>(
+                    &core,
+                    crate::negative_intersection,
+                    crate::SpanningTreeConstructionMethod::FilWh,
+                    false,
+                    crate::CliqueSource::Maximal,
+                )
+            };
+
+            assert!(low <= test_graph.treewidth, "Test graph: {}", i);
+            assert_eq!(
+                core_width.max(low),
+                test_graph.treewidth,
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_via_two_core_of_triangle_with_pendant_path() {
+        let mut graph: Graph<(), (), Undirected> = Graph::new_undirected();
+        for _ in 0..20 {
+            graph.add_node(());
+        }
+        graph.extend_with_edges([(0, 1), (1, 2), (2, 0)]);
+        graph.extend_with_edges((2..19).map(|i| (i, i + 1)));
+
+        let core = two_core(&graph);
+        assert_eq!(core.node_count(), 3);
+        assert_eq!(core.edge_count(), 3);
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            assert_eq!(
+                treewidth_via_two_core::<_, _, _, RandomState>(
+                    &graph,
+                    crate::negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                ),
+                2
+            );
+        }
+    }
+
+    #[test]
+    fn test_improved_graph_of_complete_bipartite_graph() {
+        // The vertices 0 and 1 have the four common neighbours 2, 3, 4 and 5, the vertices of the
+        // other side only have two common neighbours
+        let complete_bipartite_graph = petgraph::graph::UnGraph::<(), ()>::from_edges(
+            (0..2).flat_map(|i| (2..6).map(move |j| (i, j))),
+        );
+
+        let improved = improved_graph(&complete_bipartite_graph, 2);
+
+        assert_eq!(improved.node_count(), complete_bipartite_graph.node_count());
+        assert_eq!(
+            improved.edge_count(),
+            complete_bipartite_graph.edge_count() + 1
+        );
+        for edge in complete_bipartite_graph.edge_indices() {
+            let (source, target) = complete_bipartite_graph
+                .edge_endpoints(edge)
+                .expect("Edge should be in the graph");
+            assert!(improved.contains_edge(source, target));
+        }
+        assert!(improved.contains_edge(NodeIndex::new(0), NodeIndex::new(1)));
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let treewidth = |graph: &Graph<(), (), Undirected>| {
+                compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    graph,
+                    crate::negative_intersection,
+                    computation_method,
+                    true,
+                    CliqueSource::Maximal,
+                    None,
+                )
+            };
+            // The treewidth of both graphs is 2, which the heuristic only finds on the improved
+            // graph, since the complete bipartite graph has no triangles
+            assert_eq!(treewidth(&improved), 2);
+            assert!(treewidth(&complete_bipartite_graph) >= 2);
+        }
+    }
+}
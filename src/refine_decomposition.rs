@@ -0,0 +1,625 @@
+use petgraph::{
+    graph::{EdgeIndex, NodeIndex},
+    visit::EdgeRef,
+    Graph, Undirected,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use crate::{
+    compute_treewidth_upper_bound::compute_tree_decomposition,
+    find_width_of_tree_decomposition::find_width_of_tree_decomposition, negative_intersection,
+    validate_tree_decomposition, CliqueOrder, CliqueSource, FillOptions,
+    SpanningTreeConstructionMethod,
+};
+
+/// Tries to shrink the bags of the given tree decomposition of graph with a cheap local search and
+/// returns the width of the decomposition afterwards.
+///
+/// In each of the (at most) given number of iterations, a vertex is moved out of the current widest
+/// bag, either by removing it (if an adjacent bag also contains it) or by relocating it to an
+/// adjacent bag that is small enough for the move to pay off. Every move is reverted unless the
+/// decomposition is still valid afterwards, that is, the edges of the vertex in the graph are still
+/// covered by some bag and the bags containing the vertex still induce a subtree (the connectivity
+/// property is re-checked after each move). Stops early if the widest bag can't be shrunk.
+///
+/// Since bags are only ever shrunk or grown to less than the current width, the width never increases.
+pub fn refine_decomposition<N, E, O: Default + Copy, S: BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    decomposition: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    iterations: usize,
+) -> usize {
+    for _ in 0..iterations {
+        let widest_bag = match decomposition
+            .node_indices()
+            .max_by_key(|v| decomposition[*v].len())
+        {
+            Some(widest_bag) => widest_bag,
+            None => break,
+        };
+
+        let mut vertices_in_widest_bag: Vec<NodeIndex> =
+            decomposition[widest_bag].iter().cloned().collect();
+        vertices_in_widest_bag.sort();
+
+        let mut moved_vertex = false;
+        for vertex in vertices_in_widest_bag {
+            if try_to_move_vertex_out_of_bag(graph, decomposition, widest_bag, vertex) {
+                moved_vertex = true;
+                break;
+            }
+        }
+
+        if !moved_vertex {
+            break;
+        }
+    }
+
+    find_width_of_tree_decomposition(decomposition)
+}
+
+/// Replaces every vertex in every bag of the decomposition by its image under the map, e.g. to
+/// translate a decomposition of a reduced graph back to the vertices of the original graph.
+/// Vertices that are not keys of the map are left untouched.
+///
+/// If the map is not injective on a bag, the bag shrinks accordingly.
+pub fn relabel_decomposition<E, S: Default + BuildHasher>(
+    decomposition: &mut Graph<HashSet<NodeIndex, S>, E, Undirected>,
+    map: &HashMap<NodeIndex, NodeIndex>,
+) {
+    for bag in decomposition.node_weights_mut() {
+        *bag = bag
+            .drain()
+            .map(|vertex| *map.get(&vertex).unwrap_or(&vertex))
+            .collect();
+    }
+}
+
+/// Repairs the connectivity property of the given tree decomposition: For each vertex whose bags
+/// don't induce a subtree, the vertex is added to all bags on the paths between them, like
+/// [fill_bags_along_paths][crate::fill_bags_along_paths::fill_bags_along_paths] does. The other
+/// vertices are left untouched.
+///
+/// The decomposition is assumed to be a tree. Afterwards it is a valid tree decomposition if every
+/// vertex and edge of the graph is covered by some bag.
+pub fn repair_connectivity<E: Copy + Default, S: BuildHasher>(
+    decomposition: &mut Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) {
+    let mut vertices: Vec<NodeIndex> = decomposition
+        .node_weights()
+        .flat_map(|bag| bag.iter().copied())
+        .collect();
+    vertices.sort();
+    vertices.dedup();
+
+    for vertex in vertices {
+        let bags_containing_vertex: Vec<NodeIndex> = decomposition
+            .node_indices()
+            .filter(|bag| decomposition[*bag].contains(&vertex))
+            .collect();
+        let Some((root, other_bags)) = bags_containing_vertex.split_first() else {
+            continue;
+        };
+
+        // Parents of the bags when rooting the tree at a bag containing the vertex
+        let mut parent: Vec<Option<NodeIndex>> = vec![None; decomposition.node_count()];
+        let mut bfs = petgraph::visit::Bfs::new(&*decomposition, *root);
+        while let Some(bag) = bfs.next(&*decomposition) {
+            for neighbour in decomposition.neighbors(bag) {
+                if neighbour != *root && parent[neighbour.index()].is_none() {
+                    parent[neighbour.index()] = Some(bag);
+                }
+            }
+        }
+
+        // The union of the paths to the root is the smallest subtree containing all bags with the
+        // vertex, so the paths are filled up until a bag already containing the vertex is reached
+        for bag in other_bags {
+            let mut current_bag = parent[bag.index()];
+            while let Some(bag_on_path) = current_bag {
+                if !decomposition[bag_on_path].insert(vertex) {
+                    break;
+                }
+                current_bag = parent[bag_on_path.index()];
+            }
+        }
+    }
+}
+
+/// A tree decomposition with bags as vertex weights
+type Decomposition<E, S> = Graph<HashSet<NodeIndex, S>, E, Undirected>;
+
+/// Splits the decomposition by removing the given tree edge and returns the two resulting subtrees
+/// together with the separator, that is, the (sorted) intersection of the bags of the endpoints of
+/// the edge. The first subtree is the one containing the first endpoint of the edge. The bags and
+/// edges of the subtrees keep their relative order.
+///
+/// If the decomposition is a tree decomposition of a graph, each subtree is a tree decomposition of
+/// the subgraph induced by the vertices in its bags and these subgraphs only share the separator.
+///
+/// **Panics**
+/// Panics if the edge is not an edge of the decomposition.
+pub fn split_decomposition_at<E: Clone, S: BuildHasher + Clone>(
+    decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+    edge: EdgeIndex,
+) -> (Decomposition<E, S>, Decomposition<E, S>, Vec<NodeIndex>) {
+    let (first_bag, second_bag) = decomposition
+        .edge_endpoints(edge)
+        .expect("Edge should be an edge of the decomposition");
+    let mut separator: Vec<NodeIndex> = decomposition[first_bag]
+        .iter()
+        .filter(|vertex| decomposition[second_bag].contains(vertex))
+        .copied()
+        .collect();
+    separator.sort();
+
+    // The bags reachable from the first bag without using the removed edge
+    let mut in_first_subtree = vec![false; decomposition.node_count()];
+    in_first_subtree[first_bag.index()] = true;
+    let mut stack = vec![first_bag];
+    while let Some(bag) = stack.pop() {
+        for tree_edge in decomposition.edges(bag) {
+            let neighbour = if tree_edge.source() == bag {
+                tree_edge.target()
+            } else {
+                tree_edge.source()
+            };
+            if tree_edge.id() != edge && !in_first_subtree[neighbour.index()] {
+                in_first_subtree[neighbour.index()] = true;
+                stack.push(neighbour);
+            }
+        }
+    }
+
+    let subtree = |first: bool| {
+        decomposition.filter_map(
+            |bag, weight| (in_first_subtree[bag.index()] == first).then(|| weight.clone()),
+            |tree_edge, weight| (tree_edge != edge).then(|| weight.clone()),
+        )
+    };
+
+    (subtree(true), subtree(false), separator)
+}
+
+/// Returns the width of a tree decomposition of the graph obtained by warm-starting from the
+/// previous tree decomposition, where graph is the graph after the removed edge was deleted.
+///
+/// The previous tree decomposition is still a tree decomposition of the graph, however bags might
+/// only contain both endpoints of the removed edge to cover it. Thus, for each bag containing both
+/// endpoints, it is tried to move one of them out of the bag (see [refine_decomposition]).
+///
+/// Falls back to computing a tree decomposition from scratch (using [negative_intersection] and
+/// [SpanningTreeConstructionMethod::FilWh]) if the previous tree decomposition is not valid for
+/// the graph or if no bag could be shrunk. In the latter case, the narrower of the previous and the
+/// recomputed tree decomposition is used.
+pub fn recompute_after_edge_removal<
+    N: Clone,
+    E: Clone,
+    O: Clone + Default,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    previous: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    removed: (NodeIndex, NodeIndex),
+) -> usize {
+    find_width_of_tree_decomposition(&decomposition_after_edge_removal(graph, previous, removed))
+}
+
+/// Computes the tree decomposition whose width is returned by [recompute_after_edge_removal].
+fn decomposition_after_edge_removal<
+    N: Clone,
+    E: Clone,
+    O: Clone + Default,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    previous: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    removed: (NodeIndex, NodeIndex),
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let mut decomposition = previous.clone();
+    let previous_is_valid = validate_tree_decomposition(graph, &decomposition).is_ok();
+    let mut shrunk_bag = false;
+
+    if previous_is_valid {
+        let (vertex_one, vertex_two) = removed;
+        let bags_covering_removed_edge: Vec<NodeIndex> = decomposition
+            .node_indices()
+            .filter(|bag| {
+                decomposition[*bag].contains(&vertex_one)
+                    && decomposition[*bag].contains(&vertex_two)
+            })
+            .collect();
+
+        for bag in bags_covering_removed_edge {
+            for vertex in [vertex_one, vertex_two] {
+                if try_to_move_vertex_out_of_bag(graph, &mut decomposition, bag, vertex) {
+                    shrunk_bag = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    if shrunk_bag {
+        decomposition
+    } else {
+        let (recomputed, _, _) = compute_tree_decomposition(
+            graph,
+            negative_intersection::<S>,
+            SpanningTreeConstructionMethod::FilWh,
+            CliqueSource::Maximal,
+            None,
+            CliqueOrder::AsGiven,
+            &mut FillOptions::default(),
+        );
+        if previous_is_valid
+            && find_width_of_tree_decomposition(&decomposition)
+                <= find_width_of_tree_decomposition(&recomputed)
+        {
+            decomposition
+        } else {
+            recomputed.map(|_, bag| bag.clone(), |_, _| O::default())
+        }
+    }
+}
+
+/// Tries to remove the vertex from the bag or relocate it to an adjacent bag. Returns true if the
+/// decomposition is still valid afterwards, otherwise the move is reverted and false is returned.
+fn try_to_move_vertex_out_of_bag<N, E, O, S: BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    decomposition: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    bag: NodeIndex,
+    vertex: NodeIndex,
+) -> bool {
+    let bag_size = decomposition[bag].len();
+    let neighbouring_bags: Vec<NodeIndex> = decomposition.neighbors(bag).collect();
+    let neighbouring_bags_with_vertex = neighbouring_bags
+        .iter()
+        .filter(|b| decomposition[**b].contains(&vertex))
+        .count();
+
+    let target_bag = match neighbouring_bags_with_vertex {
+        // The vertex is only kept in this part of the tree by this bag and could be relocated
+        0 => {
+            match neighbouring_bags
+                .iter()
+                .filter(|b| decomposition[**b].len() + 1 < bag_size)
+                .min_by_key(|b| decomposition[**b].len())
+            {
+                Some(target_bag) => Some(*target_bag),
+                None => return false,
+            }
+        }
+        // The bag is a leaf of the subtree of bags containing the vertex and it could be removed
+        1 => None,
+        // The bag is an inner vertex of the subtree and removing the vertex would disconnect it
+        _ => return false,
+    };
+
+    decomposition[bag].remove(&vertex);
+    if let Some(target_bag) = target_bag {
+        decomposition[target_bag].insert(vertex);
+    }
+
+    if vertex_is_covered_correctly(graph, decomposition, vertex) {
+        true
+    } else {
+        // Revert the move
+        decomposition[bag].insert(vertex);
+        if let Some(target_bag) = target_bag {
+            decomposition[target_bag].remove(&vertex);
+        }
+        false
+    }
+}
+
+/// Checks whether the bags containing the vertex are non-empty and induce a connected subtree and
+/// whether all edges incident to the vertex are contained in some bag.
+fn vertex_is_covered_correctly<N, E, O, S: BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    vertex: NodeIndex,
+) -> bool {
+    let bags_with_vertex: Vec<NodeIndex> = decomposition
+        .node_indices()
+        .filter(|b| decomposition[*b].contains(&vertex))
+        .collect();
+
+    let first_bag = match bags_with_vertex.first() {
+        Some(first_bag) => *first_bag,
+        None => return false,
+    };
+
+    // Check connectivity property by searching the subtree induced by bags containing the vertex
+    let mut seen: HashSet<NodeIndex> = HashSet::new();
+    seen.insert(first_bag);
+    let mut stack = vec![first_bag];
+    while let Some(current_bag) = stack.pop() {
+        for next_bag in decomposition.neighbors(current_bag) {
+            if decomposition[next_bag].contains(&vertex) && seen.insert(next_bag) {
+                stack.push(next_bag);
+            }
+        }
+    }
+    if seen.len() != bags_with_vertex.len() {
+        return false;
+    }
+
+    // Check that all edges incident to the vertex are still covered
+    graph.neighbors(vertex).all(|neighbour| {
+        bags_with_vertex
+            .iter()
+            .any(|b| decomposition[*b].contains(&neighbour))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    #[test]
+    fn test_split_path_decomposition_in_the_middle() {
+        // The path 0 - 1 - ... - 5 and its path decomposition with bags {i, i + 1}
+        let path = petgraph::graph::UnGraph::<i32, i32>::from_edges((0..5).map(|i| (i, i + 1)));
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        for i in 0..5 {
+            decomposition.add_node(HashSet::from_iter([
+                NodeIndex::new(i),
+                NodeIndex::new(i + 1),
+            ]));
+        }
+        let mut middle_edge = None;
+        for i in 0..4 {
+            let tree_edge = decomposition.add_edge(NodeIndex::new(i), NodeIndex::new(i + 1), 0);
+            if i == 1 {
+                middle_edge = Some(tree_edge);
+            }
+        }
+
+        let (first_half, second_half, separator) = split_decomposition_at(
+            &decomposition,
+            middle_edge.expect("Middle edge should have been added"),
+        );
+
+        assert_eq!(separator, vec![NodeIndex::new(2)]);
+        assert_eq!(first_half.node_count(), 2);
+        assert_eq!(second_half.node_count(), 3);
+        for (mut half, vertices) in [(first_half, 0..3), (second_half, 2..6)] {
+            // The induced subgraph gets new NodeIndices starting at 0
+            let induced_subgraph = path.filter_map(
+                |vertex, weight| vertices.contains(&vertex.index()).then_some(*weight),
+                |_, weight| Some(*weight),
+            );
+            let map: HashMap<NodeIndex, NodeIndex> = vertices
+                .clone()
+                .map(|vertex| {
+                    (
+                        NodeIndex::new(vertex),
+                        NodeIndex::new(vertex - vertices.start),
+                    )
+                })
+                .collect();
+            relabel_decomposition(&mut half, &map);
+
+            assert_eq!(
+                validate_tree_decomposition(&induced_subgraph, &half),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_relabel_decomposition() {
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let first_bag = decomposition.add_node(HashSet::from_iter([0, 1].map(NodeIndex::new)));
+        let second_bag = decomposition.add_node(HashSet::from_iter([1, 2].map(NodeIndex::new)));
+        decomposition.add_edge(first_bag, second_bag, 0);
+
+        let map: HashMap<NodeIndex, NodeIndex> = [(0, 5), (1, 7)]
+            .into_iter()
+            .map(|(from, to)| (NodeIndex::new(from), NodeIndex::new(to)))
+            .collect();
+        relabel_decomposition(&mut decomposition, &map);
+
+        assert_eq!(
+            decomposition[first_bag],
+            HashSet::from_iter([5, 7].map(NodeIndex::new))
+        );
+        // Vertex 2 is not mapped and thus untouched
+        assert_eq!(
+            decomposition[second_bag],
+            HashSet::from_iter([7, 2].map(NodeIndex::new))
+        );
+        assert_eq!(decomposition.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_repair_connectivity_of_broken_decomposition() {
+        let graph =
+            petgraph::graph::UnGraph::<i32, ()>::from_edges([(0, 1), (1, 2), (2, 0), (2, 3)]);
+
+        // Vertex 0 is contained in the first and the third bag but not in the second one
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let bags: Vec<NodeIndex> = [vec![0, 1], vec![1, 2], vec![0, 2], vec![2, 3]]
+            .into_iter()
+            .map(|bag| decomposition.add_node(bag.into_iter().map(NodeIndex::new).collect()))
+            .collect();
+        decomposition.add_edge(bags[0], bags[1], 0);
+        decomposition.add_edge(bags[1], bags[2], 0);
+        decomposition.add_edge(bags[1], bags[3], 0);
+        assert!(!crate::check_tree_decomposition(
+            &graph,
+            &decomposition,
+            &None,
+            &None
+        ));
+
+        repair_connectivity(&mut decomposition);
+
+        assert!(crate::check_tree_decomposition(
+            &graph,
+            &decomposition,
+            &None,
+            &None
+        ));
+        assert_eq!(
+            decomposition[bags[1]],
+            HashSet::from_iter([0, 1, 2].map(NodeIndex::new))
+        );
+        assert_eq!(
+            decomposition[bags[3]],
+            HashSet::from_iter([2, 3].map(NodeIndex::new))
+        );
+    }
+
+    #[test]
+    fn test_refine_decomposition_shrinks_redundant_vertex() {
+        let graph = petgraph::graph::UnGraph::<i32, ()>::from_edges([(0, 1), (1, 2)]);
+
+        let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let first_bag = decomposition.add_node(
+            [0, 1, 2]
+                .into_iter()
+                .map(NodeIndex::new)
+                .collect::<HashSet<_, RandomState>>(),
+        );
+        let second_bag = decomposition.add_node(
+            [1, 2]
+                .into_iter()
+                .map(NodeIndex::new)
+                .collect::<HashSet<_, RandomState>>(),
+        );
+        decomposition.add_edge(first_bag, second_bag, 0);
+
+        let width = refine_decomposition(&graph, &mut decomposition, 10);
+
+        assert_eq!(width, 1);
+        assert!(crate::check_tree_decomposition(
+            &graph,
+            &decomposition,
+            &None,
+            &None
+        ));
+    }
+
+    #[test]
+    fn test_refine_decomposition_does_not_increase_width() {
+        // Test graph 0 is not connected and thus has no tree decomposition based on its clique graph
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            let cliques: Vec<Vec<_>> =
+                crate::find_maximal_cliques::find_maximal_cliques::<Vec<_>, _, RandomState>(
+                    &test_graph.graph,
+                )
+                .collect();
+            let clique_graph = crate::construct_clique_graph::construct_clique_graph(
+                cliques,
+                crate::negative_intersection,
+            );
+            let mut decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+                petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                    &clique_graph,
+                ));
+            crate::fill_bags_along_paths::fill_bags_along_paths(&mut decomposition);
+            let width_before = find_width_of_tree_decomposition(&decomposition);
+
+            let width_after = refine_decomposition(&test_graph.graph, &mut decomposition, 20);
+
+            assert!(width_after <= width_before, "Test graph: {}", i);
+            assert!(
+                crate::check_tree_decomposition(&test_graph.graph, &decomposition, &None, &None),
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_recompute_after_chord_removal() {
+        // The graphs are the cycle 0 - 1 - 2 - 3 - 0 after removing the chord (0, 2) (no bag can
+        // be shrunk) and the triangles 0 - 1 - 2 and 1 - 2 - 3 after removing (1, 3) (vertex 1 can
+        // be removed from the second bag)
+        for (edges, bags, removed) in [
+            (
+                vec![(0, 1), (1, 2), (2, 3), (3, 0)],
+                vec![vec![0, 1, 2], vec![0, 2, 3]],
+                (0, 2),
+            ),
+            (
+                vec![(0, 1), (1, 2), (2, 0), (2, 3)],
+                vec![vec![0, 1, 2], vec![1, 2, 3]],
+                (1, 3),
+            ),
+        ] {
+            let graph = petgraph::graph::UnGraph::<i32, i32>::from_edges(edges);
+            let mut previous: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+                Graph::new_undirected();
+            let bags: Vec<NodeIndex> = bags
+                .into_iter()
+                .map(|bag| previous.add_node(bag.into_iter().map(NodeIndex::new).collect()))
+                .collect();
+            previous.add_edge(bags[0], bags[1], 0);
+            let removed = (NodeIndex::new(removed.0), NodeIndex::new(removed.1));
+
+            let decomposition = decomposition_after_edge_removal(&graph, &previous, removed);
+            let from_scratch = crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                &graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FilWh,
+                true,
+                CliqueSource::Maximal,
+                None,
+            );
+
+            assert_eq!(validate_tree_decomposition(&graph, &decomposition), Ok(()));
+            assert!(find_width_of_tree_decomposition(&decomposition) <= from_scratch);
+            assert_eq!(
+                recompute_after_edge_removal(&graph, &previous, removed),
+                find_width_of_tree_decomposition(&decomposition)
+            );
+        }
+    }
+
+    #[test]
+    fn test_recompute_after_edge_removal_keeps_narrower_previous_decomposition() {
+        type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+        // K_{2,4} with the parts {0, 1} and {2, 3, 4, 5} after removing the edge (0, 1). The path of
+        // the bags {0, 1, v} is optimal and none of its bags can be shrunk
+        let graph = petgraph::graph::UnGraph::<i32, i32>::from_edges(
+            [0, 1].into_iter().flat_map(|u| (2..6).map(move |v| (u, v))),
+        );
+        let mut previous: Graph<HashSet<NodeIndex, FxBuildHasher>, i32, Undirected> =
+            Graph::new_undirected();
+        let bags: Vec<NodeIndex> = (2..6)
+            .map(|v| previous.add_node([0, 1, v].into_iter().map(NodeIndex::new).collect()))
+            .collect();
+        for (first_bag, second_bag) in bags.iter().zip(bags.iter().skip(1)) {
+            previous.add_edge(*first_bag, *second_bag, 0);
+        }
+        let removed = (NodeIndex::new(0), NodeIndex::new(1));
+
+        let from_scratch = crate::compute_treewidth_upper_bound::<_, _, _, FxBuildHasher>(
+            &graph,
+            negative_intersection,
+            SpanningTreeConstructionMethod::FilWh,
+            true,
+            CliqueSource::Maximal,
+            None,
+        );
+        assert!(from_scratch > 2);
+
+        let decomposition = decomposition_after_edge_removal(&graph, &previous, removed);
+        assert_eq!(validate_tree_decomposition(&graph, &decomposition), Ok(()));
+        assert_eq!(find_width_of_tree_decomposition(&decomposition), 2);
+    }
+}
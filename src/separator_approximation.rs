@@ -0,0 +1,235 @@
+use std::{collections::HashSet, hash::BuildHasher};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    find_width_of_tree_decomposition::find_width_of_tree_decomposition,
+    min_fill_heuristic::{adjacency_sets, eliminate_vertex},
+    min_fill_ordering_lazy,
+};
+
+/// Computes an upper bound for the treewidth by recursively splitting the graph with balanced
+/// separators. This is a heuristic, it follows the recursion of the separator based approximation
+/// algorithms but doesn't search for small separators, so it comes with no approximation guarantee.
+///
+/// For a component W (initially each connected component of the graph) with boundary B (the
+/// vertices of previous separators adjacent to W) a balanced separator S of W is found, that is, a
+/// set of vertices such that each component of W - S has at most balance * |W| vertices. The bag
+/// B ∪ S is added to the tree decomposition and each component of W - S is handled recursively
+/// with the vertices of B ∪ S adjacent to it as its boundary. The returned upper bound is the size
+/// of the biggest bag minus one.
+///
+/// Separators are chosen among the bags of a min fill elimination of W (one of them is always a
+/// 1/2-balanced separator of W). If no bag is balance-balanced, the bag minimizing the biggest
+/// remaining component is used. Thus balance should be in [0.5, 1). Since the separators are never
+/// empty, the recursion always terminates.
+///
+/// This is not competitive with the clique graph heuristic on easy instances, however it doesn't
+/// construct the clique graph and can thus be used on instances where the clique graph is huge.
+pub fn treewidth_approx_separator<N: Clone, E: Clone, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    balance: f64,
+) -> usize {
+    let tree_decomposition = separator_tree_decomposition::<N, E, S>(graph, balance);
+    find_width_of_tree_decomposition(&tree_decomposition)
+}
+
+/// Constructs the tree decomposition described in [treewidth_approx_separator]. If the graph is not
+/// connected, the decompositions of the components are joined by edges between their first bags.
+pub(crate) fn separator_tree_decomposition<N: Clone, E: Clone, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    balance: f64,
+) -> Graph<HashSet<NodeIndex, S>, i32, Undirected> {
+    let mut tree_decomposition: Graph<HashSet<NodeIndex, S>, i32, Undirected> =
+        Graph::new_undirected();
+    let all_vertices: HashSet<NodeIndex> = graph.node_indices().collect();
+
+    // Each entry consists of the component to be decomposed, its boundary and the bag the
+    // decomposition of the component should be attached to
+    let mut stack: Vec<(HashSet<NodeIndex>, HashSet<NodeIndex>, Option<NodeIndex>)> =
+        components_of_subgraph(graph, &all_vertices)
+            .into_iter()
+            .map(|component| (component, HashSet::new(), None))
+            .collect();
+    let mut first_bag: Option<NodeIndex> = None;
+
+    while let Some((component, boundary, parent_bag)) = stack.pop() {
+        let separator = find_balanced_separator(graph, &component, balance);
+
+        let mut bag: HashSet<NodeIndex, S> = boundary.iter().copied().collect();
+        bag.extend(separator.iter().copied());
+        let bag_index = tree_decomposition.add_node(bag.clone());
+
+        match parent_bag.or(first_bag) {
+            Some(parent_bag) => {
+                tree_decomposition.add_edge(parent_bag, bag_index, 0);
+            }
+            None => first_bag = Some(bag_index),
+        }
+
+        let remaining_vertices: HashSet<NodeIndex> =
+            component.difference(&separator).copied().collect();
+        for remaining_component in components_of_subgraph(graph, &remaining_vertices) {
+            let remaining_component_boundary: HashSet<NodeIndex> = bag
+                .iter()
+                .filter(|v| {
+                    graph
+                        .neighbors(**v)
+                        .any(|w| remaining_component.contains(&w))
+                })
+                .copied()
+                .collect();
+            stack.push((
+                remaining_component,
+                remaining_component_boundary,
+                Some(bag_index),
+            ));
+        }
+    }
+
+    tree_decomposition
+}
+
+/// Returns a separator of the subgraph induced by the component such that each component of the
+/// remaining vertices has at most balance * |component| vertices. The candidates are the bags of
+/// the tree decomposition given by a min fill elimination ordering of the subgraph. The smallest
+/// candidate satisfying the balance is returned, otherwise the candidate minimizing the biggest
+/// remaining component.
+///
+/// The returned separator is non-empty if the component is non-empty.
+pub(crate) fn find_balanced_separator<N: Clone, E: Clone>(
+    graph: &Graph<N, E, Undirected>,
+    component: &HashSet<NodeIndex>,
+    balance: f64,
+) -> HashSet<NodeIndex> {
+    // The vertices of the induced subgraph are labeled with their NodeIndex in the original graph
+    let subgraph: Graph<NodeIndex, (), Undirected> =
+        graph.filter_map(|v, _| component.contains(&v).then_some(v), |_, _| Some(()));
+    let ordering = min_fill_ordering_lazy(&subgraph);
+
+    let mut adjacency = adjacency_sets(&subgraph);
+    let mut candidates: Vec<HashSet<NodeIndex>> = Vec::with_capacity(ordering.len());
+    for vertex in ordering {
+        let mut candidate: HashSet<NodeIndex> = adjacency[vertex.index()]
+            .iter()
+            .map(|v| subgraph[NodeIndex::new(*v)])
+            .collect();
+        candidate.insert(subgraph[vertex]);
+        candidates.push(candidate);
+        eliminate_vertex(&mut adjacency, vertex.index());
+    }
+
+    let maximum_component_size = (balance * component.len() as f64).floor() as usize;
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let remaining_vertices: HashSet<NodeIndex> =
+                component.difference(&candidate).copied().collect();
+            let biggest_remaining_component = components_of_subgraph(graph, &remaining_vertices)
+                .iter()
+                .map(|c| c.len())
+                .max()
+                .unwrap_or(0);
+            (candidate, biggest_remaining_component)
+        })
+        .min_by_key(|(candidate, biggest_remaining_component)| {
+            if *biggest_remaining_component <= maximum_component_size {
+                (0, candidate.len(), *biggest_remaining_component)
+            } else {
+                (1, *biggest_remaining_component, candidate.len())
+            }
+        })
+        .map(|(candidate, _)| candidate)
+        .unwrap_or_default()
+}
+
+/// Returns the connected components of the subgraph induced by the given vertices.
+pub(crate) fn components_of_subgraph<N, E>(
+    graph: &Graph<N, E, Undirected>,
+    vertices: &HashSet<NodeIndex>,
+) -> Vec<HashSet<NodeIndex>> {
+    let mut seen: HashSet<NodeIndex> = HashSet::new();
+    let mut components = Vec::new();
+
+    let mut sorted_vertices: Vec<_> = vertices.iter().copied().collect();
+    sorted_vertices.sort();
+    for start_vertex in sorted_vertices {
+        if !seen.insert(start_vertex) {
+            continue;
+        }
+        let mut component = HashSet::new();
+        component.insert(start_vertex);
+        let mut stack = vec![start_vertex];
+        while let Some(vertex) = stack.pop() {
+            for neighbour in graph.neighbors(vertex) {
+                if vertices.contains(&neighbour) && seen.insert(neighbour) {
+                    component.insert(neighbour);
+                    stack.push(neighbour);
+                }
+            }
+        }
+        components.push(component);
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    #[test]
+    fn test_separator_decomposition_is_valid_on_test_graphs() {
+        // Test graph 0 is not connected and thus its decomposition can't be checked
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            let tree_decomposition =
+                separator_tree_decomposition::<_, _, RandomState>(&test_graph.graph, 0.5);
+
+            assert!(
+                crate::check_tree_decomposition(
+                    &test_graph.graph,
+                    &tree_decomposition,
+                    &None,
+                    &None
+                ),
+                "Test graph: {}",
+                i
+            );
+            assert!(
+                find_width_of_tree_decomposition(&tree_decomposition) >= test_graph.treewidth,
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_approx_separator_on_k_tree() {
+        for (k, n) in [(2, 50), (3, 60), (5, 80), (8, 100)] {
+            let k_tree = crate::generate_k_tree(k, n).expect("k should be smaller or eq to n");
+
+            let tree_decomposition =
+                separator_tree_decomposition::<_, _, RandomState>(&k_tree, 2.0 / 3.0);
+            assert!(crate::check_tree_decomposition(
+                &k_tree,
+                &tree_decomposition,
+                &None,
+                &None
+            ));
+
+            let upper_bound = treewidth_approx_separator::<_, _, RandomState>(&k_tree, 2.0 / 3.0);
+            let logarithmic_bound = (k + 1) * ((n as f64).log2().ceil() as usize + 1);
+            assert!(
+                k <= upper_bound && upper_bound <= logarithmic_bound,
+                "k: {}, n: {}, upper bound: {}",
+                k,
+                n,
+                upper_bound
+            );
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::separator_approximation::components_of_subgraph;
+
+/// Computes an upper bound for the treedepth of the graph, that is, the height of an elimination
+/// forest of the graph.
+///
+/// The elimination forest is built by recursively removing a single vertex from each connected
+/// component and continuing with the components of the remaining vertices. The removed vertex is
+/// the one minimizing the size of the biggest remaining component (ties are broken by the smallest
+/// NodeIndex). The height of the elimination forest is the maximum number of vertices removed on a
+/// path from a root to a leaf.
+///
+/// Since treewidth ≤ treedepth - 1, this also gives a (weak) upper bound for the treewidth. Note
+/// that choosing the vertex takes quadratic time in the size of the component.
+pub fn treedepth_upper_bound<N, E>(graph: &Graph<N, E, Undirected>) -> usize {
+    let all_vertices: HashSet<NodeIndex> = graph.node_indices().collect();
+    let mut height = 0;
+
+    // Each entry consists of a component and the depth of its parent in the elimination forest
+    let mut stack: Vec<(HashSet<NodeIndex>, usize)> = components_of_subgraph(graph, &all_vertices)
+        .into_iter()
+        .map(|component| (component, 0))
+        .collect();
+
+    while let Some((component, parent_depth)) = stack.pop() {
+        let depth = parent_depth + 1;
+        height = height.max(depth);
+
+        let mut sorted_vertices: Vec<_> = component.iter().copied().collect();
+        sorted_vertices.sort();
+        let remaining_components = sorted_vertices
+            .into_iter()
+            .map(|vertex| {
+                let mut remaining_vertices = component.clone();
+                remaining_vertices.remove(&vertex);
+                components_of_subgraph(graph, &remaining_vertices)
+            })
+            .min_by_key(|remaining_components| {
+                remaining_components
+                    .iter()
+                    .map(|c| c.len())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .expect("Components should not be empty");
+
+        stack.extend(
+            remaining_components
+                .into_iter()
+                .map(|remaining_component| (remaining_component, depth)),
+        );
+    }
+
+    height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_treedepth_upper_bound_on_path() {
+        for n in 1..=40 {
+            let mut path: Graph<(), (), Undirected> = Graph::new_undirected();
+            let vertices: Vec<_> = (0..n).map(|_| path.add_node(())).collect();
+            for window in vertices.windows(2) {
+                path.add_edge(window[0], window[1], ());
+            }
+
+            assert_eq!(
+                treedepth_upper_bound(&path),
+                ((n + 1) as f64).log2().ceil() as usize,
+                "n: {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_treedepth_upper_bound_bounds_treewidth() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            assert!(
+                test_graph.treewidth < treedepth_upper_bound(&test_graph.graph),
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+}
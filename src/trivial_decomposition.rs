@@ -0,0 +1,88 @@
+use petgraph::{graph::NodeIndex, visit::Bfs, Graph, Undirected};
+use std::{collections::HashSet, hash::BuildHasher};
+
+use crate::fill_bags_along_paths::fill_bags_along_paths;
+
+/// Constructs a trivial tree decomposition of the given graph from a BFS spanning tree, which
+/// serves as a quick baseline to compare the clique graph heuristic against.
+///
+/// The tree decomposition has the shape of the spanning tree. The bag of each vertex contains the
+/// vertex and its parent in the spanning tree. For each edge not in the spanning tree one
+/// endpoint is added to the bag of the other endpoint. Afterwards the bags are filled up along
+/// the paths in the tree (see [fill_bags_along_paths]). If the graph is not connected, the roots
+/// of the spanning trees of the components are joined to the first root.
+///
+/// The width is usually far from optimal, for example a path has width 1 while a cycle might
+/// already end up with a bag containing half of its vertices.
+pub fn trivial_decomposition_from_spanning_tree<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+) -> Graph<HashSet<NodeIndex, S>, (), Undirected> {
+    // The bag of each vertex has the same index as the vertex
+    let mut decomposition: Graph<HashSet<NodeIndex, S>, (), Undirected> = graph.map(
+        |v, _| {
+            let mut bag: HashSet<NodeIndex, S> = Default::default();
+            bag.insert(v);
+            bag
+        },
+        |_, _| (),
+    );
+    decomposition.clear_edges();
+
+    let mut visited = vec![false; graph.node_count()];
+    let mut first_root: Option<NodeIndex> = None;
+    for root in graph.node_indices() {
+        if visited[root.index()] {
+            continue;
+        }
+        match first_root {
+            Some(first_root) => {
+                decomposition.add_edge(first_root, root, ());
+            }
+            None => first_root = Some(root),
+        }
+
+        visited[root.index()] = true;
+        let mut bfs = Bfs::new(graph, root);
+        while let Some(vertex) = bfs.next(graph) {
+            for neighbour in graph.neighbors(vertex) {
+                if !visited[neighbour.index()] {
+                    // Tree edge found by the bfs
+                    visited[neighbour.index()] = true;
+                    decomposition[neighbour].insert(vertex);
+                    decomposition.add_edge(vertex, neighbour, ());
+                } else if !decomposition[neighbour].contains(&vertex) {
+                    // Cover the edge by the bag of the vertex
+                    decomposition[vertex].insert(neighbour);
+                }
+            }
+        }
+    }
+
+    fill_bags_along_paths(&mut decomposition);
+
+    decomposition
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    #[test]
+    fn test_trivial_decomposition_from_spanning_tree_is_valid() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+
+            let decomposition =
+                trivial_decomposition_from_spanning_tree::<_, _, RandomState>(&test_graph.graph);
+
+            assert_eq!(decomposition.node_count(), test_graph.graph.node_count());
+            assert!(
+                crate::check_tree_decomposition(&test_graph.graph, &decomposition, &None, &None),
+                "Test graph: {}",
+                i
+            );
+        }
+    }
+}